@@ -7,6 +7,11 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Index credential and schema events of finalized blocks into a local database, queried
+	/// through the `indexer_*` RPC methods.
+	#[arg(long)]
+	pub enable_indexer: bool,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -206,8 +206,9 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let enable_indexer = cli.enable_indexer;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, enable_indexer).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
@@ -0,0 +1,822 @@
+//! Optional index of credential and schema history, enabled with `--enable-indexer`.
+//!
+//! The indexer follows finalized blocks, decodes the events of the credentials and schemas
+//! pallets and keeps them in a local RocksDB database next to the chain database, so questions
+//! the runtime can no longer answer, such as every credential an account ever issued including
+//! deleted ones, are served by the `indexer_*` RPC methods. Blocks whose events don't decode with
+//! the event types of this runtime, such as blocks from before an incompatible runtime upgrade,
+//! are skipped.
+
+use std::{collections::HashMap, io, path::Path, sync::Arc};
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use kvdb::{DBTransaction, KeyValueDB};
+use kvdb_rocksdb::{Database, DatabaseConfig};
+use node_primitives::{AccountId, BlockNumber, Hash, Moment};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hashing::{blake2_256, twox_128},
+	storage::StorageKey,
+};
+use sp_runtime::traits::Header as _;
+use zeno_runtime::RuntimeEvent;
+
+use crate::service::FullClient;
+
+/// Last block whose events were indexed.
+const COL_META: u32 = 0;
+/// Credential events keyed by credential id and position.
+const COL_CREDENTIALS: u32 = 1;
+/// Credential events keyed by issuer account and position.
+const COL_CREDENTIALS_BY_ISSUER: u32 = 2;
+/// Issuer account of each credential ever created.
+const COL_CREDENTIAL_ISSUERS: u32 = 3;
+/// Schema events keyed by schema id and position.
+const COL_SCHEMAS: u32 = 4;
+/// Schema events keyed by creator account and position.
+const COL_SCHEMAS_BY_CREATOR: u32 = 5;
+/// Creator account of each schema ever created.
+const COL_SCHEMA_CREATORS: u32 = 6;
+/// Delegation events keyed by the hash of the issuer DID and position.
+const COL_DELEGATIONS_BY_ISSUER: u32 = 7;
+/// Delegation events keyed by delegate account and position.
+const COL_DELEGATIONS_BY_DELEGATE: u32 = 8;
+const NUM_COLUMNS: u32 = 9;
+
+const LAST_BLOCK_KEY: &[u8] = b"last_block";
+const INDEX_VERSION_KEY: &[u8] = b"index_version";
+/// Version of what the indexer records, blocks indexed by an earlier version are indexed again.
+/// Version 1 added credential offers, batches and delegations.
+const INDEX_VERSION: u32 = 1;
+
+/// What happened to a credential.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CredentialAction {
+	Created {
+		holder_did: Vec<u8>,
+		schema_id: u32,
+		schema_version: u32,
+		content_hash: Hash,
+		moment: Moment,
+	},
+	Updated {
+		holder_did: Vec<u8>,
+		schema_id: u32,
+		schema_version: u32,
+		content_hash: Hash,
+		moment: Moment,
+	},
+	Deleted,
+	Revoked { reason: u16 },
+	Suspended { reason: u16 },
+	Reinstated { reason: u16 },
+	/// Offered to its holder by the account the event is filed under, which may be a delegate of
+	/// the issuer.
+	Offered { holder_did: Vec<u8>, content_hash: Hash, expires_at: BlockNumber },
+	OfferRejected,
+	OfferExpired,
+	/// Offered as one of the `count` credentials of the batch signed under the Merkle `root`.
+	Batched { root: Hash, count: u32 },
+}
+
+/// What happened to a schema.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SchemaAction {
+	Created { creator_did: Vec<u8>, content_hash: Hash, moment: Moment },
+	Updated { version: u32, creator_did: Vec<u8>, content_hash: Hash, moment: Moment },
+	Deleted,
+	VersionDeprecated { version: u32 },
+	Deprecated,
+	IssuerAdded { issuer_did: Vec<u8> },
+	IssuerRemoved { issuer_did: Vec<u8> },
+}
+
+/// A credential or schema event as recorded by the indexer.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedEvent<Action> {
+	/// Id of the credential or schema the event is about.
+	pub id: u32,
+	/// Issuer of the credential or creator of the schema, if the indexer saw it being created.
+	pub account: Option<AccountId>,
+	pub action: Action,
+	pub block_number: BlockNumber,
+	pub block_hash: Hash,
+	/// Index of the event within the events of its block.
+	pub event_index: u32,
+}
+
+pub type CredentialEvent = IndexedEvent<CredentialAction>;
+pub type SchemaEvent = IndexedEvent<SchemaAction>;
+
+/// What happened to the right of a delegate to issue on behalf of an issuer DID.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DelegationAction {
+	Added,
+	Removed,
+}
+
+/// A delegation event as recorded by the indexer.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationEvent {
+	pub issuer_did: Vec<u8>,
+	pub delegate: AccountId,
+	pub action: DelegationAction,
+	pub block_number: BlockNumber,
+	pub block_hash: Hash,
+	/// Index of the event within the events of its block.
+	pub event_index: u32,
+}
+
+/// The columns holding the events of one pallet.
+struct Columns {
+	events: u32,
+	by_account: u32,
+	accounts: u32,
+}
+
+const CREDENTIAL_COLUMNS: Columns = Columns {
+	events: COL_CREDENTIALS,
+	by_account: COL_CREDENTIALS_BY_ISSUER,
+	accounts: COL_CREDENTIAL_ISSUERS,
+};
+const SCHEMA_COLUMNS: Columns = Columns {
+	events: COL_SCHEMAS,
+	by_account: COL_SCHEMAS_BY_CREATOR,
+	accounts: COL_SCHEMA_CREATORS,
+};
+
+/// Local database of indexed credential and schema events.
+pub struct Indexer {
+	db: Arc<dyn KeyValueDB>,
+}
+
+impl Indexer {
+	/// Open the indexer database at `path`, creating it if it doesn't exist.
+	pub fn open(path: &Path) -> io::Result<Self> {
+		let config = DatabaseConfig::with_columns(NUM_COLUMNS);
+		let indexer = Self::new(Arc::new(Database::open(&config, path)?));
+		indexer.upgrade()?;
+		Ok(indexer)
+	}
+
+	fn new(db: Arc<dyn KeyValueDB>) -> Self {
+		Self { db }
+	}
+
+	// Index every block again if it was indexed by an earlier version. Events keep their keys,
+	// so indexing a block again rewrites them rather than recording them twice.
+	fn upgrade(&self) -> io::Result<()> {
+		let version = self
+			.db
+			.get(COL_META, INDEX_VERSION_KEY)?
+			.map(|value| decode::<u32>(&value))
+			.transpose()?;
+		if version.map_or(true, |version| version < INDEX_VERSION) {
+			let mut transaction = self.db.transaction();
+			transaction.delete(COL_META, LAST_BLOCK_KEY);
+			transaction.put(COL_META, INDEX_VERSION_KEY, &INDEX_VERSION.encode());
+			self.db.write(transaction)?;
+		}
+		Ok(())
+	}
+
+	/// Last block whose events were indexed.
+	pub fn last_block(&self) -> io::Result<Option<BlockNumber>> {
+		self.db
+			.get(COL_META, LAST_BLOCK_KEY)?
+			.map(|value| decode(&value))
+			.transpose()
+	}
+
+	/// Events of credential `id`, oldest first.
+	pub fn credential_history(&self, id: u32) -> io::Result<Vec<CredentialEvent>> {
+		self.events(COL_CREDENTIALS, &id.to_be_bytes())
+	}
+
+	/// Events of every credential `issuer` ever issued, oldest first.
+	pub fn credentials_by_issuer(&self, issuer: &AccountId) -> io::Result<Vec<CredentialEvent>> {
+		self.events(COL_CREDENTIALS_BY_ISSUER, issuer.as_ref())
+	}
+
+	/// Events of schema `id`, oldest first.
+	pub fn schema_history(&self, id: u32) -> io::Result<Vec<SchemaEvent>> {
+		self.events(COL_SCHEMAS, &id.to_be_bytes())
+	}
+
+	/// Events of every schema `creator` ever created, oldest first.
+	pub fn schemas_by_creator(&self, creator: &AccountId) -> io::Result<Vec<SchemaEvent>> {
+		self.events(COL_SCHEMAS_BY_CREATOR, creator.as_ref())
+	}
+
+	/// Delegations the `issuer_did` DID ever granted or revoked, oldest first.
+	pub fn delegations_by_issuer(&self, issuer_did: &[u8]) -> io::Result<Vec<DelegationEvent>> {
+		self.events(COL_DELEGATIONS_BY_ISSUER, &blake2_256(issuer_did))
+	}
+
+	/// Delegations `delegate` was ever granted or lost, oldest first.
+	pub fn delegations_by_delegate(
+		&self,
+		delegate: &AccountId,
+	) -> io::Result<Vec<DelegationEvent>> {
+		self.events(COL_DELEGATIONS_BY_DELEGATE, delegate.as_ref())
+	}
+
+	/// Record the credential and schema events among `events` of block `number` and mark the
+	/// block as indexed, all in one database transaction.
+	pub fn index_block(
+		&self,
+		number: BlockNumber,
+		hash: Hash,
+		events: impl IntoIterator<Item = RuntimeEvent>,
+	) -> io::Result<()> {
+		let mut block = BlockWriter {
+			db: &*self.db,
+			transaction: self.db.transaction(),
+			accounts: HashMap::new(),
+			offered: Vec::new(),
+			number,
+			hash,
+		};
+		for (index, event) in events.into_iter().enumerate() {
+			let index = index as u32;
+			match event {
+				RuntimeEvent::Credentials(event) => block.credential_event(index, event)?,
+				RuntimeEvent::Schemas(event) => block.schema_event(index, event)?,
+				_ => {},
+			}
+		}
+		let mut transaction = block.transaction;
+		transaction.put(COL_META, LAST_BLOCK_KEY, &number.encode());
+		self.db.write(transaction)
+	}
+
+	fn events<E: Decode>(&self, column: u32, prefix: &[u8]) -> io::Result<Vec<E>> {
+		self.db
+			.iter_with_prefix(column, prefix)
+			.map(|entry| decode(&entry?.1))
+			.collect()
+	}
+}
+
+/// Collects the writes of one block into a single transaction.
+struct BlockWriter<'a> {
+	db: &'a dyn KeyValueDB,
+	transaction: DBTransaction,
+	// accounts of credentials and schemas created earlier in the block, keyed by column and id
+	accounts: HashMap<(u32, u32), AccountId>,
+	// ids of the credentials offered earlier in the block, in order
+	offered: Vec<u32>,
+	number: BlockNumber,
+	hash: Hash,
+}
+
+impl BlockWriter<'_> {
+	fn credential_event(
+		&mut self,
+		index: u32,
+		event: pallet_credentials::Event<zeno_runtime::Runtime>,
+	) -> io::Result<()> {
+		use pallet_credentials::Event;
+
+		let (id, account, action) = match event {
+			Event::CredentialCreated {
+				credential_id,
+				issuer,
+				holder_did,
+				schema_id,
+				schema_version,
+				content_hash,
+				moment,
+			} => (
+				credential_id,
+				Some(issuer),
+				CredentialAction::Created {
+					holder_did,
+					schema_id,
+					schema_version,
+					content_hash: content_hash.into(),
+					moment,
+				},
+			),
+			Event::CredentialUpdated {
+				credential_id,
+				issuer,
+				holder_did,
+				schema_id,
+				schema_version,
+				content_hash,
+				moment,
+			} => (
+				credential_id,
+				Some(issuer),
+				CredentialAction::Updated {
+					holder_did,
+					schema_id,
+					schema_version,
+					content_hash: content_hash.into(),
+					moment,
+				},
+			),
+			Event::CredentialDeleted(id) => (id, None, CredentialAction::Deleted),
+			Event::CredentialRevoked(id, reason) =>
+				(id, None, CredentialAction::Revoked { reason }),
+			Event::CredentialSuspended(id, reason) =>
+				(id, None, CredentialAction::Suspended { reason }),
+			Event::CredentialReinstated(id, reason) =>
+				(id, None, CredentialAction::Reinstated { reason }),
+			Event::CredentialOffered {
+				credential_id,
+				issuer,
+				holder_did,
+				content_hash,
+				expires_at,
+			} => {
+				self.offered.push(credential_id);
+				(
+					credential_id,
+					Some(issuer),
+					CredentialAction::Offered {
+						holder_did,
+						content_hash: content_hash.into(),
+						expires_at,
+					},
+				)
+			},
+			Event::CredentialOfferRejected(id) => (id, None, CredentialAction::OfferRejected),
+			Event::CredentialOfferExpired(id) => (id, None, CredentialAction::OfferExpired),
+			// a batch announces its credentials right before, as the last `count` offers
+			Event::CredentialBatchCreated { root, issuer, count } => {
+				let first = self.offered.len().saturating_sub(count as usize);
+				for id in self.offered.split_off(first) {
+					let action = CredentialAction::Batched { root: root.into(), count };
+					self.put(&CREDENTIAL_COLUMNS, index, id, Some(issuer.clone()), action)?;
+				}
+				return Ok(())
+			},
+			Event::DelegateAdded { issuer_did, delegate } =>
+				return self.put_delegation(index, issuer_did, delegate, DelegationAction::Added),
+			Event::DelegateRemoved { issuer_did, delegate } =>
+				return self.put_delegation(index, issuer_did, delegate, DelegationAction::Removed),
+			// only the hidden variant of the generated enum is left
+			_ => return Ok(()),
+		};
+		self.put(&CREDENTIAL_COLUMNS, index, id, account, action)
+	}
+
+	fn schema_event(
+		&mut self,
+		index: u32,
+		event: pallet_schemas::Event<zeno_runtime::Runtime>,
+	) -> io::Result<()> {
+		use pallet_schemas::Event;
+
+		let (id, account, action) = match event {
+			Event::SchemaCreated { schema_id, creator, creator_did, content_hash, moment } => (
+				schema_id,
+				Some(creator),
+				SchemaAction::Created { creator_did, content_hash: content_hash.into(), moment },
+			),
+			Event::SchemaUpdated {
+				schema_id,
+				version,
+				creator,
+				creator_did,
+				content_hash,
+				moment,
+			} => (
+				schema_id,
+				Some(creator),
+				SchemaAction::Updated {
+					version,
+					creator_did,
+					content_hash: content_hash.into(),
+					moment,
+				},
+			),
+			Event::SchemaDeleted(id) => (id, None, SchemaAction::Deleted),
+			Event::SchemaVersionDeprecated(id, version) =>
+				(id, None, SchemaAction::VersionDeprecated { version }),
+			Event::SchemaDeprecated(id) => (id, None, SchemaAction::Deprecated),
+			Event::IssuerAdded(id, issuer_did) =>
+				(id, None, SchemaAction::IssuerAdded { issuer_did }),
+			Event::IssuerRemoved(id, issuer_did) =>
+				(id, None, SchemaAction::IssuerRemoved { issuer_did }),
+			// only the hidden variant of the generated enum is left
+			_ => return Ok(()),
+		};
+		self.put(&SCHEMA_COLUMNS, index, id, account, action)
+	}
+
+	// Events that don't name the account are filed under the account the item was created by
+	fn put<Action: Encode>(
+		&mut self,
+		columns: &Columns,
+		index: u32,
+		id: u32,
+		account: Option<AccountId>,
+		action: Action,
+	) -> io::Result<()> {
+		let account = match account {
+			Some(account) => {
+				self.transaction.put(columns.accounts, &id.to_be_bytes(), account.as_ref());
+				self.accounts.insert((columns.accounts, id), account.clone());
+				Some(account)
+			},
+			None => match self.accounts.get(&(columns.accounts, id)) {
+				Some(account) => Some(account.clone()),
+				None => self
+					.db
+					.get(columns.accounts, &id.to_be_bytes())?
+					.map(|value| decode(&value))
+					.transpose()?,
+			},
+		};
+		let event = IndexedEvent {
+			id,
+			account: account.clone(),
+			action,
+			block_number: self.number,
+			block_hash: self.hash,
+			event_index: index,
+		};
+		let position = self.position(index);
+		let value = event.encode();
+		let key = [&id.to_be_bytes()[..], &position].concat();
+		self.transaction.put_vec(columns.events, &key, value.clone());
+		if let Some(account) = account {
+			let key = [account.as_ref(), &position[..]].concat();
+			self.transaction.put_vec(columns.by_account, &key, value);
+		}
+		Ok(())
+	}
+
+	// Delegation events are filed under both the issuer DID and the delegate
+	fn put_delegation(
+		&mut self,
+		index: u32,
+		issuer_did: Vec<u8>,
+		delegate: AccountId,
+		action: DelegationAction,
+	) -> io::Result<()> {
+		let position = self.position(index);
+		let by_issuer = [&blake2_256(&issuer_did)[..], &position].concat();
+		let by_delegate = [delegate.as_ref(), &position[..]].concat();
+		let event = DelegationEvent {
+			issuer_did,
+			delegate,
+			action,
+			block_number: self.number,
+			block_hash: self.hash,
+			event_index: index,
+		};
+		let value = event.encode();
+		self.transaction.put_vec(COL_DELEGATIONS_BY_ISSUER, &by_issuer, value.clone());
+		self.transaction.put_vec(COL_DELEGATIONS_BY_DELEGATE, &by_delegate, value);
+		Ok(())
+	}
+
+	// Key suffix ordering the events of this block after those of earlier blocks
+	fn position(&self, index: u32) -> Vec<u8> {
+		[self.number.to_be_bytes(), index.to_be_bytes()].concat()
+	}
+}
+
+fn decode<T: Decode>(mut value: &[u8]) -> io::Result<T> {
+	T::decode(&mut value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+/// Index the events of every finalized block, resuming after the last indexed one. Runs until
+/// the finality notification stream ends. Reading blocks and writing the database blocks, so
+/// this is spawned as a blocking task, which also keeps catching up with a long chain off the
+/// async executor. Blocks that fail to index are retried on the next finality notification.
+pub async fn run(client: Arc<FullClient>, indexer: Arc<Indexer>) {
+	let mut finality = client.finality_notification_stream();
+	// catch up with blocks finalized while the indexer wasn't running
+	if let Err(e) = index_until(&client, &indexer, client.info().finalized_number) {
+		log::error!("Indexing finalized blocks failed, retrying on the next one: {}", e);
+	}
+	while let Some(notification) = finality.next().await {
+		if let Err(e) = index_until(&client, &indexer, *notification.header.number()) {
+			log::error!("Indexing finalized blocks failed, retrying on the next one: {}", e);
+		}
+	}
+}
+
+fn index_until(client: &FullClient, indexer: &Indexer, finalized: BlockNumber) -> io::Result<()> {
+	let next = indexer.last_block()?.map_or(0, |last| last + 1);
+	for number in next..=finalized {
+		let hash = client
+			.hash(number)
+			.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?
+			.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "finalized block not found"))?;
+		let events = match block_events(client, hash) {
+			Ok(events) => events,
+			Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+				log::warn!("Skipping the events of block #{} ({}): {}", number, hash, e);
+				Vec::new()
+			},
+			Err(e) => return Err(e),
+		};
+		indexer.index_block(number, hash, events.into_iter().map(|record| record.event))?;
+	}
+	Ok(())
+}
+
+fn block_events(
+	client: &FullClient,
+	hash: Hash,
+) -> io::Result<Vec<frame_system::EventRecord<RuntimeEvent, Hash>>> {
+	let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+	client
+		.storage(hash, &key)
+		.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?
+		.map_or(Ok(Vec::new()), |data| decode(&data.0))
+}
+
+#[rpc(server)]
+pub trait IndexerApi {
+	/// Returns the number of the last indexed block.
+	#[method(name = "indexer_lastBlock")]
+	fn last_block(&self) -> RpcResult<Option<BlockNumber>>;
+
+	/// Returns every indexed event of credential `id`, oldest first.
+	#[method(name = "indexer_credentialHistory")]
+	fn credential_history(&self, id: u32) -> RpcResult<Vec<CredentialEvent>>;
+
+	/// Returns the indexed events of every credential `issuer` ever issued, deleted ones
+	/// included, oldest first.
+	#[method(name = "indexer_credentialsByIssuer")]
+	fn credentials_by_issuer(&self, issuer: AccountId) -> RpcResult<Vec<CredentialEvent>>;
+
+	/// Returns every indexed event of schema `id`, oldest first.
+	#[method(name = "indexer_schemaHistory")]
+	fn schema_history(&self, id: u32) -> RpcResult<Vec<SchemaEvent>>;
+
+	/// Returns the indexed events of every schema `creator` ever created, deleted ones included,
+	/// oldest first.
+	#[method(name = "indexer_schemasByCreator")]
+	fn schemas_by_creator(&self, creator: AccountId) -> RpcResult<Vec<SchemaEvent>>;
+
+	/// Returns every delegation the `issuer_did` DID granted or revoked, oldest first.
+	#[method(name = "indexer_delegationsByIssuer")]
+	fn delegations_by_issuer(&self, issuer_did: Vec<u8>) -> RpcResult<Vec<DelegationEvent>>;
+
+	/// Returns every delegation `delegate` was granted or lost, oldest first.
+	#[method(name = "indexer_delegationsByDelegate")]
+	fn delegations_by_delegate(&self, delegate: AccountId) -> RpcResult<Vec<DelegationEvent>>;
+}
+
+/// Provides RPC methods to query the indexer.
+pub struct IndexerRpc {
+	indexer: Arc<Indexer>,
+}
+
+impl IndexerRpc {
+	/// Creates a new instance of the indexer RPC helper.
+	pub fn new(indexer: Arc<Indexer>) -> Self {
+		Self { indexer }
+	}
+}
+
+fn database_error_into_rpc_err(err: io::Error) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		1,
+		"Unable to query the indexer.",
+		Some(err.to_string()),
+	))
+	.into()
+}
+
+impl IndexerApiServer for IndexerRpc {
+	fn last_block(&self) -> RpcResult<Option<BlockNumber>> {
+		self.indexer.last_block().map_err(database_error_into_rpc_err)
+	}
+
+	fn credential_history(&self, id: u32) -> RpcResult<Vec<CredentialEvent>> {
+		self.indexer.credential_history(id).map_err(database_error_into_rpc_err)
+	}
+
+	fn credentials_by_issuer(&self, issuer: AccountId) -> RpcResult<Vec<CredentialEvent>> {
+		self.indexer.credentials_by_issuer(&issuer).map_err(database_error_into_rpc_err)
+	}
+
+	fn schema_history(&self, id: u32) -> RpcResult<Vec<SchemaEvent>> {
+		self.indexer.schema_history(id).map_err(database_error_into_rpc_err)
+	}
+
+	fn schemas_by_creator(&self, creator: AccountId) -> RpcResult<Vec<SchemaEvent>> {
+		self.indexer.schemas_by_creator(&creator).map_err(database_error_into_rpc_err)
+	}
+
+	fn delegations_by_issuer(&self, issuer_did: Vec<u8>) -> RpcResult<Vec<DelegationEvent>> {
+		self.indexer.delegations_by_issuer(&issuer_did).map_err(database_error_into_rpc_err)
+	}
+
+	fn delegations_by_delegate(&self, delegate: AccountId) -> RpcResult<Vec<DelegationEvent>> {
+		self.indexer.delegations_by_delegate(&delegate).map_err(database_error_into_rpc_err)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+
+	fn created(credential_id: u32, issuer: AccountId) -> RuntimeEvent {
+		RuntimeEvent::Credentials(pallet_credentials::Event::CredentialCreated {
+			credential_id,
+			issuer,
+			holder_did: b"did:seneca:holder".to_vec(),
+			schema_id: 7,
+			schema_version: 0,
+			content_hash: [credential_id as u8; 32],
+			moment: 1_000,
+		})
+	}
+
+	#[test]
+	fn deleted_credentials_stay_listed_under_their_issuer() {
+		let indexer = Indexer::new(Arc::new(kvdb_memorydb::create(NUM_COLUMNS)));
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		assert_eq!(indexer.last_block().unwrap(), None);
+
+		// a credential created and revoked within one block is attributed to its issuer
+		indexer
+			.index_block(
+				1,
+				Hash::repeat_byte(1),
+				vec![
+					created(1, alice.clone()),
+					created(2, bob.clone()),
+					RuntimeEvent::Credentials(pallet_credentials::Event::CredentialRevoked(1, 3)),
+				],
+			)
+			.unwrap();
+		indexer
+			.index_block(
+				2,
+				Hash::repeat_byte(2),
+				vec![RuntimeEvent::Credentials(pallet_credentials::Event::CredentialDeleted(1))],
+			)
+			.unwrap();
+		assert_eq!(indexer.last_block().unwrap(), Some(2));
+
+		let history = indexer.credentials_by_issuer(&alice).unwrap();
+		assert_eq!(
+			history.iter().map(|event| event.action.clone()).collect::<Vec<_>>(),
+			vec![
+				CredentialAction::Created {
+					holder_did: b"did:seneca:holder".to_vec(),
+					schema_id: 7,
+					schema_version: 0,
+					content_hash: Hash::repeat_byte(1),
+					moment: 1_000,
+				},
+				CredentialAction::Revoked { reason: 3 },
+				CredentialAction::Deleted,
+			]
+		);
+		assert!(history.iter().all(|event| event.id == 1 && event.account == Some(alice.clone())));
+		assert_eq!(history[2].block_hash, Hash::repeat_byte(2));
+		assert_eq!(indexer.credential_history(1).unwrap(), history);
+		assert_eq!(indexer.credentials_by_issuer(&bob).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn schema_events_are_filed_under_their_creator() {
+		let indexer = Indexer::new(Arc::new(kvdb_memorydb::create(NUM_COLUMNS)));
+		let alice = AccountKeyring::Alice.to_account_id();
+		indexer
+			.index_block(
+				1,
+				Hash::repeat_byte(1),
+				vec![
+					RuntimeEvent::Schemas(pallet_schemas::Event::SchemaCreated {
+						schema_id: 4,
+						creator: alice.clone(),
+						creator_did: b"did:seneca:alice".to_vec(),
+						content_hash: [0; 32],
+						moment: 1_000,
+					}),
+					RuntimeEvent::Schemas(pallet_schemas::Event::SchemaDeprecated(4)),
+				],
+			)
+			.unwrap();
+
+		let history = indexer.schemas_by_creator(&alice).unwrap();
+		assert_eq!(history.len(), 2);
+		assert_eq!(history[1].action, SchemaAction::Deprecated);
+		assert_eq!(history[1].event_index, 1);
+		assert_eq!(indexer.schema_history(4).unwrap(), history);
+		assert!(indexer.schema_history(5).unwrap().is_empty());
+	}
+
+	#[test]
+	fn offers_batches_and_delegations_are_indexed() {
+		use pallet_credentials::Event;
+
+		let indexer = Indexer::new(Arc::new(kvdb_memorydb::create(NUM_COLUMNS)));
+		let bob = AccountKeyring::Bob.to_account_id();
+		let alice_did = b"did:seneca:alice".to_vec();
+		let offered = |credential_id: u32| {
+			RuntimeEvent::Credentials(Event::CredentialOffered {
+				credential_id,
+				issuer: bob.clone(),
+				holder_did: b"did:seneca:holder".to_vec(),
+				content_hash: [credential_id as u8; 32],
+				expires_at: 11,
+			})
+		};
+		// bob offers credential 1 on his own, and 2 and 3 in a batch as a delegate of alice
+		indexer
+			.index_block(
+				1,
+				Hash::repeat_byte(1),
+				vec![
+					RuntimeEvent::Credentials(Event::DelegateAdded {
+						issuer_did: alice_did.clone(),
+						delegate: bob.clone(),
+					}),
+					offered(1),
+					offered(2),
+					offered(3),
+					RuntimeEvent::Credentials(Event::CredentialBatchCreated {
+						root: [9; 32],
+						issuer: bob.clone(),
+						count: 2,
+					}),
+				],
+			)
+			.unwrap();
+		indexer
+			.index_block(
+				2,
+				Hash::repeat_byte(2),
+				vec![
+					RuntimeEvent::Credentials(Event::CredentialOfferRejected(2)),
+					RuntimeEvent::Credentials(Event::CredentialOfferExpired(1)),
+					RuntimeEvent::Credentials(Event::DelegateRemoved {
+						issuer_did: alice_did.clone(),
+						delegate: bob.clone(),
+					}),
+				],
+			)
+			.unwrap();
+
+		let actions = |id: u32| {
+			indexer
+				.credential_history(id)
+				.unwrap()
+				.into_iter()
+				.map(|event| event.action)
+				.collect::<Vec<_>>()
+		};
+		let offer = |id: u8| CredentialAction::Offered {
+			holder_did: b"did:seneca:holder".to_vec(),
+			content_hash: Hash::repeat_byte(id),
+			expires_at: 11,
+		};
+		let batched = CredentialAction::Batched { root: Hash::repeat_byte(9), count: 2 };
+		assert_eq!(actions(1), vec![offer(1), CredentialAction::OfferExpired]);
+		assert_eq!(actions(2), vec![offer(2), batched.clone(), CredentialAction::OfferRejected]);
+		assert_eq!(actions(3), vec![offer(3), batched]);
+		assert_eq!(indexer.credentials_by_issuer(&bob).unwrap().len(), 7);
+
+		let delegations = indexer.delegations_by_delegate(&bob).unwrap();
+		assert_eq!(
+			delegations.iter().map(|event| event.action.clone()).collect::<Vec<_>>(),
+			vec![DelegationAction::Added, DelegationAction::Removed]
+		);
+		assert_eq!(indexer.delegations_by_issuer(&alice_did).unwrap(), delegations);
+		assert!(indexer.delegations_by_issuer(b"did:seneca:alic").unwrap().is_empty());
+	}
+
+	#[test]
+	fn blocks_indexed_by_an_earlier_version_are_indexed_again() {
+		let indexer = Indexer::new(Arc::new(kvdb_memorydb::create(NUM_COLUMNS)));
+		let alice = AccountKeyring::Alice.to_account_id();
+		indexer.index_block(1, Hash::repeat_byte(1), vec![created(1, alice.clone())]).unwrap();
+		indexer.upgrade().unwrap();
+		assert_eq!(indexer.last_block().unwrap(), None);
+
+		// indexing the block again leaves a single copy of its events
+		indexer.index_block(1, Hash::repeat_byte(1), vec![created(1, alice.clone())]).unwrap();
+		indexer.upgrade().unwrap();
+		assert_eq!(indexer.last_block().unwrap(), Some(1));
+		assert_eq!(indexer.credentials_by_issuer(&alice).unwrap().len(), 1);
+	}
+}
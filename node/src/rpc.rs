@@ -8,12 +8,14 @@
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
-use node_primitives::{AccountId, Balance, Index};
+use node_primitives::{AccountId, Balance, BlockNumber, Index, Moment, Signature};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
-use zeno_runtime::opaque::Block;
+use zeno_runtime::{opaque::Block, StoredClaimOpening, StoredCredential};
+
+use crate::indexer::Indexer;
 
 pub use sc_rpc_api::DenyUnsafe;
 
@@ -25,6 +27,8 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Credential and schema indexer, if enabled.
+	pub indexer: Option<Arc<Indexer>>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -37,17 +41,32 @@ where
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_credentials_rpc::CredentialsRuntimeApi<
+		Block,
+		u32,
+		BlockNumber,
+		Moment,
+		StoredCredential,
+		StoredClaimOpening,
+		Signature,
+	>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 {
+	use crate::indexer::{IndexerApiServer, IndexerRpc};
+	use pallet_credentials_rpc::{Credentials, CredentialsApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, indexer } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Credentials::new(client).into_rpc())?;
+	if let Some(indexer) = indexer {
+		module.merge(IndexerRpc::new(indexer).into_rpc())?;
+	}
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
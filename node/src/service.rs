@@ -11,6 +11,8 @@ use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use std::{sync::Arc, time::Duration};
 use zeno_runtime::{self, opaque::Block, RuntimeApi};
 
+use crate::indexer::Indexer;
+
 // Our native executor instance.
 pub struct ExecutorDispatch;
 
@@ -149,8 +151,12 @@ pub fn new_partial(
 	})
 }
 
-/// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+/// Builds a new service for a full client, following finalized blocks with the credential and
+/// schema indexer if `enable_indexer` is set.
+pub fn new_full(
+	mut config: Configuration,
+	enable_indexer: bool,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -204,13 +210,36 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	let indexer = if enable_indexer {
+		let path = config
+			.base_path
+			.as_ref()
+			.map(|base_path| base_path.config_dir(config.chain_spec.id()).join("indexer"))
+			.ok_or_else(|| ServiceError::Other("The indexer needs a base path".into()))?;
+		let indexer = Arc::new(Indexer::open(&path).map_err(|e| {
+			ServiceError::Other(format!("Failed to open the indexer database: {}", e))
+		})?);
+		task_manager.spawn_handle().spawn_blocking(
+			"credentials-indexer",
+			None,
+			crate::indexer::run(client.clone(), indexer.clone()),
+		);
+		Some(indexer)
+	} else {
+		None
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				indexer: indexer.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -0,0 +1,339 @@
+//! RPC interface for the credentials pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_credentials::types::{
+	BatchProof, CredentialStatusRecord, DisclosureError, Presentation, PresentationVerification,
+	UpdateRecord,
+};
+pub use pallet_credentials_runtime_api::CredentialsApi as CredentialsRuntimeApi;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+/// Pages of credentials hold at most [`pallet_credentials::MAX_PAGE_SIZE`] of them, larger `limit`s
+/// are clamped.
+#[rpc(client, server)]
+pub trait CredentialsApi<
+	BlockHash,
+	CredentialId,
+	BlockNumber,
+	Moment,
+	Credential,
+	ClaimOpening,
+	Signature,
+>
+{
+	/// Returns up to `limit` credentials issued against `schema_id`, starting after `start`.
+	#[method(name = "credentials_bySchema")]
+	fn credentials_by_schema(
+		&self,
+		schema_id: u32,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>>;
+
+	/// Returns up to `limit` credentials issued for `subject_id`, starting after `start`.
+	#[method(name = "credentials_bySubject")]
+	fn credentials_by_subject(
+		&self,
+		subject_id: Vec<u8>,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>>;
+
+	/// Returns up to `limit` credentials held by the `holder` DID, starting after `start`.
+	#[method(name = "credentials_byHolder")]
+	fn credentials_by_holder(
+		&self,
+		holder: Vec<u8>,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>>;
+
+	/// Returns up to `limit` credentials issued by the `issuer` DID, starting after `start`.
+	#[method(name = "credentials_byIssuer")]
+	fn credentials_by_issuer(
+		&self,
+		issuer: Vec<u8>,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>>;
+
+	/// Returns the revocation status of `id`.
+	#[method(name = "credentials_status")]
+	fn credential_status(
+		&self,
+		id: CredentialId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<CredentialStatusRecord<BlockNumber, Moment>>>;
+
+	/// Returns whether `id` exists, is active and is within its validity period.
+	#[method(name = "credentials_isValid")]
+	fn is_credential_valid(&self, id: CredentialId, at: Option<BlockHash>) -> RpcResult<bool>;
+
+	/// Returns the lowest nonce the `issuer` DID may sign its next credential with.
+	#[method(name = "credentials_nextNonce")]
+	fn next_credential_nonce(&self, issuer: Vec<u8>, at: Option<BlockHash>) -> RpcResult<u64>;
+
+	/// Returns the lowest nonce the `creator` DID may sign its next schema with.
+	#[method(name = "credentials_nextSchemaNonce")]
+	fn next_schema_nonce(&self, creator: Vec<u8>, at: Option<BlockHash>) -> RpcResult<u64>;
+
+	/// Returns the update history of credential `id`, oldest update first.
+	#[method(name = "credentials_history")]
+	fn credential_history(
+		&self,
+		id: CredentialId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(u32, UpdateRecord<BlockNumber, Moment>)>>;
+
+	/// Returns the update history of schema `schema_id`, oldest update first.
+	#[method(name = "credentials_schemaHistory")]
+	fn schema_history(
+		&self,
+		schema_id: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(u32, UpdateRecord<BlockNumber, Moment>)>>;
+
+	/// Checks claims revealed by the holder of `id` against its claim commitments.
+	#[method(name = "credentials_verifyDisclosure")]
+	fn verify_disclosure(
+		&self,
+		id: CredentialId,
+		openings: Vec<ClaimOpening>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Result<(), DisclosureError>>;
+
+	/// Checks the holder signature of `presentation` and every credential it presents.
+	#[method(name = "credentials_verifyPresentation")]
+	fn verify_presentation(
+		&self,
+		presentation: Presentation<CredentialId, Signature>,
+		at: Option<BlockHash>,
+	) -> RpcResult<PresentationVerification<CredentialId>>;
+
+	/// Returns the proof that credential `id` was issued in a batch, `None` if it was signed on
+	/// its own.
+	#[method(name = "credentials_batchProof")]
+	fn batch_proof(&self, id: CredentialId, at: Option<BlockHash>) -> RpcResult<Option<BatchProof>>;
+}
+
+/// Provides RPC methods to query the credentials pallet.
+pub struct Credentials<C, B> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Credentials<C, B> {
+	/// Creates a new instance of the Credentials RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query credentials.",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
+
+impl<C, Block, CredentialId, BlockNumber, Moment, Credential, ClaimOpening, Signature>
+	CredentialsApiServer<
+		<Block as BlockT>::Hash,
+		CredentialId,
+		BlockNumber,
+		Moment,
+		Credential,
+		ClaimOpening,
+		Signature,
+	> for Credentials<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: CredentialsRuntimeApi<
+		Block,
+		CredentialId,
+		BlockNumber,
+		Moment,
+		Credential,
+		ClaimOpening,
+		Signature,
+	>,
+	CredentialId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	BlockNumber: Codec + Serialize + Send + Sync + 'static,
+	Moment: Codec + Serialize + Send + Sync + 'static,
+	Credential: Codec + Serialize + Send + Sync + 'static,
+	ClaimOpening: Codec + DeserializeOwned + Send + Sync + 'static,
+	Signature: Codec + DeserializeOwned + Send + Sync + 'static,
+{
+	fn credentials_by_schema(
+		&self,
+		schema_id: u32,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.credentials_by_schema(at_hash, schema_id, start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn credentials_by_subject(
+		&self,
+		subject_id: Vec<u8>,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.credentials_by_subject(at_hash, subject_id, start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn credentials_by_holder(
+		&self,
+		holder: Vec<u8>,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.credentials_by_holder(at_hash, holder, start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn credentials_by_issuer(
+		&self,
+		issuer: Vec<u8>,
+		start: Option<CredentialId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(CredentialId, Credential)>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.credentials_by_issuer(at_hash, issuer, start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+	fn credential_status(
+		&self,
+		id: CredentialId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<CredentialStatusRecord<BlockNumber, Moment>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.credential_status(at_hash, id).map_err(runtime_error_into_rpc_err)
+	}
+	fn is_credential_valid(
+		&self,
+		id: CredentialId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<bool> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.is_credential_valid(at_hash, id).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn next_credential_nonce(
+		&self,
+		issuer: Vec<u8>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<u64> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.next_credential_nonce(at_hash, issuer).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn next_schema_nonce(
+		&self,
+		creator: Vec<u8>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<u64> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.next_schema_nonce(at_hash, creator).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn credential_history(
+		&self,
+		id: CredentialId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(u32, UpdateRecord<BlockNumber, Moment>)>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.credential_history(at_hash, id).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn schema_history(
+		&self,
+		schema_id: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(u32, UpdateRecord<BlockNumber, Moment>)>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.schema_history(at_hash, schema_id).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn verify_disclosure(
+		&self,
+		id: CredentialId,
+		openings: Vec<ClaimOpening>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Result<(), DisclosureError>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.verify_disclosure(at_hash, id, openings).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn verify_presentation(
+		&self,
+		presentation: Presentation<CredentialId, Signature>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<PresentationVerification<CredentialId>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.verify_presentation(at_hash, presentation).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn batch_proof(
+		&self,
+		id: CredentialId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<BatchProof>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.batch_proof(at_hash, id).map_err(runtime_error_into_rpc_err)
+	}
+}
@@ -0,0 +1,77 @@
+//! Runtime API definition for the credentials pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_credentials::types::{
+	BatchProof, CredentialStatusRecord, DisclosureError, Presentation, PresentationVerification,
+	UpdateRecord,
+};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Query credentials stored by the credentials pallet.
+	///
+	/// `Credential` is the `VerifiableCredential` type and `ClaimOpening` the `ClaimOpening` type
+	/// bounded by the runtime configuration, `Signature` the type credentials are signed with.
+	/// Pages of credentials hold at most `pallet_credentials::MAX_PAGE_SIZE` of them, whatever
+	/// the `limit` asked for.
+	pub trait CredentialsApi<CredentialId, BlockNumber, Moment, Credential, ClaimOpening, Signature>
+	where
+		CredentialId: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+		Credential: Codec,
+		ClaimOpening: Codec,
+		Signature: Codec,
+	{
+		/// Returns up to `limit` credentials issued against `schema_id`, starting after `start`.
+		fn credentials_by_schema(
+			schema_id: u32,
+			start: Option<CredentialId>,
+			limit: u32,
+		) -> Vec<(CredentialId, Credential)>;
+		/// Returns up to `limit` credentials issued for `subject_id`, starting after `start`.
+		fn credentials_by_subject(
+			subject_id: Vec<u8>,
+			start: Option<CredentialId>,
+			limit: u32,
+		) -> Vec<(CredentialId, Credential)>;
+		/// Returns up to `limit` credentials held by the `holder` DID, starting after `start`.
+		fn credentials_by_holder(
+			holder: Vec<u8>,
+			start: Option<CredentialId>,
+			limit: u32,
+		) -> Vec<(CredentialId, Credential)>;
+		/// Returns up to `limit` credentials issued by the `issuer` DID, starting after `start`.
+		fn credentials_by_issuer(
+			issuer: Vec<u8>,
+			start: Option<CredentialId>,
+			limit: u32,
+		) -> Vec<(CredentialId, Credential)>;
+		/// Returns the revocation status of `id`, or `None` if it does not exist.
+		fn credential_status(id: CredentialId) -> Option<CredentialStatusRecord<BlockNumber, Moment>>;
+		/// Returns whether `id` exists, is active and is within its validity period.
+		fn is_credential_valid(id: CredentialId) -> bool;
+		/// Returns the lowest nonce the `issuer` DID may sign its next credential with.
+		fn next_credential_nonce(issuer: Vec<u8>) -> u64;
+		/// Returns the lowest nonce the `creator` DID may sign its next schema with.
+		fn next_schema_nonce(creator: Vec<u8>) -> u64;
+		/// Returns the update history of credential `id`, oldest update first.
+		fn credential_history(id: CredentialId) -> Vec<(u32, UpdateRecord<BlockNumber, Moment>)>;
+		/// Returns the update history of schema `schema_id`, oldest update first.
+		fn schema_history(schema_id: u32) -> Vec<(u32, UpdateRecord<BlockNumber, Moment>)>;
+		/// Checks claims revealed by the holder of `id` against its claim commitments.
+		fn verify_disclosure(
+			id: CredentialId,
+			openings: Vec<ClaimOpening>,
+		) -> Result<(), DisclosureError>;
+		/// Checks the holder signature of `presentation` and every credential it presents.
+		fn verify_presentation(
+			presentation: Presentation<CredentialId, Signature>,
+		) -> PresentationVerification<CredentialId>;
+		/// Returns the proof that credential `id` was issued in a batch, `None` if it was signed
+		/// on its own.
+		fn batch_proof(id: CredentialId) -> Option<BatchProof>;
+	}
+}
@@ -2,19 +2,155 @@
 use super::*;
 use crate::types::*;
 use crate::Pallet as CredentialRegistry;
-use pallet_schemas::Pallet as SchemaRegistry;
+use pallet_schemas::{BalanceOf, Pallet as SchemaRegistry, SchemaOf};
 use codec::Encode;
 use frame_benchmarking::benchmarks;
-use frame_support::assert_ok;
+use frame_support::{
+	assert_ok, bounded_vec,
+	traits::{Currency, Get, Hooks},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
-use scale_info::prelude::format;
-use scale_info::prelude::vec;
+use scale_info::prelude::{format, vec::Vec};
+use sp_runtime::traits::Bounded;
 use pallet_schemas::types::{
 	Attribute, AttributeType, Claim, ClaimType, VerifiableCredentialSchema,
 };
 use sp_application_crypto::RuntimePublic;
 use sp_application_crypto::sr25519::Public;
 
+// Registers a schema and a credential issued by `public`
+fn setup_credential<T: Config>(
+	public: &Public,
+) -> (T::AccountId, T::CredentialId, CredentialOf<T>)
+where
+	T::AccountId: From<sp_core::sr25519::Public>,
+	T::Signature: From<sp_core::sr25519::Signature>,
+{
+	let credential_id: T::CredentialId = Default::default();
+	let caller: T::AccountId = public.clone().into();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	let account_id = format!("did:seneca:{:#?}", caller.clone());
+	let credential: CredentialOf<T> = VerifiableCredential {
+		context: b"Credential context".to_vec().try_into().unwrap(),
+		schema: 123u32,
+		schema_version: 0,
+		issuer: account_id.clone().into_bytes().try_into().unwrap(),
+		issuance_date: Some(Default::default()),
+		expiration_date: Some(Default::default()),
+		subject: Subject {
+			id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
+				.to_vec()
+				.try_into()
+				.unwrap(),
+			claim: bounded_vec![
+				Claim {
+					property: b"property".to_vec().try_into().unwrap(),
+					value: b"value".to_vec().try_into().unwrap(),
+					schemaid: None,
+					claim_type: ClaimType::SubjectClaim,
+					issuance_requirement: None,
+				},
+				Claim {
+					property: b"name".to_vec().try_into().unwrap(),
+					value: b"0x1234".to_vec().try_into().unwrap(),
+					schemaid: None,
+					claim_type: ClaimType::SubjectClaim,
+					issuance_requirement: None,
+				},
+			],
+			committed_claims: Default::default(),
+		},
+		credential_holder: account_id.clone().into_bytes().try_into().unwrap(),
+		nonce: 2u64,
+	};
+	let vf_schema: SchemaOf<T> = VerifiableCredentialSchema {
+		name: b"name".to_vec().try_into().unwrap(),
+		creator: account_id.into_bytes().try_into().unwrap(),
+		public: false,
+		creation_date: Default::default(),
+		expiration_date: credential.expiration_date,
+		mandatory_fields: bounded_vec![Attribute {
+			name: b"name".to_vec().try_into().unwrap(),
+			attribute_type: AttributeType::Hex,
+		}],
+		issuer_claims: bounded_vec![Claim {
+			property: b"property".to_vec().try_into().unwrap(),
+			value: b"value".to_vec().try_into().unwrap(),
+			schemaid: None,
+			claim_type: ClaimType::IssuerClaim,
+			issuance_requirement: None,
+		}],
+		subject_claims: bounded_vec![Claim {
+			property: b"property".to_vec().try_into().unwrap(),
+			value: b"value".to_vec().try_into().unwrap(),
+			schemaid: None,
+			claim_type: ClaimType::SubjectClaim,
+			issuance_requirement: None,
+		}],
+		credential_claims: bounded_vec![Claim {
+			property: b"property".to_vec().try_into().unwrap(),
+			value: b"value".to_vec().try_into().unwrap(),
+			schemaid: None,
+			claim_type: ClaimType::CredentialClaim,
+			issuance_requirement: None,
+		}],
+		metadata: b"metadata".to_vec().try_into().unwrap(),
+		nonce: 2u64,
+	};
+	let sig = public.sign(sp_core::testing::SR25519, &credential.encode()).unwrap();
+	let schema_data_sig = public.sign(sp_core::testing::SR25519, &vf_schema.encode()).unwrap();
+	assert_ok!(SchemaRegistry::<T>::create_schema(
+		RawOrigin::Signed(caller.clone()).into(),
+		Some(credential.schema.into()),
+		vf_schema.name,
+		vf_schema.creator,
+		vf_schema.public,
+		vf_schema.mandatory_fields,
+		vf_schema.creation_date,
+		vf_schema.expiration_date,
+		vf_schema.issuer_claims,
+		vf_schema.subject_claims,
+		vf_schema.credential_claims,
+		vf_schema.metadata,
+		schema_data_sig.into(),
+		vf_schema.nonce
+	));
+	assert_ok!(CredentialRegistry::<T>::create_credential(
+		RawOrigin::Signed(caller.clone()).into(),
+		Some(credential_id.clone()),
+		credential.context.clone(),
+		credential.schema,
+		credential.schema_version,
+		credential.issuer.clone(),
+		credential.issuance_date,
+		credential.expiration_date,
+		credential.subject.clone(),
+		credential.credential_holder.clone(),
+		sig.into(),
+		credential.nonce
+	));
+	accept_offer::<T>(&public, &caller, &credential_id, &credential);
+	(caller, credential_id, credential)
+}
+
+// Accepts the credential offered as `credential_id` with the key of its holder `public`
+fn accept_offer<T: Config>(
+	public: &Public,
+	caller: &T::AccountId,
+	credential_id: &T::CredentialId,
+	credential: &CredentialOf<T>,
+) where
+	T::Signature: From<sp_core::sr25519::Signature>,
+{
+	let content_hash = sp_io::hashing::blake2_256(&credential.encode());
+	let acceptance = public.sign(sp_core::testing::SR25519, &content_hash).unwrap();
+	assert_ok!(CredentialRegistry::<T>::accept_credential(
+		RawOrigin::Signed(caller.clone()).into(),
+		credential_id.clone(),
+		acceptance.into()
+	));
+}
 
 benchmarks! {
     where_clause {
@@ -28,60 +164,74 @@ benchmarks! {
 		let credential_id: T::CredentialId = Default::default();
 		let public = Public::generate_pair(sp_core::testing::SR25519, None);
 		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
 		let account_id = format!("did:seneca:{:#?}", caller.clone());
 		let creation_date: T::Moment = Default::default();
-		let credential: VerifiableCredential<T::Moment> = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let credential: CredentialOf<T> = VerifiableCredential {
+			context: b"Credential context".to_vec().try_into().unwrap(),
 			schema: schema_id,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: account_id.clone().into_bytes().try_into().unwrap(),
 			issuance_date: Some(Default::default()),
 			expiration_date: Some(Default::default()),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
+					.to_vec()
+					.try_into()
+					.unwrap(),
+				claim: bounded_vec![
+					Claim {
+						property: b"property".to_vec().try_into().unwrap(),
+						value: b"value".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: b"name".to_vec().try_into().unwrap(),
+						value: b"0x1234".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
+			credential_holder: account_id.clone().into_bytes().try_into().unwrap(),
 			nonce: 2u64,
 		};
-		let vf_schema: VerifiableCredentialSchema<T::Moment> = VerifiableCredentialSchema {
-			name: b"name".to_vec(),
-			creator: account_id.clone().into(),
+		let vf_schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"name".to_vec().try_into().unwrap(),
+			creator: account_id.clone().into_bytes().try_into().unwrap(),
 			public: false,
 			creation_date: Default::default(),
 			expiration_date: credential.expiration_date,
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: b"metadata".to_vec().try_into().unwrap(),
 			nonce: 2u64,
 		};
 
@@ -93,8 +243,8 @@ benchmarks! {
 		
 		assert_ok!(SchemaRegistry::<T>::create_schema(
 			RawOrigin::Signed(caller.clone()).into(),
-			credential.schema.into(),
-			b"name".to_vec(),
+			Some(credential.schema.into()),
+			b"name".to_vec().try_into().unwrap(),
 			vf_schema.creator,
 			false,
 			vf_schema.mandatory_fields,
@@ -103,16 +253,17 @@ benchmarks! {
 			vf_schema.issuer_claims,
 			vf_schema.subject_claims,
 			vf_schema.credential_claims,
-			b"metadata".to_vec(),
+			b"metadata".to_vec().try_into().unwrap(),
 			schema_data_sig.into(),
 			vf_schema.nonce
 		));
 		// Encode and sign the schema message.
 	}:  _(
 		RawOrigin::Signed(caller), 
-		credential_id.clone(),
+		Some(credential_id.clone()),
 		credential.clone().context,
 		credential.clone().schema,
+		credential.clone().schema_version,
 		credential.clone().issuer,
 		credential.clone().issuance_date,
 		credential.clone().expiration_date,
@@ -121,8 +272,12 @@ benchmarks! {
 		data_sig.clone().into(),
 		credential.clone().nonce)
 	verify {
-		//assert that the credential stored is different from the one created since the nonce is different.
-		assert_eq!(CredentialStore::<T>::get(credential_id), Some((data_sig.clone().into(), credential)));
+		// the credential waits for its holder to accept it
+		assert_eq!(
+			CredentialOffers::<T>::get(credential_id).map(|(sig, credential, ..)| (sig, credential)),
+			Some((data_sig.clone().into(), credential.clone()))
+		);
+		assert_eq!(CredentialStore::<T>::get(credential_id), None);
 	}
 	update_credential{
 		let s in 0 .. 100;
@@ -131,94 +286,123 @@ benchmarks! {
 		let credential_id: T::CredentialId = Default::default();
 		let public = Public::generate_pair(sp_core::testing::SR25519, None);
 		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
 		let account_id = format!("did:seneca:{:#?}", caller.clone());
 		let creation_date: T::Moment =  Default::default();
-		let credential: VerifiableCredential<T::Moment> = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let credential: CredentialOf<T> = VerifiableCredential {
+			context: b"Credential context".to_vec().try_into().unwrap(),
 			schema: schema_id,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: account_id.clone().into_bytes().try_into().unwrap(),
 			issuance_date: Some(Default::default()),
 			expiration_date: Some(Default::default()),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
+					.to_vec()
+					.try_into()
+					.unwrap(),
+				claim: bounded_vec![
+					Claim {
+						property: b"property".to_vec().try_into().unwrap(),
+						value: b"value".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: b"name".to_vec().try_into().unwrap(),
+						value: b"0x1234".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
+			credential_holder: account_id.clone().into_bytes().try_into().unwrap(),
 			nonce: 2u64,
 		};
-		let updated_credential:VerifiableCredential<T::Moment> = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let updated_credential:CredentialOf<T> = VerifiableCredential {
+			context: b"Credential context".to_vec().try_into().unwrap(),
 			schema: schema_id,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: account_id.clone().into_bytes().try_into().unwrap(),
 			issuance_date: Some(Default::default()),
 			expiration_date: Some(Default::default()),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
+					.to_vec()
+					.try_into()
+					.unwrap(),
+				claim: bounded_vec![
+					Claim {
+						property: b"property".to_vec().try_into().unwrap(),
+						value: b"value".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: b"name".to_vec().try_into().unwrap(),
+						value: b"0x1234".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
+			credential_holder: account_id.clone().into_bytes().try_into().unwrap(),
 			nonce: 3u64,
 		};
 
-		let vf_schema: VerifiableCredentialSchema<T::Moment> = VerifiableCredentialSchema {
-			name: b"name".to_vec(),
-			creator: account_id.clone().into(),
+		let vf_schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"name".to_vec().try_into().unwrap(),
+			creator: account_id.clone().into_bytes().try_into().unwrap(),
 			public: false,
 			creation_date: Default::default(),
 			expiration_date: credential.expiration_date,
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: b"metadata".to_vec().try_into().unwrap(),
 			nonce: 2u64,
 		};
 
 		// sign the schema in benchmarks
 		let sig = public.sign(sp_core::testing::SR25519, &credential.encode()).unwrap();
+		let updated_sig =
+			public.sign(sp_core::testing::SR25519, &updated_credential.encode()).unwrap();
 		let binding = vf_schema.encode();
 		let vc_bytes = binding.as_slice();
 		let schema_data_sig = public.sign(sp_core::testing::SR25519, &vc_bytes.encode()).unwrap();
 
 		assert_ok!(SchemaRegistry::<T>::create_schema(
 			RawOrigin::Signed(caller.clone()).into(),
-			credential.schema.into(),
-			b"name".to_vec(),
+			Some(credential.schema.into()),
+			b"name".to_vec().try_into().unwrap(),
 			vf_schema.creator,
 			false,
 			vf_schema.mandatory_fields,
@@ -227,90 +411,111 @@ benchmarks! {
 			vf_schema.issuer_claims,
 			vf_schema.subject_claims,
 			vf_schema.credential_claims,
-			b"metadata".to_vec(),
+			b"metadata".to_vec().try_into().unwrap(),
 			schema_data_sig.into(),
 			vf_schema.nonce
 		));
 
 		assert_ok!(CredentialRegistry::<T>::create_credential(
 			RawOrigin::Signed(caller.clone()).into(), 
-			credential_id.clone(),
-			credential.context,
+			Some(credential_id.clone()),
+			credential.context.clone(),
 			credential.schema,
-			credential.issuer,
+			credential.schema_version,
+			credential.issuer.clone(),
 			credential.issuance_date,
 			credential.expiration_date,
-			credential.subject,
-			credential.credential_holder,
+			credential.subject.clone(),
+			credential.credential_holder.clone(),
 			sig.clone().into(),
 			credential.nonce
 		));
-	}:  _(RawOrigin::Signed(caller), credential_id.clone().into(), (sig.clone().into(), updated_credential.clone()))
+		accept_offer::<T>(&public, &caller, &credential_id, &credential);
+	}:  _(RawOrigin::Signed(caller), credential_id.clone().into(), (updated_sig.clone().into(), updated_credential.clone()))
 	verify {
-		assert_eq!(CredentialStore::<T>::get(credential_id), Some((sig.into(), updated_credential)));
+		// the update waits for the holder to accept it
+		assert_eq!(
+			CredentialOffers::<T>::get(credential_id).map(|(sig, credential, ..)| (sig, credential)),
+			Some((updated_sig.into(), updated_credential))
+		);
+		assert_eq!(CredentialStore::<T>::get(credential_id), Some((sig.into(), credential)));
 	}
 
 	delete_credential{
-		let s in 0 .. 100;
+		let r in 0 .. 100;
 
 		//create random schema_id
 		let schema_id = 123u32;
 		let credential_id: T::CredentialId = Default::default();
 		let public = Public::generate_pair(sp_core::testing::SR25519, None);
 		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
 		let account_id = format!("did:seneca:{:#?}", caller.clone());
-		let credential: VerifiableCredential<T::Moment> = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let credential: CredentialOf<T> = VerifiableCredential {
+			context: b"Credential context".to_vec().try_into().unwrap(),
 			schema: schema_id,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: account_id.clone().into_bytes().try_into().unwrap(),
 			issuance_date: Some(Default::default()),
 			expiration_date: Some(Default::default()),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
+					.to_vec()
+					.try_into()
+					.unwrap(),
+				claim: bounded_vec![
+					Claim {
+						property: b"property".to_vec().try_into().unwrap(),
+						value: b"value".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: b"name".to_vec().try_into().unwrap(),
+						value: b"0x1234".to_vec().try_into().unwrap(),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
+			credential_holder: account_id.clone().into_bytes().try_into().unwrap(),
 			nonce: 2u64,
 		};
-		let vf_schema: VerifiableCredentialSchema<T::Moment> = VerifiableCredentialSchema {
-			name: b"name".to_vec(),
-			creator: account_id.clone().into(),
+		let vf_schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"name".to_vec().try_into().unwrap(),
+			creator: account_id.clone().into_bytes().try_into().unwrap(),
 			public: false,
 			creation_date: Default::default(),
 			expiration_date: credential.expiration_date,
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: b"metadata".to_vec().try_into().unwrap(),
 			nonce: 2u64,
 		};
 
@@ -322,8 +527,8 @@ benchmarks! {
 
 		assert_ok!(SchemaRegistry::<T>::create_schema(
 			RawOrigin::Signed(caller.clone()).into(),
-			credential.schema.into(),
-			b"name".to_vec(),
+			Some(credential.schema.into()),
+			b"name".to_vec().try_into().unwrap(),
 			vf_schema.creator,
 			false,
 			vf_schema.mandatory_fields,
@@ -332,27 +537,245 @@ benchmarks! {
 			vf_schema.issuer_claims,
 			vf_schema.subject_claims,
 			vf_schema.credential_claims,
-			b"metadata".to_vec(),
+			b"metadata".to_vec().try_into().unwrap(),
 			schema_data_sig.into(),
 			vf_schema.nonce
 		));
 
 		assert_ok!(CredentialRegistry::<T>::create_credential(
 			RawOrigin::Signed(caller.clone()).into(), 
+			Some(credential_id.clone()),
+			credential.context.clone(),
+			credential.schema,
+			credential.schema_version,
+			credential.issuer.clone(),
+			credential.issuance_date,
+			credential.expiration_date,
+			credential.subject.clone(),
+			credential.credential_holder.clone(),
+			sig.clone().into(),
+			credential.nonce
+		));
+		accept_offer::<T>(&public, &caller, &credential_id, &credential);
+		// every accepted update leaves a revision in the history deleted with the credential
+		for revision in 0..r {
+			let updated = CredentialOf::<T> {
+				nonce: credential.nonce + 1 + revision as u64,
+				..credential.clone()
+			};
+			let updated_sig = public.sign(sp_core::testing::SR25519, &updated.encode()).unwrap();
+			assert_ok!(CredentialRegistry::<T>::update_credential(
+				RawOrigin::Signed(caller.clone()).into(),
+				credential_id.clone(),
+				(updated_sig.into(), updated.clone())
+			));
+			accept_offer::<T>(&public, &caller, &credential_id, &updated);
+		}
+	}:  _(RawOrigin::Signed(caller), credential_id.clone(), r)
+	verify {
+		assert_eq!(CredentialStore::<T>::get(credential_id), None);
+		assert_eq!(CredentialHistory::<T>::iter_prefix(credential_id).count(), 0);
+		assert!(!CredentialsBySchema::<T>::contains_key(schema_id, credential_id));
+		assert!(!CredentialsByHolder::<T>::contains_key(&credential.credential_holder, credential_id));
+		assert!(!CredentialsByIssuer::<T>::contains_key(&credential.issuer, credential_id));
+		assert!(!CredentialsBySubject::<T>::contains_key(&credential.subject.id, credential_id));
+	}
+	revoke_credential{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let (caller, credential_id, _) = setup_credential::<T>(&public);
+	}:  _(RawOrigin::Signed(caller), credential_id.clone(), 1u16)
+	verify {
+		assert_eq!(
+			CredentialStatuses::<T>::get(credential_id).map(|record| record.status),
+			Some(CredentialStatus::Revoked)
+		);
+	}
+
+	suspend_credential{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let (caller, credential_id, _) = setup_credential::<T>(&public);
+	}:  _(RawOrigin::Signed(caller), credential_id.clone(), 1u16)
+	verify {
+		assert_eq!(
+			CredentialStatuses::<T>::get(credential_id).map(|record| record.status),
+			Some(CredentialStatus::Suspended)
+		);
+	}
+
+	reinstate_credential{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let (caller, credential_id, _) = setup_credential::<T>(&public);
+		assert_ok!(CredentialRegistry::<T>::suspend_credential(
+			RawOrigin::Signed(caller.clone()).into(),
 			credential_id.clone(),
+			1u16
+		));
+	}:  _(RawOrigin::Signed(caller), credential_id.clone(), 0u16)
+	verify {
+		assert_eq!(
+			CredentialStatuses::<T>::get(credential_id).map(|record| record.status),
+			Some(CredentialStatus::Active)
+		);
+	}
+	accept_credential{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let (caller, credential_id, credential) = setup_credential::<T>(&public);
+		// accepting an update replaces the stored credential, the costlier acceptance
+		let credential = CredentialOf::<T> {
+			context: b"Updated context".to_vec().try_into().unwrap(),
+			nonce: credential.nonce + 1,
+			..credential
+		};
+		let sig = public.sign(sp_core::testing::SR25519, &credential.encode()).unwrap();
+		assert_ok!(CredentialRegistry::<T>::update_credential(
+			RawOrigin::Signed(caller.clone()).into(),
+			credential_id.clone(),
+			(sig.clone().into(), credential.clone())
+		));
+		let content_hash = sp_io::hashing::blake2_256(&credential.encode());
+		let acceptance = public.sign(sp_core::testing::SR25519, &content_hash).unwrap();
+	}:  _(RawOrigin::Signed(caller), credential_id.clone(), acceptance.into())
+	verify {
+		assert_eq!(CredentialStore::<T>::get(credential_id), Some((sig.into(), credential)));
+		assert!(!CredentialOffers::<T>::contains_key(credential_id));
+		assert_eq!(CredentialRevisions::<T>::get(credential_id), 1);
+	}
+
+	reject_credential{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let (caller, credential_id, credential) = setup_credential::<T>(&public);
+		assert_ok!(CredentialRegistry::<T>::delete_credential(
+			RawOrigin::Signed(caller.clone()).into(),
+			credential_id.clone(),
+			0
+		));
+		let credential = CredentialOf::<T> { nonce: credential.nonce + 1, ..credential };
+		let sig = public.sign(sp_core::testing::SR25519, &credential.encode()).unwrap();
+		assert_ok!(CredentialRegistry::<T>::create_credential(
+			RawOrigin::Signed(caller.clone()).into(),
+			Some(credential_id.clone()),
 			credential.context,
 			credential.schema,
+			credential.schema_version,
 			credential.issuer,
 			credential.issuance_date,
 			credential.expiration_date,
 			credential.subject,
 			credential.credential_holder,
-			sig.clone().into(),
+			sig.into(),
 			credential.nonce
 		));
 	}:  _(RawOrigin::Signed(caller), credential_id.clone())
 	verify {
-		assert_eq!(CredentialStore::<T>::get(credential_id), None);
+		assert!(!CredentialOffers::<T>::contains_key(credential_id));
+		assert_eq!(CredentialDeposits::<T>::get(credential_id), None);
+	}
+
+	create_credentials_batch{
+		let n in 1 .. T::MaxBatchSize::get();
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let (caller, _, credential) = setup_credential::<T>(&public);
+		let credentials: Vec<CredentialOf<T>> = (1..=n as u64)
+			.map(|i| CredentialOf::<T> { nonce: credential.nonce + i, ..credential.clone() })
+			.collect();
+		let leaves: Vec<[u8; 32]> = credentials
+			.iter()
+			.map(|credential| sp_io::hashing::blake2_256(&credential.encode()))
+			.collect();
+		let root = crate::merkle::root(&leaves);
+		let sig = public.sign(sp_core::testing::SR25519, &root).unwrap();
+		let credentials: BoundedVec<_, T::MaxBatchSize> = credentials.try_into().unwrap();
+	}:  _(RawOrigin::Signed(caller), credential.issuer.clone(), credentials, sig.into())
+	verify {
+		assert_eq!(
+			CredentialBatches::<T>::get(root).map(|(_, _, leaves)| leaves.len() as u32),
+			Some(n)
+		);
+		assert!(BatchDeposits::<T>::contains_key(root));
+		assert_eq!(CredentialOffers::<T>::iter().count() as u32, n);
+	}
+
+	add_delegate{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let issuer: DidOf<T> =
+			format!("did:seneca:{:#?}", caller.clone()).into_bytes().try_into().unwrap();
+		let delegate: T::AccountId = frame_benchmarking::account("delegate", 0, 0);
+		let schemas: BoundedVec<u32, T::MaxDelegatedSchemas> =
+			(0..T::MaxDelegatedSchemas::get()).collect::<Vec<_>>().try_into().unwrap();
+	}:  _(RawOrigin::Signed(caller), issuer.clone(), delegate.clone(), schemas, None, 10)
+	verify {
+		assert!(Delegations::<T>::contains_key(&issuer, &delegate));
+		assert!(DelegationDeposits::<T>::contains_key(&issuer, &delegate));
+	}
+
+	remove_delegate{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let issuer: DidOf<T> =
+			format!("did:seneca:{:#?}", caller.clone()).into_bytes().try_into().unwrap();
+		let delegate: T::AccountId = frame_benchmarking::account("delegate", 0, 0);
+		assert_ok!(CredentialRegistry::<T>::add_delegate(
+			RawOrigin::Signed(caller.clone()).into(),
+			issuer.clone(),
+			delegate.clone(),
+			bounded_vec![123u32],
+			None,
+			10
+		));
+	}:  _(RawOrigin::Signed(caller), issuer.clone(), delegate.clone())
+	verify {
+		assert!(!Delegations::<T>::contains_key(&issuer, &delegate));
+	}
+
+	reap_delegate{
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let issuer: DidOf<T> =
+			format!("did:seneca:{:#?}", caller.clone()).into_bytes().try_into().unwrap();
+		let delegate: T::AccountId = frame_benchmarking::account("delegate", 0, 0);
+		// a delegation allowing no credentials is spent from the start
+		assert_ok!(CredentialRegistry::<T>::add_delegate(
+			RawOrigin::Signed(caller.clone()).into(),
+			issuer.clone(),
+			delegate.clone(),
+			bounded_vec![123u32],
+			None,
+			0
+		));
+	}:  _(RawOrigin::Signed(caller), issuer.clone(), delegate.clone())
+	verify {
+		assert!(!DelegationDeposits::<T>::contains_key(&issuer, &delegate));
+	}
+
+	on_initialize{
+		let b in 1 .. T::MaxExpiriesPerBlock::get();
+		let e in 0 .. T::MaxExpiriesPerBlock::get();
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let (caller, _, credential) = setup_credential::<T>(&public);
+		let sig: T::Signature =
+			public.sign(sp_core::testing::SR25519, &credential.encode()).unwrap().into();
+		// the offers expire at the first of the `b` blocks looked at
+		for i in 1 ..= e {
+			assert_ok!(CredentialRegistry::<T>::offer_credential(
+				&T::CredentialId::from(i),
+				caller.clone(),
+				&sig,
+				&credential
+			));
+		}
+		let first = frame_system::Pallet::<T>::block_number() + T::OfferLifetime::get();
+		NextExpiryBlock::<T>::put(first);
+		let n = first + (b - 1).into();
+	}: { CredentialRegistry::<T>::on_initialize(n); }
+	verify {
+		assert_eq!(CredentialOffers::<T>::iter().count(), 0);
+		// a full first block is looked at again in the next one
+		let next = if e < T::MaxExpiriesPerBlock::get() { n + 1u32.into() } else { first };
+		assert_eq!(NextExpiryBlock::<T>::get(), Some(next));
 	}
 	impl_benchmark_test_suite!(CredentialRegistry, crate::mock::new_test_ext(), crate::mock::Test);
 }
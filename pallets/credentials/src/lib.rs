@@ -4,33 +4,80 @@
 mod mock;
 
 pub mod credential;
+pub mod merkle;
 #[cfg(test)]
 mod tests;
-mod types;
+pub mod types;
+#[cfg(feature = "std")]
+pub mod w3c;
 
 pub use pallet::*;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use crate::credential::Credential;
+	use crate::merkle;
 	use crate::types::*;
 	use crate::weights::WeightInfo;
 	use codec::HasCompact;
 	use frame_support::{
 		ensure,
 		pallet_prelude::*,
-		sp_runtime::traits::{IdentifyAccount, Member, Verify},
-		traits::IsType
+		sp_runtime::traits::{IdentifyAccount, Member, Saturating, Verify, Zero},
+		traits::{IsType, ReservableCurrency, Time},
+		CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
 	};
 	use frame_system::pallet_prelude::*;
-	use pallet_schemas::schema::SchemaInterface;
+	use node_primitives::did::{DidError, DidResolver};
+	use pallet_schemas::{
+		schema::SchemaInterface,
+		types::{BoundedDid, SchemaLimits},
+		BalanceOf,
+	};
 	use scale_info::prelude::vec::Vec;
 
+	/// Bounds of the credential types, taken from the pallet configuration.
+	#[derive(CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound)]
+	pub struct CredentialLimitsOf<T>(PhantomData<T>);
+
+	impl<T: Config> SchemaLimits for CredentialLimitsOf<T> {
+		type MaxNameLength = T::MaxNameLength;
+		type MaxDidLength = T::MaxDidLength;
+		type MaxClaims = T::MaxClaims;
+		type MaxValueLength = T::MaxValueLength;
+		type MaxMetadataLength = T::MaxMetadataLength;
+	}
+
+	impl<T: Config> CredentialLimits for CredentialLimitsOf<T> {
+		type MaxContextLength = T::MaxContextLength;
+		type MaxDelegatedSchemas = T::MaxDelegatedSchemas;
+	}
+
+	pub type CredentialOf<T> =
+		VerifiableCredential<<T as pallet_schemas::Config>::Moment, CredentialLimitsOf<T>>;
+	pub type SubjectOf<T> = Subject<CredentialLimitsOf<T>>;
+	pub type ClaimOpeningOf<T> = ClaimOpening<CredentialLimitsOf<T>>;
+	pub type PresentationOf<T> =
+		Presentation<<T as Config>::CredentialId, <T as pallet_schemas::Config>::Signature>;
+	pub type DidOf<T> = BoundedDid<CredentialLimitsOf<T>>;
+	pub type DelegationOf<T> =
+		Delegation<<T as pallet_schemas::Config>::Moment, CredentialLimitsOf<T>>;
+
+	/// The largest page of credentials returned by the credential queries.
+	pub const MAX_PAGE_SIZE: u32 = 100;
+
+	/// The first id assigned from the id counter, callers may only supply ids below it.
+	pub const FIRST_ASSIGNED_ID: u32 = 1 << 31;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
+
 	#[pallet::pallet]
-	#[pallet::without_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -55,8 +102,32 @@ pub mod pallet {
 			+ Ord
 			+ PartialOrd
 			+ MaxEncodedLen
+			+ From<u32>
 			+ TypeInfo;
 		type SchemaCheck: SchemaInterface;
+		/// The base deposit reserved for storing a credential.
+		#[pallet::constant]
+		type CredentialDepositBase: Get<BalanceOf<Self>>;
+		/// The deposit reserved per byte of a stored credential and its signature.
+		#[pallet::constant]
+		type CredentialDepositPerByte: Get<BalanceOf<Self>>;
+		/// The maximum length of a credential context.
+		#[pallet::constant]
+		type MaxContextLength: Get<u32>;
+		/// The number of blocks a holder has to accept a credential offered to them, must not be
+		/// zero.
+		#[pallet::constant]
+		type OfferLifetime: Get<Self::BlockNumber>;
+		/// The maximum number of credentials issued in a single batch.
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+		/// The maximum number of schemas a single issuer delegation covers.
+		#[pallet::constant]
+		type MaxDelegatedSchemas: Get<u32>;
+		/// The maximum number of expired credential offers dropped in a single block, and of
+		/// blocks looked at for them.
+		#[pallet::constant]
+		type MaxExpiriesPerBlock: Get<u32>;
 	}
 
 	#[pallet::storage]
@@ -65,13 +136,202 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		T::CredentialId,
-		(T::Signature, VerifiableCredential<T::Moment>),
+		(T::Signature, CredentialOf<T>),
+		OptionQuery,
+	>;
+
+	/// Layout that credentials migrated from earlier storage versions were signed in, until
+	/// their issuer updates them. Other credentials are signed in their current encoding.
+	#[pallet::storage]
+	pub type LegacyEncodings<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CredentialId, LegacyEncoding, OptionQuery>;
+
+	/// Credentials offered to their holder and not accepted yet, with the block the offer expires
+	/// at and the account whose key signed the credential.
+	#[pallet::storage]
+	#[pallet::getter(fn credential_offer)]
+	pub type CredentialOffers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::CredentialId,
+		(T::Signature, CredentialOf<T>, T::BlockNumber, T::AccountId),
+		OptionQuery,
+	>;
+
+	/// Index of pending credential offers by the block they expire at.
+	#[pallet::storage]
+	pub type OfferExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		T::CredentialId,
+		(),
+		OptionQuery,
+	>;
+
+	/// First block whose expiring credential offers have not all been dropped yet.
+	#[pallet::storage]
+	pub type NextExpiryBlock<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Credential batches by the Merkle root their issuer signed, with the issuer DID and the
+	/// leaves of the tree.
+	#[pallet::storage]
+	#[pallet::getter(fn credential_batch)]
+	pub type CredentialBatches<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		[u8; 32],
+		(T::Signature, DidOf<T>, BoundedVec<[u8; 32], T::MaxBatchSize>),
+		OptionQuery,
+	>;
+
+	/// Batch root and position in the batch of credentials issued in a batch and not updated
+	/// since.
+	#[pallet::storage]
+	pub type BatchedCredentials<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CredentialId, ([u8; 32], u32), OptionQuery>;
+
+	/// Account and amount of the deposit reserved for each credential batch, with the number of
+	/// its credentials still offered or stored under it. The batch is dropped with the last one.
+	#[pallet::storage]
+	pub type BatchDeposits<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		[u8; 32],
+		(T::AccountId, BalanceOf<T>, u32),
+		OptionQuery,
+	>;
+
+	/// Delegate accounts allowed to issue credentials on behalf of each issuer DID.
+	#[pallet::storage]
+	#[pallet::getter(fn delegation)]
+	pub type Delegations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DidOf<T>,
+		Blake2_128Concat,
+		T::AccountId,
+		DelegationOf<T>,
+		OptionQuery,
+	>;
+
+	/// Account and amount of the deposit reserved for each delegation.
+	#[pallet::storage]
+	pub type DelegationDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DidOf<T>,
+		Blake2_128Concat,
+		T::AccountId,
+		(T::AccountId, BalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// Delegate that signed each credential issued on behalf of its issuer DID, until the issuer
+	/// updates it.
+	#[pallet::storage]
+	#[pallet::getter(fn credential_delegate)]
+	pub type CredentialDelegates<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CredentialId, T::AccountId, OptionQuery>;
+
+	/// Account whose key signed each stored credential, the assertion key of its issuer DID or a
+	/// delegate of it when it was offered. The signature stays valid when the issuer rotates its
+	/// key afterwards.
+	#[pallet::storage]
+	#[pallet::getter(fn credential_signer)]
+	pub type CredentialSigners<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CredentialId, T::AccountId, OptionQuery>;
+
+	/// Counter the ids of credentials created without a caller supplied id are taken from,
+	/// offset by `FIRST_ASSIGNED_ID`.
+	#[pallet::storage]
+	pub type NextCredentialId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Index of credential ids by the schema they were issued against.
+	#[pallet::storage]
+	pub type CredentialsBySchema<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u32,
+		Blake2_128Concat,
+		T::CredentialId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Index of credential ids by the DID of their holder.
+	#[pallet::storage]
+	pub type CredentialsByHolder<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DidOf<T>,
+		Blake2_128Concat,
+		T::CredentialId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Index of credential ids by the DID of their issuer.
+	#[pallet::storage]
+	pub type CredentialsByIssuer<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DidOf<T>,
+		Blake2_128Concat,
+		T::CredentialId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Index of credential ids by the id of their subject.
+	#[pallet::storage]
+	pub type CredentialsBySubject<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DidOf<T>,
+		Blake2_128Concat,
+		T::CredentialId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Revocation registry entry of each credential.
+	#[pallet::storage]
+	pub type CredentialStatuses<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::CredentialId,
+		CredentialStatusRecord<T::BlockNumber, T::Moment>,
 		OptionQuery,
 	>;
 
+	/// Account and amount of the deposit reserved for each credential.
+	#[pallet::storage]
+	pub type CredentialDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CredentialId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// Next nonce expected in a credential signed by each issuer DID.
 	#[pallet::storage]
 	#[pallet::getter(fn get_nonce)]
-	pub(super) type Nonce<T: Config> = StorageValue<_, u64, ValueQuery>;
+	pub(super) type Nonce<T: Config> = StorageMap<_, Blake2_128Concat, DidOf<T>, u64, ValueQuery>;
+
+	/// Number of updates applied to each credential.
+	#[pallet::storage]
+	pub type CredentialRevisions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::CredentialId, u32, ValueQuery>;
+
+	/// Update history of each credential, keyed by the revision the update produced.
+	#[pallet::storage]
+	pub type CredentialHistory<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::CredentialId,
+		Twox64Concat,
+		u32,
+		UpdateRecord<T::BlockNumber, T::Moment>,
+		OptionQuery,
+	>;
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/main-docs/build/events-errors/
@@ -80,29 +340,168 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// Event documentation should end with an array that provides descriptive names for event
 		/// parameters. [something, who]
-		// Event is emitted when a Credential item is created
-		CredentialCreated(T::CredentialId, Vec<u8>),
-		// Event is emitted when an existing credential item is updated
-		CredentialUpdated(T::CredentialId, Vec<u8>),
+		// Event is emitted when a credential, or an update of a stored one, is offered to its
+		// holder, who accepts it by signing `content_hash`, the Blake2-256 hash of the encoded
+		// credential, before `expires_at`. `issuer` is the account that signed it, a delegate of
+		// the issuer DID if delegated
+		CredentialOffered {
+			credential_id: T::CredentialId,
+			issuer: T::AccountId,
+			holder_did: Vec<u8>,
+			content_hash: [u8; 32],
+			expires_at: T::BlockNumber,
+		},
+		// Event is emitted when a Credential item is created on acceptance by its holder,
+		// `content_hash` is the Blake2-256 hash of the encoded credential
+		CredentialCreated {
+			credential_id: T::CredentialId,
+			issuer: T::AccountId,
+			holder_did: Vec<u8>,
+			schema_id: u32,
+			schema_version: u32,
+			content_hash: [u8; 32],
+			moment: T::Moment,
+		},
+		// Event is emitted when an update of an existing credential item is accepted by its holder
+		CredentialUpdated {
+			credential_id: T::CredentialId,
+			issuer: T::AccountId,
+			holder_did: Vec<u8>,
+			schema_id: u32,
+			schema_version: u32,
+			content_hash: [u8; 32],
+			moment: T::Moment,
+		},
 		// Event is emitted when an existing Credential item is deleted
 		CredentialDeleted(T::CredentialId),
+		// Event is emitted when a credential is revoked [id, reason]
+		CredentialRevoked(T::CredentialId, u16),
+		// Event is emitted when a credential is suspended [id, reason]
+		CredentialSuspended(T::CredentialId, u16),
+		// Event is emitted when a suspended credential is reinstated [id, reason]
+		CredentialReinstated(T::CredentialId, u16),
+		// Event is emitted when a credential offer is rejected by the holder or withdrawn by
+		// the issuer
+		CredentialOfferRejected(T::CredentialId),
+		// Event is emitted when a credential offer expires without being accepted
+		CredentialOfferExpired(T::CredentialId),
+		// Event is emitted when a batch of credentials is offered to their holders under a single
+		// issuer signature over `root`, each credential is announced by `CredentialOffered`
+		CredentialBatchCreated { root: [u8; 32], issuer: T::AccountId, count: u32 },
+		// Event is emitted when an issuer DID allows a delegate to issue on its behalf
+		DelegateAdded { issuer_did: Vec<u8>, delegate: T::AccountId },
+		// Event is emitted when an issuer DID revokes the delegation of a delegate, or a spent
+		// delegation is reaped
+		DelegateRemoved { issuer_did: Vec<u8>, delegate: T::AccountId },
 	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
 	pub enum Error<T> {
-		/// Error names should be descriptive.
-		NoneValue,
 		/// Errors emitted when credential already exists.
 		CredentialAlreadyExists,
+		/// Error emitted when an update leaves the credential unchanged
+		CredentialUnchanged,
+		/// Error emitted when the credential schema doesn't exist
+		UnknownSchema,
 		///Error emitted when credential is unknown
 		UnknownCredential,
 		/// Error emitted when signature is invalid
 		SignatureVerifyError,
 		/// Error emitted when invalid DID is used
 		InvalidDID,
+		/// Error emitted when the DID does not encode a valid account
+		InvalidDidAccount,
+		/// Error emitted when the DID is deactivated
+		DidDeactivated,
+		/// Error emitted when the DID has no assertion key
+		NoAssertionKey,
 		/// Error emitted when credential issuer and origin don't match
-		NotCredentialOwner
+		NotCredentialOwner,
+		/// Error emitted when a revoked credential is modified or deleted
+		CredentialRevoked,
+		/// Error emitted when suspending a credential that is not active
+		CredentialNotActive,
+		/// Error emitted when reinstating a credential that is not suspended
+		CredentialNotSuspended,
+		/// Error emitted when the issuance date is ahead of on-chain time
+		IssuanceDateInFuture,
+		/// Error emitted when the expiration date is already in the past
+		ExpirationDateInPast,
+		/// Error emitted when a mandatory field of the schema has no matching claim
+		MissingMandatoryClaim,
+		/// Error emitted when a claim value does not match its schema attribute type
+		InvalidClaimValue,
+		/// Error emitted when a claim is not declared by the schema
+		UndeclaredClaim,
+		/// Error emitted when the nonce was already used by the credential issuer
+		StaleNonce,
+		/// Error emitted when an update changes the credential issuer
+		IssuerChanged,
+		/// Error emitted when an update changes the holder who accepted the credential
+		HolderChanged,
+		/// Error emitted when the credential offer is unknown
+		UnknownOffer,
+		/// Error emitted when accepting a credential offer after it expired
+		OfferExpired,
+		/// Error emitted when an offer is rejected by neither its holder, its issuer nor the
+		/// account that made it
+		NotCredentialHolder,
+		/// Error emitted when a credential batch has no credentials
+		EmptyBatch,
+		/// Error emitted when the delegate is already allowed to issue for the issuer DID
+		DelegateAlreadyAdded,
+		/// Error emitted when the delegation is unknown
+		UnknownDelegate,
+		/// Error emitted when a delegate issues against a schema outside its delegation
+		SchemaNotDelegated,
+		/// Error emitted when a delegate issues after its delegation expired
+		DelegationExpired,
+		/// Error emitted when a delegate issued as many credentials as its delegation allows
+		DelegationExhausted,
+		/// Error emitted when the id counter has assigned every id above `FIRST_ASSIGNED_ID`, an id
+		/// has to be supplied instead
+		NoFreeCredentialId,
+		/// Error emitted when reaping a delegation that has neither expired nor run out
+		DelegationActive,
+		/// Error emitted when deleting a credential with fewer `revisions` than it went through
+		InvalidRevisionCount,
+		/// Error emitted when a caller supplies an id reserved for the id counter
+		CredentialIdReserved,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// Offers are only dropped once their lifetime is over, without one they would never be
+		fn integrity_test() {
+			assert!(!T::OfferLifetime::get().is_zero(), "`OfferLifetime` must not be zero");
+		}
+
+		// Drop the credential offers expiring up to block `n` and release their deposits, at
+		// most `MaxExpiriesPerBlock` of them. Offers left over are dropped in the following
+		// blocks, they cannot be accepted in the meantime.
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let max = T::MaxExpiriesPerBlock::get();
+			let mut block = NextExpiryBlock::<T>::get().unwrap_or(n);
+			let (mut blocks, mut expired) = (0u32, 0u32);
+			while block <= n && blocks < max && expired < max {
+				blocks += 1;
+				let room = max - expired;
+				let ids: Vec<T::CredentialId> =
+					OfferExpiries::<T>::iter_key_prefix(block).take(room as usize).collect();
+				for id in ids.iter() {
+					Self::remove_offer(id);
+					Self::deposit_event(Event::CredentialOfferExpired(*id));
+				}
+				expired += ids.len() as u32;
+				// the block may hold more offers than there was room for
+				if (ids.len() as u32) < room {
+					block = block.saturating_add(1u32.into());
+				}
+			}
+			NextExpiryBlock::<T>::put(block);
+			T::CredentialsWeightInfo::on_initialize(blocks, expired)
+		}
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -110,39 +509,58 @@ pub mod pallet {
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Create a new credential item
+		/// Offer a new credential item to its holder, it is stored once the holder accepts it
+		/// with `accept_credential`. Without an `id` the next free one is assigned and returned
+		/// in the `CredentialOffered` event, supplying it is meant for migrating existing
+		/// credentials.
 		#[pallet::call_index(2)]
 		#[pallet::weight(T::CredentialsWeightInfo::create_credential())]
 		pub fn create_credential(
 			origin: OriginFor<T>,
-			#[pallet::compact] id: T::CredentialId,
-			context: Vec<u8>,
+			id: Option<T::CredentialId>,
+			context: BoundedVec<u8, T::MaxContextLength>,
 			schema: u32,
-			issuer: Vec<u8>,
+			schema_version: u32,
+			issuer: DidOf<T>,
 			issuance_date: Option<T::Moment>,
 			expiration_date: Option<T::Moment>,
-			subject: Subject,
-			credential_holder: Vec<u8>,
+			subject: SubjectOf<T>,
+			credential_holder: DidOf<T>,
 			signature: T::Signature,
 			nonce: u64,
 		) -> DispatchResult {
 			// Ensure that the caller of the function is signed
 			let origin = ensure_signed(origin)?;
-			// ensure credential issuer is the same as the origin of the extrinsic
-			let credential_creator = Self::split_publickey_from_did(&issuer)?;
-			ensure!(credential_creator == origin, Error::<T>::NotCredentialOwner);
-			let schema_id = T::SchemaCheck::to_schema_id(&schema);
-			//Ensure schema id exists
+			// ensure the origin of the extrinsic is the credential issuer or a delegate of it
+			let delegate = Self::ensure_issuer_or_delegate(&origin, &issuer, schema)?;
+			Self::ensure_issuable(&schema, schema_version, &issuer, &subject)?;
+			let id = match id {
+				Some(id) => {
+					ensure!(
+						id < T::CredentialId::from(FIRST_ASSIGNED_ID),
+						Error::<T>::CredentialIdReserved
+					);
+					id
+				},
+				None => Self::next_credential_id()?,
+			};
+			// Ensure that the Credential does not already exist or is not already offered
 			ensure!(
-				T::SchemaCheck::check_schema_id_exists(schema_id).is_ok(),
-				"Schema does not exist"
+				!CredentialStore::<T>::contains_key(&id) &&
+					!CredentialOffers::<T>::contains_key(&id),
+				Error::<T>::CredentialAlreadyExists
 			);
-			// Ensure that the Credential does not already exist
-			ensure!(!CredentialStore::<T>::contains_key(&id), "Credential already exists");
+			// Ensure the credential dates are consistent with on-chain time
+			Self::ensure_valid_dates(issuance_date, expiration_date)?;
+			Self::ensure_fresh_nonce(&issuer, nonce)?;
+			if let Some(delegate) = delegate {
+				CredentialDelegates::<T>::insert(&id, delegate);
+			}
 			Self::create_verifiable_credential(
 				&id,
 				&context,
 				&schema,
+				&schema_version,
 				&issuer,
 				issuance_date,
 				expiration_date,
@@ -153,13 +571,15 @@ pub mod pallet {
 			)
 		}
 
-		// Function to update an existing credential
+		// Function to offer an update of an existing credential to its holder, the stored
+		// credential is replaced once the holder accepts it with `accept_credential`. A pending
+		// update is replaced by a newer one.
 		#[pallet::call_index(4)]
 		#[pallet::weight(T::CredentialsWeightInfo::update_credential())]
 		pub fn update_credential(
 			origin: OriginFor<T>,
 			#[pallet::compact] old_credential_key: T::CredentialId,
-			new_data: (T::Signature, VerifiableCredential<T::Moment>),
+			new_data: (T::Signature, CredentialOf<T>),
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			// fetch credential from credential store
@@ -168,18 +588,46 @@ pub mod pallet {
 			// ensure credential creator is the one updating the credential
 			let credential_creator = Self::split_publickey_from_did(&credential_data.1.issuer)?;
 			ensure!(credential_creator == origin, Error::<T>::NotCredentialOwner);
-			ensure!(credential_data != new_data, Error::<T>::CredentialAlreadyExists);
+			ensure!(credential_data.1.issuer == new_data.1.issuer, Error::<T>::IssuerChanged);
+			ensure!(
+				credential_data.1.credential_holder == new_data.1.credential_holder,
+				Error::<T>::HolderChanged
+			);
+			ensure!(credential_data != new_data, Error::<T>::CredentialUnchanged);
+			// revoked credentials are frozen
+			ensure!(
+				Self::status_of(&old_credential_key) != CredentialStatus::Revoked,
+				Error::<T>::CredentialRevoked
+			);
+			Self::ensure_valid_dates(new_data.1.issuance_date, new_data.1.expiration_date)?;
+			T::SchemaCheck::check_schema_version(
+				T::SchemaCheck::to_schema_id(&new_data.1.schema),
+				new_data.1.schema_version,
+			)?;
+			T::SchemaCheck::check_issuer_authorized(
+				T::SchemaCheck::to_schema_id(&new_data.1.schema),
+				&new_data.1.issuer,
+			)?;
+			Self::ensure_valid_claims(
+				&new_data.1.schema,
+				new_data.1.schema_version,
+				&new_data.1.subject,
+			)?;
+			Self::ensure_fresh_nonce(&new_data.1.issuer, new_data.1.nonce)?;
 			// Update the credential data
 			Self::update_verifiable_credential(&old_credential_key, &new_data)
 		}
 
-		// Function to delete an existing credential
+		// Function to delete an existing credential, revoked credentials are kept so verifiers can
+		// tell they were revoked. The call is weighed by `revisions`, which has to cover every
+		// revision kept in the credential history.
 		#[pallet::call_index(6)]
-		#[pallet::weight(T::CredentialsWeightInfo::delete_credential())]
+		#[pallet::weight(T::CredentialsWeightInfo::delete_credential(*revisions))]
 		pub fn delete_credential(
 			origin: OriginFor<T>,
 			#[pallet::compact] key: T::CredentialId,
-		) -> DispatchResult {
+			revisions: u32,
+		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 			// fetch credential from credential store
 			let credential_data =
@@ -187,27 +635,323 @@ pub mod pallet {
 			// ensure credential creator is the one updating the credential
 			let credential_creator = Self::split_publickey_from_did(&credential_data.1.issuer)?;
 			ensure!(credential_creator == origin, Error::<T>::NotCredentialOwner);
-			Self::delete_verifiable_credential(&key)
+			ensure!(
+				Self::status_of(&key) != CredentialStatus::Revoked,
+				Error::<T>::CredentialRevoked
+			);
+			let revision = CredentialRevisions::<T>::get(&key);
+			ensure!(revision <= revisions, Error::<T>::InvalidRevisionCount);
+			Self::delete_verifiable_credential(&key)?;
+			Ok(Some(T::CredentialsWeightInfo::delete_credential(revision)).into())
+		}
+
+		/// Permanently revoke a credential while keeping it in storage
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::CredentialsWeightInfo::revoke_credential())]
+		pub fn revoke_credential(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::CredentialId,
+			reason: u16,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_credential_issuer(&origin, &key)?;
+			ensure!(
+				Self::status_of(&key) != CredentialStatus::Revoked,
+				Error::<T>::CredentialRevoked
+			);
+			Self::set_status(&key, CredentialStatus::Revoked, reason);
+			Self::deposit_event(Event::CredentialRevoked(key, reason));
+			Ok(())
+		}
+
+		/// Temporarily suspend an active credential
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::CredentialsWeightInfo::suspend_credential())]
+		pub fn suspend_credential(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::CredentialId,
+			reason: u16,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_credential_issuer(&origin, &key)?;
+			ensure!(
+				Self::status_of(&key) == CredentialStatus::Active,
+				Error::<T>::CredentialNotActive
+			);
+			Self::set_status(&key, CredentialStatus::Suspended, reason);
+			Self::deposit_event(Event::CredentialSuspended(key, reason));
+			Ok(())
+		}
+
+		/// Reinstate a suspended credential
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::CredentialsWeightInfo::reinstate_credential())]
+		pub fn reinstate_credential(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::CredentialId,
+			reason: u16,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_credential_issuer(&origin, &key)?;
+			ensure!(
+				Self::status_of(&key) == CredentialStatus::Suspended,
+				Error::<T>::CredentialNotSuspended
+			);
+			Self::set_status(&key, CredentialStatus::Active, reason);
+			Self::deposit_event(Event::CredentialReinstated(key, reason));
+			Ok(())
+		}
+
+		/// Accept a credential, or an update of a stored one, offered to the holder. `signature`
+		/// is the holder's signature over the Blake2-256 hash of the encoded credential, anyone
+		/// may submit it on their behalf.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::CredentialsWeightInfo::accept_credential())]
+		pub fn accept_credential(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::CredentialId,
+			signature: T::Signature,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let (issuer_signature, credential, expires_at, signer) =
+				CredentialOffers::<T>::get(&key).ok_or(Error::<T>::UnknownOffer)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() < expires_at,
+				Error::<T>::OfferExpired
+			);
+			// the schema may have been deleted while the offer was pending
+			ensure!(
+				T::SchemaCheck::check_schema_id_exists(T::SchemaCheck::to_schema_id(
+					&credential.schema
+				))
+				.is_ok(),
+				Error::<T>::UnknownSchema
+			);
+			let holder = Self::split_publickey_from_did(&credential.credential_holder)?;
+			let content_hash = sp_io::hashing::blake2_256(&credential.encode());
+			Self::is_valid_signer(&content_hash, &signature, &holder)?;
+			let issuer = Self::split_publickey_from_did(&credential.issuer)?;
+			let replaced = CredentialStore::<T>::get(&key);
+			if replaced.is_some() {
+				// the credential may have been revoked while its update was pending
+				ensure!(
+					Self::status_of(&key) != CredentialStatus::Revoked,
+					Error::<T>::CredentialRevoked
+				);
+			}
+			CredentialOffers::<T>::remove(&key);
+			OfferExpiries::<T>::remove(expires_at, &key);
+			CredentialSigners::<T>::insert(&key, signer);
+			let new_data = (issuer_signature, credential);
+			match replaced {
+				Some((_, old_credential)) => {
+					Self::replace_credential(&key, &old_credential, &new_data);
+					// release the part of the deposit held for the replaced credential
+					if let Some((depositor, _)) = CredentialDeposits::<T>::get(&key) {
+						Self::hold_deposit(&key, &depositor, Self::deposit_for(&new_data))?;
+					}
+					Self::deposit_event(Event::CredentialUpdated {
+						credential_id: key,
+						issuer,
+						holder_did: new_data.1.credential_holder.to_vec(),
+						schema_id: new_data.1.schema,
+						schema_version: new_data.1.schema_version,
+						content_hash,
+						moment: T::Timestamp::now(),
+					});
+				},
+				None => {
+					CredentialStore::<T>::insert(&key, &new_data);
+					Self::index_credential(&key, &new_data.1);
+					Self::set_status(&key, CredentialStatus::Active, 0);
+					Self::deposit_event(Event::CredentialCreated {
+						credential_id: key,
+						issuer,
+						holder_did: new_data.1.credential_holder.to_vec(),
+						schema_id: new_data.1.schema,
+						schema_version: new_data.1.schema_version,
+						content_hash,
+						moment: T::Timestamp::now(),
+					});
+				},
+			}
+			Ok(())
+		}
+
+		/// Reject a credential offer as its holder, or withdraw it as its issuer or the delegate
+		/// that made it
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::CredentialsWeightInfo::reject_credential())]
+		pub fn reject_credential(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::CredentialId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let (_, credential, _, offered_by) =
+				CredentialOffers::<T>::get(&key).ok_or(Error::<T>::UnknownOffer)?;
+			ensure!(
+				offered_by == origin ||
+					[&credential.credential_holder, &credential.issuer].iter().any(|did| {
+						Self::split_publickey_from_did(did).ok().as_ref() == Some(&origin)
+					}),
+				Error::<T>::NotCredentialHolder
+			);
+			Self::remove_offer(&key);
+			Self::deposit_event(Event::CredentialOfferRejected(key));
+			Ok(())
+		}
+
+		/// Offer a batch of credentials to their holders under a single `signature` of the issuer,
+		/// or a delegate of it, over the Merkle root of the batch, see [`merkle`]. Each credential
+		/// is assigned the next free id and accepted by its holder with `accept_credential`, their
+		/// nonces have to increase through the batch. The batch holds a deposit of its own, released
+		/// once none of its credentials is offered or stored anymore.
+		#[pallet::call_index(12)]
+		#[pallet::weight(
+			T::CredentialsWeightInfo::create_credentials_batch(credentials.len() as u32)
+		)]
+		pub fn create_credentials_batch(
+			origin: OriginFor<T>,
+			issuer: DidOf<T>,
+			credentials: BoundedVec<CredentialOf<T>, T::MaxBatchSize>,
+			signature: T::Signature,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			ensure!(!credentials.is_empty(), Error::<T>::EmptyBatch);
+			let leaves = BoundedVec::<[u8; 32], T::MaxBatchSize>::truncate_from(
+				credentials
+					.iter()
+					.map(|credential| sp_io::hashing::blake2_256(&credential.encode()))
+					.collect(),
+			);
+			let root = merkle::root(&leaves);
+			Self::is_valid_signer(&root, &signature, &origin)?;
+			for (index, credential) in credentials.iter().enumerate() {
+				ensure!(credential.issuer == issuer, Error::<T>::NotCredentialOwner);
+				let delegate =
+					Self::ensure_issuer_or_delegate(&origin, &issuer, credential.schema)?;
+				Self::ensure_issuable(
+					&credential.schema,
+					credential.schema_version,
+					&credential.issuer,
+					&credential.subject,
+				)?;
+				Self::ensure_valid_dates(credential.issuance_date, credential.expiration_date)?;
+				Self::ensure_fresh_nonce(&credential.issuer, credential.nonce)?;
+				let id = Self::next_credential_id()?;
+				Self::offer_credential(&id, origin.clone(), &signature, credential)?;
+				BatchedCredentials::<T>::insert(&id, (root, index as u32));
+				if let Some(delegate) = delegate {
+					CredentialDelegates::<T>::insert(&id, delegate);
+				}
+			}
+			let count = leaves.len() as u32;
+			let batch = (signature, issuer, leaves);
+			let deposit = Self::deposit_for(&batch);
+			T::Currency::reserve(&origin, deposit)?;
+			BatchDeposits::<T>::insert(root, (&origin, deposit, count));
+			CredentialBatches::<T>::insert(root, batch);
+			Self::deposit_event(Event::CredentialBatchCreated { root, issuer: origin, count });
+			Ok(())
+		}
+
+		/// Allow `delegate` to issue credentials on behalf of the `issuer` DID against `schemas`,
+		/// at most `max_credentials` of them and until `expiration_date` if one is given. A
+		/// deposit for the delegation is reserved from the issuer until it is removed or reaped.
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::CredentialsWeightInfo::add_delegate())]
+		pub fn add_delegate(
+			origin: OriginFor<T>,
+			issuer: DidOf<T>,
+			delegate: T::AccountId,
+			schemas: BoundedVec<u32, T::MaxDelegatedSchemas>,
+			expiration_date: Option<T::Moment>,
+			max_credentials: u32,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let issuer_account = Self::split_publickey_from_did(&issuer)?;
+			ensure!(issuer_account == origin, Error::<T>::NotCredentialOwner);
+			ensure!(
+				!Delegations::<T>::contains_key(&issuer, &delegate),
+				Error::<T>::DelegateAlreadyAdded
+			);
+			let delegation = Delegation { schemas, expiration_date, max_credentials, issued: 0 };
+			let deposit = Self::deposit_for(&delegation);
+			T::Currency::reserve(&origin, deposit)?;
+			DelegationDeposits::<T>::insert(&issuer, &delegate, (&origin, deposit));
+			Delegations::<T>::insert(&issuer, &delegate, delegation);
+			Self::deposit_event(Event::DelegateAdded { issuer_did: issuer.to_vec(), delegate });
+			Ok(())
+		}
+
+		/// Revoke the delegation of `delegate` by the `issuer` DID, the credentials it issued so
+		/// far are kept
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::CredentialsWeightInfo::remove_delegate())]
+		pub fn remove_delegate(
+			origin: OriginFor<T>,
+			issuer: DidOf<T>,
+			delegate: T::AccountId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let issuer_account = Self::split_publickey_from_did(&issuer)?;
+			ensure!(issuer_account == origin, Error::<T>::NotCredentialOwner);
+			ensure!(
+				Delegations::<T>::contains_key(&issuer, &delegate),
+				Error::<T>::UnknownDelegate
+			);
+			Self::drop_delegation(&issuer, &delegate);
+			Self::deposit_event(Event::DelegateRemoved { issuer_did: issuer.to_vec(), delegate });
+			Ok(())
+		}
+
+		/// Remove the delegation of `delegate` by the `issuer` DID once it expired or issued as
+		/// many credentials as it allows, releasing its deposit. Anyone may reap it.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::CredentialsWeightInfo::reap_delegate())]
+		pub fn reap_delegate(
+			origin: OriginFor<T>,
+			issuer: DidOf<T>,
+			delegate: T::AccountId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let delegation =
+				Delegations::<T>::get(&issuer, &delegate).ok_or(Error::<T>::UnknownDelegate)?;
+			let expired = delegation
+				.expiration_date
+				.map_or(false, |expiration_date| expiration_date < T::Timestamp::now());
+			ensure!(
+				expired || delegation.issued >= delegation.max_credentials,
+				Error::<T>::DelegationActive
+			);
+			Self::drop_delegation(&issuer, &delegate);
+			Self::deposit_event(Event::DelegateRemoved { issuer_did: issuer.to_vec(), delegate });
+			Ok(())
 		}
 	}
 
-	impl<T: Config> Credential<T::AccountId, T::Moment, T::Signature, T::CredentialId> for Pallet<T> {
-		// create a new credential
+	impl<T: Config>
+		Credential<T::AccountId, T::Moment, T::Signature, T::CredentialId, CredentialLimitsOf<T>>
+		for Pallet<T>
+	{
+		// offer a new credential to its holder
 		fn create_verifiable_credential(
 			id: &T::CredentialId,
-			context: &Vec<u8>,
+			context: &BoundedVec<u8, T::MaxContextLength>,
 			schema: &u32,
-			issuer: &Vec<u8>,
+			schema_version: &u32,
+			issuer: &DidOf<T>,
 			issuance_date: Option<T::Moment>,
 			expiration_date: Option<T::Moment>,
-			subject: &Subject,
-			credential_holder: &Vec<u8>,
+			subject: &SubjectOf<T>,
+			credential_holder: &DidOf<T>,
 			signature: &T::Signature,
 			nonce: &u64,
 		) -> DispatchResult {
-			let verifiable_credential = VerifiableCredential {
+			let verifiable_credential: CredentialOf<T> = VerifiableCredential {
 				context: context.clone(),
 				schema: schema.clone(),
+				schema_version: schema_version.clone(),
 				issuer: issuer.clone(),
 				issuance_date,
 				expiration_date,
@@ -217,33 +961,42 @@ pub mod pallet {
 			};
 			let binding = verifiable_credential.encode();
 			let vc_bytes = binding.as_slice();
-			let signer = Self::split_publickey_from_did(&verifiable_credential.issuer)?;
+			let signer = Self::signing_account(id, &verifiable_credential.issuer)?;
 			Self::is_valid_signer(vc_bytes, signature, &signer)?;
-			// Save the Schema data in storage
-			CredentialStore::<T>::insert(&id, (&signature, &verifiable_credential));
-			// Emit an event to indicate that the Credential was created and stored
-			Self::deposit_event(Event::CredentialCreated(
-				id.clone(),
-				verifiable_credential.encode(),
-			));
-			Ok(())
+			Self::offer_credential(id, signer, signature, &verifiable_credential)
 		}
-		// update a credential
+		// offer an update of a credential to its holder
 		fn update_verifiable_credential(
 			old_credential_key: &T::CredentialId,
-			new_data: &(T::Signature, VerifiableCredential<T::Moment>),
+			new_data: &(T::Signature, CredentialOf<T>),
 		) -> DispatchResult {
-			// Update the credential data
-			CredentialStore::<T>::insert(old_credential_key, new_data);
-			Self::deposit_event(Event::CredentialUpdated(
-				old_credential_key.clone(),
-				new_data.encode(),
-			));
-			Ok(())
+			let signer = Self::split_publickey_from_did(&new_data.1.issuer)?;
+			Self::is_valid_signer(&new_data.1.encode(), &new_data.0, &signer)?;
+			// a pending update is superseded by the new one
+			if CredentialOffers::<T>::contains_key(old_credential_key) {
+				Self::remove_offer(old_credential_key);
+			}
+			Self::offer_credential(old_credential_key, signer, &new_data.0, &new_data.1)
 		}
 		// delete a credential
 		fn delete_verifiable_credential(key: &T::CredentialId) -> DispatchResult {
-			<CredentialStore<T>>::remove(key);
+			if let Some((_, credential)) = <CredentialStore<T>>::take(key) {
+				Self::deindex_credential(key, &credential);
+			}
+			// drop an update offered for the credential, its deposit is released below
+			if let Some((_, _, expires_at, _)) = <CredentialOffers<T>>::take(key) {
+				<OfferExpiries<T>>::remove(expires_at, key);
+			}
+			<CredentialStatuses<T>>::remove(key);
+			if let Some((depositor, deposit)) = <CredentialDeposits<T>>::take(key) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			<CredentialRevisions<T>>::remove(key);
+			let _ = <CredentialHistory<T>>::clear_prefix(key, u32::MAX, None);
+			Self::leave_batch(key);
+			<CredentialDelegates<T>>::remove(key);
+			<CredentialSigners<T>>::remove(key);
+			<LegacyEncodings<T>>::remove(key);
 			Self::deposit_event(Event::CredentialDeleted(key.clone()));
 			Ok(())
 		}
@@ -254,75 +1007,589 @@ pub mod pallet {
 		}
 
 		fn split_publickey_from_did(did: &Vec<u8>) -> Result<T::AccountId, DispatchError> {
-			let did_string = match sp_std::str::from_utf8(did) {
-				Ok(did_str) => did_str,
-				Err(e) => {
-					log::error!("{:?}", e);
-					return Err(<Error<T>>::InvalidDID.into());
-				},
-			};
-			let did_vec: Vec<&str> = did_string.split(":").collect();
-			let public_key_str = did_vec[2].trim();
-			match node_primitives::convert2accountid::convert_string_to_accountid(public_key_str) {
-				Ok(account_id) => Ok(account_id),
-				Err(e) => Err(e),
-			}
+			<T as pallet_schemas::Config>::DidResolver::resolve(did).map_err(|e| {
+				log::error!("{:?}", e);
+				match e {
+					DidError::Deactivated => Error::<T>::DidDeactivated,
+					DidError::NoAssertionKey => Error::<T>::NoAssertionKey,
+					DidError::InvalidAccount(_) => Error::<T>::InvalidDidAccount,
+					_ => Error::<T>::InvalidDID,
+				}
+				.into()
+			})
+		}
+
+		// Verify credentials presented by their holder, see `verify_presented_credential`
+		fn verify_presentation(
+			presentation: &PresentationOf<T>,
+		) -> PresentationVerification<T::CredentialId> {
+			// the holder signs as its DID, for this chain only
+			let genesis_hash =
+				frame_system::Pallet::<T>::block_hash(T::BlockNumber::from(0u32));
+			let payload = presentation.payload(&genesis_hash);
+			let holder_keys = <T as pallet_schemas::Config>::DidResolver::authentication_keys(
+				&presentation.holder,
+			);
+			let holder_signature_valid = holder_keys.map_or(false, |keys| {
+				keys.iter().any(|key| presentation.signature.verify(&payload[..], key))
+			});
+			let credentials = presentation
+				.credential_ids
+				.iter()
+				.map(|id| Self::verify_presented_credential(id, &presentation.holder))
+				.collect();
+			PresentationVerification { holder_signature_valid, credentials }
 		}
 
 		// Fetch credentials by schemaid
 		fn get_credentials_by_schemaid(
 			schema_id: &u32,
-		) -> Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> {
-			let mut credentials: Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> =
-				Vec::new();
-			for (key, value) in CredentialStore::<T>::iter() {
-				if value.1.schema == *schema_id {
-					credentials.push((key, value.1.clone()));
-				}
-			}
-			credentials
+			start: Option<T::CredentialId>,
+			limit: u32,
+		) -> Vec<(T::CredentialId, CredentialOf<T>)> {
+			let ids = match start {
+				Some(start) => CredentialsBySchema::<T>::iter_key_prefix_from(
+					schema_id,
+					CredentialsBySchema::<T>::hashed_key_for(schema_id, start),
+				),
+				None => CredentialsBySchema::<T>::iter_key_prefix(schema_id),
+			};
+			Self::collect_page(ids, limit)
 		}
 
-		// Fetch credentials by subject
+		// Fetch credentials by subject id
 		fn get_credentials_by_subject(
-			subject: &Subject,
-		) -> Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> {
-			let mut credentials: Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> =
-				Vec::new();
-			for (key, value) in CredentialStore::<T>::iter() {
-				if value.1.subject == *subject {
-					credentials.push((key, value.1.clone()));
-				}
-			}
-			credentials
+			subject_id: &Vec<u8>,
+			start: Option<T::CredentialId>,
+			limit: u32,
+		) -> Vec<(T::CredentialId, CredentialOf<T>)> {
+			// DIDs exceeding the bound cannot have been indexed
+			let subject_id: DidOf<T> = match subject_id.clone().try_into() {
+				Ok(subject_id) => subject_id,
+				Err(_) => return Vec::new(),
+			};
+			let ids = match start {
+				Some(start) => CredentialsBySubject::<T>::iter_key_prefix_from(
+					&subject_id,
+					CredentialsBySubject::<T>::hashed_key_for(&subject_id, start),
+				),
+				None => CredentialsBySubject::<T>::iter_key_prefix(&subject_id),
+			};
+			Self::collect_page(ids, limit)
 		}
 
 		// Fetch credentials by holder
 		fn get_credentials_by_holder(
 			holder: &Vec<u8>,
-		) -> Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> {
-			let mut credentials: Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> =
-				Vec::new();
-			for (key, value) in CredentialStore::<T>::iter() {
-				if value.1.credential_holder == *holder {
-					credentials.push((key, value.1.clone()));
-				}
-			}
-			credentials
+			start: Option<T::CredentialId>,
+			limit: u32,
+		) -> Vec<(T::CredentialId, CredentialOf<T>)> {
+			let holder: DidOf<T> = match holder.clone().try_into() {
+				Ok(holder) => holder,
+				Err(_) => return Vec::new(),
+			};
+			let ids = match start {
+				Some(start) => CredentialsByHolder::<T>::iter_key_prefix_from(
+					&holder,
+					CredentialsByHolder::<T>::hashed_key_for(&holder, start),
+				),
+				None => CredentialsByHolder::<T>::iter_key_prefix(&holder),
+			};
+			Self::collect_page(ids, limit)
 		}
 
 		// Fetch credentials by creator
 		fn get_credentials_by_creator(
 			creator: &Vec<u8>,
-		) -> Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> {
-			let mut credentials: Vec<(T::CredentialId, VerifiableCredential<T::Moment>)> =
-				Vec::new();
-			for (key, value) in CredentialStore::<T>::iter() {
-				if value.1.issuer == *creator {
-					credentials.push((key, value.1.clone()));
+			start: Option<T::CredentialId>,
+			limit: u32,
+		) -> Vec<(T::CredentialId, CredentialOf<T>)> {
+			let creator: DidOf<T> = match creator.clone().try_into() {
+				Ok(creator) => creator,
+				Err(_) => return Vec::new(),
+			};
+			let ids = match start {
+				Some(start) => CredentialsByIssuer::<T>::iter_key_prefix_from(
+					&creator,
+					CredentialsByIssuer::<T>::hashed_key_for(&creator, start),
+				),
+				None => CredentialsByIssuer::<T>::iter_key_prefix(&creator),
+			};
+			Self::collect_page(ids, limit)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Revocation status of a credential, or `None` if it does not exist
+		pub fn credential_status(
+			id: &T::CredentialId,
+		) -> Option<CredentialStatusRecord<T::BlockNumber, T::Moment>> {
+			if !CredentialStore::<T>::contains_key(id) {
+				return None
+			}
+			Some(CredentialStatuses::<T>::get(id).unwrap_or(CredentialStatusRecord {
+				status: CredentialStatus::Active,
+				reason: 0,
+				block: Default::default(),
+				moment: Default::default(),
+			}))
+		}
+
+		/// Update history of a credential, oldest update first
+		pub fn credential_history(
+			id: &T::CredentialId,
+		) -> Vec<(u32, UpdateRecord<T::BlockNumber, T::Moment>)> {
+			let mut history: Vec<_> = CredentialHistory::<T>::iter_prefix(id).collect();
+			history.sort_by_key(|(revision, _)| *revision);
+			history
+		}
+
+		/// Whether a credential exists, is active and is within its validity period
+		pub fn is_credential_valid(id: &T::CredentialId) -> bool {
+			match CredentialStore::<T>::get(id) {
+				Some((_, credential)) =>
+					Self::status_of(id) == CredentialStatus::Active &&
+						Self::ensure_valid_dates(
+							credential.issuance_date,
+							credential.expiration_date,
+						)
+						.is_ok(),
+				None => false,
+			}
+		}
+
+		/// Check the claims revealed by a holder against the commitments of a valid credential
+		pub fn verify_disclosure(
+			id: &T::CredentialId,
+			openings: &[ClaimOpeningOf<T>],
+		) -> Result<(), DisclosureError> {
+			let (_, credential) =
+				CredentialStore::<T>::get(id).ok_or(DisclosureError::UnknownCredential)?;
+			ensure!(Self::is_credential_valid(id), DisclosureError::CredentialNotValid);
+			credential.subject.verify_disclosure(openings)
+		}
+
+		/// Proof that credential `id` was issued in a batch, or `None` if it was signed on its own
+		pub fn batch_proof(id: &T::CredentialId) -> Option<BatchProof> {
+			let (root, leaf_index) = BatchedCredentials::<T>::get(id)?;
+			let (_, _, leaves) = CredentialBatches::<T>::get(root)?;
+			Some(BatchProof {
+				root,
+				leaf_index,
+				leaf_count: leaves.len() as u32,
+				proof: merkle::proof(&leaves, leaf_index)?,
+			})
+		}
+
+		// Ensure `who` is the account of the `issuer` DID, or a delegate of it allowed to issue
+		// against `schema`, counting the credential against its delegation. Returns the delegate.
+		fn ensure_issuer_or_delegate(
+			who: &T::AccountId,
+			issuer: &DidOf<T>,
+			schema: u32,
+		) -> Result<Option<T::AccountId>, DispatchError> {
+			if Self::split_publickey_from_did(issuer)? == *who {
+				return Ok(None)
+			}
+			Delegations::<T>::try_mutate(issuer, who, |delegation| -> Result<_, DispatchError> {
+				let delegation = delegation.as_mut().ok_or(Error::<T>::NotCredentialOwner)?;
+				ensure!(delegation.schemas.contains(&schema), Error::<T>::SchemaNotDelegated);
+				if let Some(expiration_date) = delegation.expiration_date {
+					ensure!(expiration_date >= T::Timestamp::now(), Error::<T>::DelegationExpired);
 				}
+				ensure!(
+					delegation.issued < delegation.max_credentials,
+					Error::<T>::DelegationExhausted
+				);
+				delegation.issued = delegation.issued.saturating_add(1);
+				Ok(Some(who.clone()))
+			})
+		}
+
+		// Account that signed credential `id`, as recorded when it was accepted, otherwise its
+		// delegate or the account of its `issuer` DID
+		fn signing_account(
+			id: &T::CredentialId,
+			issuer: &DidOf<T>,
+		) -> Result<T::AccountId, DispatchError> {
+			match CredentialSigners::<T>::get(id).or_else(|| CredentialDelegates::<T>::get(id)) {
+				Some(signer) => Ok(signer),
+				None => Self::split_publickey_from_did(issuer),
 			}
-			credentials
+		}
+
+		// Ensure the schema version exists and is valid for issuance by `issuer`, and `subject`
+		// conforms to it
+		fn ensure_issuable(
+			schema: &u32,
+			schema_version: u32,
+			issuer: &DidOf<T>,
+			subject: &SubjectOf<T>,
+		) -> DispatchResult {
+			//Ensure schema id exists
+			ensure!(
+				T::SchemaCheck::check_schema_id_exists(T::SchemaCheck::to_schema_id(schema))
+					.is_ok(),
+				Error::<T>::UnknownSchema
+			);
+			// Ensure the schema version is still valid for issuance
+			T::SchemaCheck::check_schema_version(
+				T::SchemaCheck::to_schema_id(schema),
+				schema_version,
+			)?;
+			// Ensure the issuer may issue credentials against the schema
+			T::SchemaCheck::check_issuer_authorized(T::SchemaCheck::to_schema_id(schema), issuer)?;
+			// Ensure the subject claims conform to the schema version
+			Self::ensure_valid_claims(schema, schema_version, subject)
+		}
+
+		// Ensure the issuance date has passed and the expiration date has not
+		fn ensure_valid_dates(
+			issuance_date: Option<T::Moment>,
+			expiration_date: Option<T::Moment>,
+		) -> DispatchResult {
+			let now = T::Timestamp::now();
+			if let Some(issuance_date) = issuance_date {
+				ensure!(issuance_date <= now, Error::<T>::IssuanceDateInFuture);
+			}
+			if let Some(expiration_date) = expiration_date {
+				ensure!(expiration_date >= now, Error::<T>::ExpirationDateInPast);
+			}
+			Ok(())
+		}
+
+		// Ensure every mandatory field of the schema version is claimed with a well-typed value
+		// and every claim is declared by it
+		fn ensure_valid_claims(
+			schema: &u32,
+			version: u32,
+			subject: &SubjectOf<T>,
+		) -> DispatchResult {
+			let schema = T::SchemaCheck::get_schema(T::SchemaCheck::to_schema_id(schema), version)
+				.ok_or(Error::<T>::UnknownSchema)?;
+			for field in schema.mandatory_fields.iter() {
+				match subject.claim.iter().find(|claim| claim.property[..] == field.name[..]) {
+					Some(claim) => ensure!(
+						field.attribute_type.accepts(&claim.value),
+						Error::<T>::InvalidClaimValue
+					),
+					// The value of a committed claim can only be checked once it is disclosed
+					None => ensure!(
+						subject.committed_claims.iter().any(|c| c.property[..] == field.name[..]),
+						Error::<T>::MissingMandatoryClaim
+					),
+				}
+			}
+			let properties = subject
+				.claim
+				.iter()
+				.map(|claim| &claim.property)
+				.chain(subject.committed_claims.iter().map(|committed| &committed.property));
+			for property in properties {
+				ensure!(
+					schema.mandatory_fields.iter().any(|field| field.name[..] == property[..]) ||
+						schema.subject_claims.iter().any(|c| c.property[..] == property[..]),
+					Error::<T>::UndeclaredClaim
+				);
+			}
+			Ok(())
+		}
+
+		// Take the next id from the counter, counter ids start at `FIRST_ASSIGNED_ID` so callers
+		// cannot take them ahead of it
+		fn next_credential_id() -> Result<T::CredentialId, DispatchError> {
+			let next = NextCredentialId::<T>::get();
+			let id = FIRST_ASSIGNED_ID.checked_add(next).ok_or(Error::<T>::NoFreeCredentialId)?;
+			NextCredentialId::<T>::put(next.saturating_add(1));
+			Ok(T::CredentialId::from(id))
+		}
+
+		// Ensure `nonce` has not been used by `issuer` yet
+		fn ensure_fresh_nonce(issuer: &DidOf<T>, nonce: u64) -> DispatchResult {
+			ensure!(nonce >= Nonce::<T>::get(issuer), Error::<T>::StaleNonce);
+			Ok(())
+		}
+
+		// Deposit required to store `data`
+		fn deposit_for<V: Encode>(data: &V) -> BalanceOf<T> {
+			let bytes: BalanceOf<T> = (data.encoded_size() as u32).into();
+			T::CredentialDepositBase::get()
+				.saturating_add(T::CredentialDepositPerByte::get().saturating_mul(bytes))
+		}
+
+		// Hold `deposit` from `who` for credential `id`, adjusting or moving any deposit already
+		// held
+		fn hold_deposit(
+			id: &T::CredentialId,
+			who: &T::AccountId,
+			deposit: BalanceOf<T>,
+		) -> DispatchResult {
+			match CredentialDeposits::<T>::get(id) {
+				Some((depositor, held)) if depositor == *who =>
+					if deposit > held {
+						T::Currency::reserve(who, deposit - held)?;
+					} else {
+						T::Currency::unreserve(who, held - deposit);
+					},
+				previous => {
+					T::Currency::reserve(who, deposit)?;
+					if let Some((depositor, held)) = previous {
+						T::Currency::unreserve(&depositor, held);
+					}
+				},
+			}
+			CredentialDeposits::<T>::insert(id, (who, deposit));
+			Ok(())
+		}
+
+		// Current status of a credential, credentials without a record are active
+		fn status_of(id: &T::CredentialId) -> CredentialStatus {
+			CredentialStatuses::<T>::get(id)
+				.map(|record| record.status)
+				.unwrap_or(CredentialStatus::Active)
+		}
+
+		// Record a status change at the current block and moment
+		fn set_status(id: &T::CredentialId, status: CredentialStatus, reason: u16) {
+			CredentialStatuses::<T>::insert(
+				id,
+				CredentialStatusRecord {
+					status,
+					reason,
+					block: frame_system::Pallet::<T>::block_number(),
+					moment: T::Timestamp::now(),
+				},
+			);
+		}
+
+		// Ensure `who` issued the stored credential `id`
+		fn ensure_credential_issuer(who: &T::AccountId, id: &T::CredentialId) -> DispatchResult {
+			let (_, credential) =
+				CredentialStore::<T>::get(id).ok_or(Error::<T>::UnknownCredential)?;
+			let credential_creator = Self::split_publickey_from_did(&credential.issuer)?;
+			ensure!(credential_creator == *who, Error::<T>::NotCredentialOwner);
+			Ok(())
+		}
+
+		// Whether `signature` was made by the signer of credential `id` over it, in the layout it
+		// was issued in, or over the root of the batch it was issued in
+		fn is_issuer_signature(
+			id: &T::CredentialId,
+			signature: &T::Signature,
+			credential: &CredentialOf<T>,
+		) -> bool {
+			let issuer = match Self::signing_account(id, &credential.issuer) {
+				Ok(issuer) => issuer,
+				Err(_) => return false,
+			};
+			match BatchedCredentials::<T>::get(id) {
+				Some((root, index)) =>
+					CredentialBatches::<T>::get(root).map_or(false, |(_, _, leaves)| {
+						leaves.get(index as usize) ==
+							Some(&sp_io::hashing::blake2_256(&credential.encode()))
+					}) && signature.verify(&root[..], &issuer),
+				None => signature.verify(
+					&credential.signed_encoding(LegacyEncodings::<T>::get(id))[..],
+					&issuer,
+				),
+			}
+		}
+
+		// Check stored credential `id` as presented by the `holder` DID. The issuer signature of
+		// credentials migrated from earlier storage layouts is checked against the layout it was
+		// made over, recorded in `LegacyEncodings`.
+		fn verify_presented_credential(
+			id: &T::CredentialId,
+			holder: &[u8],
+		) -> CredentialVerification<T::CredentialId> {
+			match CredentialStore::<T>::get(id) {
+				Some((signature, credential)) => {
+					let status = Self::status_of(id);
+					CredentialVerification {
+						id: *id,
+						found: true,
+						issuer_signature_valid: Self::is_issuer_signature(
+							id,
+							&signature,
+							&credential,
+						),
+						holder_bound: credential.credential_holder[..] == holder[..],
+						not_expired: Self::ensure_valid_dates(
+							credential.issuance_date,
+							credential.expiration_date,
+						)
+						.is_ok(),
+						not_revoked: status != CredentialStatus::Revoked,
+						not_suspended: status != CredentialStatus::Suspended,
+						schema_valid: T::SchemaCheck::check_schema_version(
+							T::SchemaCheck::to_schema_id(&credential.schema),
+							credential.schema_version,
+						)
+						.is_ok(),
+					}
+				},
+				None => CredentialVerification {
+					id: *id,
+					found: false,
+					issuer_signature_valid: false,
+					holder_bound: false,
+					not_expired: false,
+					not_revoked: false,
+					not_suspended: false,
+					schema_valid: false,
+				},
+			}
+		}
+
+		// Hold the deposit for `credential` signed with `signature` by `signer` and offer it to its
+		// holder. An update offer also keeps the deposit of the stored credential it would replace
+		// held.
+		fn offer_credential(
+			id: &T::CredentialId,
+			signer: T::AccountId,
+			signature: &T::Signature,
+			credential: &CredentialOf<T>,
+		) -> DispatchResult {
+			let mut deposit = Self::deposit_for(&(signature, credential));
+			if let Some(stored) = CredentialStore::<T>::get(id) {
+				deposit = deposit.max(Self::deposit_for(&stored));
+			}
+			Self::hold_deposit(id, &signer, deposit)?;
+			// Keep the credential pending until its holder accepts it
+			let expires_at =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::OfferLifetime::get());
+			CredentialOffers::<T>::insert(id, (signature, credential, expires_at, &signer));
+			OfferExpiries::<T>::insert(expires_at, id, ());
+			Nonce::<T>::insert(&credential.issuer, credential.nonce.saturating_add(1));
+			// Emit an event to indicate that the Credential was offered to its holder
+			Self::deposit_event(Event::CredentialOffered {
+				credential_id: id.clone(),
+				issuer: signer,
+				holder_did: credential.credential_holder.to_vec(),
+				content_hash: sp_io::hashing::blake2_256(&credential.encode()),
+				expires_at,
+			});
+			Ok(())
+		}
+
+		// Drop a pending credential offer and release its deposit, handing the credential back to
+		// the delegation it was counted against. Dropping an update offer keeps the stored
+		// credential and the deposit held for it.
+		fn remove_offer(id: &T::CredentialId) {
+			let offer = CredentialOffers::<T>::take(id);
+			if let Some((_, _, expires_at, _)) = &offer {
+				OfferExpiries::<T>::remove(expires_at, id);
+			}
+			if let Some(stored) = CredentialStore::<T>::get(id) {
+				if let Some((depositor, _)) = CredentialDeposits::<T>::get(id) {
+					// only releases, the held deposit covers the stored credential
+					let _ = Self::hold_deposit(id, &depositor, Self::deposit_for(&stored));
+				}
+				return
+			}
+			Self::leave_batch(id);
+			if let (Some((_, credential, _, _)), Some(delegate)) =
+				(offer, CredentialDelegates::<T>::take(id))
+			{
+				Delegations::<T>::mutate(&credential.issuer, &delegate, |delegation| {
+					if let Some(delegation) = delegation {
+						delegation.issued = delegation.issued.saturating_sub(1);
+					}
+				});
+			}
+			if let Some((depositor, deposit)) = CredentialDeposits::<T>::take(id) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+		}
+
+		// Replace stored credential `id` by an update its holder accepted, moving its index
+		// entries and recording the replaced credential in its history
+		fn replace_credential(
+			id: &T::CredentialId,
+			old_credential: &CredentialOf<T>,
+			new_data: &(T::Signature, CredentialOf<T>),
+		) {
+			Self::deindex_credential(id, old_credential);
+			let revision = CredentialRevisions::<T>::mutate(id, |revision| {
+				*revision = revision.saturating_add(1);
+				*revision
+			});
+			CredentialHistory::<T>::insert(
+				id,
+				revision,
+				UpdateRecord {
+					previous_hash: sp_io::hashing::blake2_256(&old_credential.encode()),
+					block: frame_system::Pallet::<T>::block_number(),
+					moment: T::Timestamp::now(),
+				},
+			);
+			CredentialStore::<T>::insert(id, new_data);
+			// the credential is now signed on its own by the issuer, in the current layout
+			Self::leave_batch(id);
+			CredentialDelegates::<T>::remove(id);
+			LegacyEncodings::<T>::remove(id);
+			Self::index_credential(id, &new_data.1);
+		}
+
+		// Remove the delegation of `delegate` by `issuer` and release its deposit
+		fn drop_delegation(issuer: &DidOf<T>, delegate: &T::AccountId) {
+			Delegations::<T>::remove(issuer, delegate);
+			if let Some((depositor, deposit)) = DelegationDeposits::<T>::take(issuer, delegate) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+		}
+
+		// Take credential `id` out of the batch it was issued in, dropping the batch and
+		// releasing its deposit once no credential is left in it
+		fn leave_batch(id: &T::CredentialId) {
+			let root = match BatchedCredentials::<T>::take(id) {
+				Some((root, _)) => root,
+				None => return,
+			};
+			let remaining = BatchDeposits::<T>::mutate(root, |entry| {
+				entry.as_mut().map(|(_, _, remaining)| {
+					*remaining = remaining.saturating_sub(1);
+					*remaining
+				})
+			});
+			if remaining == Some(0) {
+				if let Some((depositor, deposit, _)) = BatchDeposits::<T>::take(root) {
+					T::Currency::unreserve(&depositor, deposit);
+				}
+				CredentialBatches::<T>::remove(root);
+			}
+		}
+
+		// Add a credential to the schema, holder, issuer and subject indexes and count it as a
+		// reference to its schema
+		fn index_credential(id: &T::CredentialId, credential: &CredentialOf<T>) {
+			CredentialsBySchema::<T>::insert(credential.schema, id, ());
+			CredentialsByHolder::<T>::insert(&credential.credential_holder, id, ());
+			CredentialsByIssuer::<T>::insert(&credential.issuer, id, ());
+			CredentialsBySubject::<T>::insert(&credential.subject.id, id, ());
+			T::SchemaCheck::increment_references(T::SchemaCheck::to_schema_id(&credential.schema));
+		}
+
+		// Remove a credential from the schema, holder, issuer and subject indexes and release
+		// its schema reference
+		fn deindex_credential(id: &T::CredentialId, credential: &CredentialOf<T>) {
+			CredentialsBySchema::<T>::remove(credential.schema, id);
+			CredentialsByHolder::<T>::remove(&credential.credential_holder, id);
+			CredentialsByIssuer::<T>::remove(&credential.issuer, id);
+			CredentialsBySubject::<T>::remove(&credential.subject.id, id);
+			T::SchemaCheck::decrement_references(T::SchemaCheck::to_schema_id(&credential.schema));
+		}
+
+		// Resolve up to `limit` indexed ids, at most `MAX_PAGE_SIZE`, into their credentials
+		fn collect_page(
+			ids: impl Iterator<Item = T::CredentialId>,
+			limit: u32,
+		) -> Vec<(T::CredentialId, CredentialOf<T>)> {
+			ids.filter_map(|id| {
+				CredentialStore::<T>::get(&id).map(|(_, credential)| (id, credential))
+			})
+			.take(limit.min(MAX_PAGE_SIZE) as usize)
+			.collect()
 		}
 	}
 }
@@ -0,0 +1,75 @@
+//! Binary Merkle tree over Blake2-256 hashes, letting an issuer sign a batch of credentials once.
+//!
+//! The leaves are the Blake2-256 hashes of the SCALE encoded credentials in batch order. Inner
+//! nodes hash the concatenation of their two children and a node without a sibling is promoted
+//! to the next level unchanged, so a batch of a single credential has its hash as root.
+
+use scale_info::prelude::vec::Vec;
+use sp_io::hashing::blake2_256;
+
+pub type Hash = [u8; 32];
+
+/// Root of the tree over `leaves`, the zero hash if there are none.
+pub fn root(leaves: &[Hash]) -> Hash {
+	let mut level = leaves.to_vec();
+	while level.len() > 1 {
+		level = next_level(&level);
+	}
+	level.first().copied().unwrap_or_default()
+}
+
+/// Sibling hashes on the path from leaf `index` up to the root, `None` if there is no such leaf.
+pub fn proof(leaves: &[Hash], index: u32) -> Option<Vec<Hash>> {
+	let mut index = index as usize;
+	if index >= leaves.len() {
+		return None
+	}
+	let mut level = leaves.to_vec();
+	let mut proof = Vec::new();
+	while level.len() > 1 {
+		if let Some(sibling) = level.get(index ^ 1) {
+			proof.push(*sibling);
+		}
+		level = next_level(&level);
+		index /= 2;
+	}
+	Some(proof)
+}
+
+/// Whether `proof` shows `leaf` is leaf `index` of a tree of `leaf_count` leaves with `root`.
+pub fn verify(root: &Hash, leaf: &Hash, index: u32, leaf_count: u32, proof: &[Hash]) -> bool {
+	if index >= leaf_count {
+		return false
+	}
+	let (mut index, mut width) = (index, leaf_count);
+	let mut node = *leaf;
+	let mut siblings = proof.iter();
+	while width > 1 {
+		// the last node of a level with an odd width has no sibling
+		if index ^ 1 < width {
+			node = match siblings.next() {
+				Some(sibling) if index % 2 == 0 => hash_pair(&node, sibling),
+				Some(sibling) => hash_pair(sibling, &node),
+				None => return false,
+			};
+		}
+		index /= 2;
+		width = (width + 1) / 2;
+	}
+	siblings.next().is_none() && node == *root
+}
+
+// Hash each pair of nodes of `level` into the level above it
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+	level
+		.chunks(2)
+		.map(|pair| pair.get(1).map_or(pair[0], |right| hash_pair(&pair[0], right)))
+		.collect()
+}
+
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+	let mut pair = [0u8; 64];
+	pair[..32].copy_from_slice(left);
+	pair[32..].copy_from_slice(right);
+	blake2_256(&pair)
+}
@@ -0,0 +1,543 @@
+//! Storage migrations of the credentials pallet.
+
+/// Moves credentials from unbounded vectors to the bounded types of storage version 1.
+pub mod v1 {
+	use super::{v2, v4};
+	use crate::{
+		types::*, Config, CredentialDeposits, CredentialHistory, CredentialRevisions,
+		CredentialStatuses, CredentialsBySchema, Pallet,
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		pallet_prelude::*,
+		storage_alias,
+		traits::{OnRuntimeUpgrade, ReservableCurrency},
+	};
+	use pallet_schemas::migrations::v1::{bound_all, OldClaim};
+	use scale_info::prelude::vec::Vec;
+
+	/// Credential layout before storage version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OldVerifiableCredential<Moment> {
+		pub context: Vec<u8>,
+		pub schema: u32,
+		pub issuer: Vec<u8>,
+		pub issuance_date: Option<Moment>,
+		pub expiration_date: Option<Moment>,
+		pub subject: OldSubject,
+		pub credential_holder: Vec<u8>,
+		pub nonce: u64,
+	}
+
+	/// Subject layout before storage version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OldSubject {
+		pub id: Vec<u8>,
+		pub claim: Vec<OldClaim>,
+	}
+
+	impl<Moment> OldVerifiableCredential<Moment> {
+		pub fn bounded<L: CredentialLimits>(
+			self,
+		) -> Option<v2::OldVerifiableCredential<Moment, L>> {
+			Some(v2::OldVerifiableCredential {
+				context: self.context.try_into().ok()?,
+				schema: self.schema,
+				issuer: self.issuer.try_into().ok()?,
+				issuance_date: self.issuance_date,
+				expiration_date: self.expiration_date,
+				subject: v4::OldSubject {
+					id: self.subject.id.try_into().ok()?,
+					claim: bound_all(self.subject.claim, OldClaim::bounded)?,
+				},
+				credential_holder: self.credential_holder.try_into().ok()?,
+				nonce: self.nonce,
+			})
+		}
+	}
+
+	type OldCredential<T> = OldVerifiableCredential<<T as pallet_schemas::Config>::Moment>;
+
+	// Bounded credentials are stored in the layout of storage version 1
+	#[storage_alias]
+	type CredentialStore<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		<T as Config>::CredentialId,
+		(<T as pallet_schemas::Config>::Signature, v2::OldCredential<T>),
+		OptionQuery,
+	>;
+
+	// The DID keyed maps, left unbounded here so over-long keys decode
+	#[storage_alias]
+	type CredentialsByHolder<T: Config> = StorageDoubleMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		<T as Config>::CredentialId,
+		(),
+		OptionQuery,
+	>;
+	#[storage_alias]
+	type CredentialsByIssuer<T: Config> = StorageDoubleMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		<T as Config>::CredentialId,
+		(),
+		OptionQuery,
+	>;
+	#[storage_alias]
+	type CredentialsBySubject<T: Config> = StorageDoubleMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		<T as Config>::CredentialId,
+		(),
+		OptionQuery,
+	>;
+	#[storage_alias]
+	type Nonce<T: Config> = StorageMap<Pallet<T>, Blake2_128Concat, Vec<u8>, u64, ValueQuery>;
+
+	/// The nonce shared by every issuer before nonces were kept per DID.
+	pub mod shared {
+		use crate::{Config, Pallet};
+		use frame_support::{pallet_prelude::*, storage_alias};
+
+		#[storage_alias]
+		pub type Nonce<T: Config> = StorageValue<Pallet<T>, u64, ValueQuery>;
+	}
+
+	/// Translates stored credentials to their bounded form. Credentials exceeding the configured
+	/// bounds are removed together with their index entries, status, history and deposit, as are
+	/// nonces of over-long issuer DIDs. The shared nonce the DID keyed ones replaced is removed.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				log::info!("credentials storage is already at version 1, skipping migration");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			let mut dropped = Vec::new();
+			CredentialStore::<T>::translate::<(T::Signature, OldCredential<T>), _>(
+				|id, (signature, credential)| {
+					reads += 1;
+					writes += 1;
+					let bounded: Option<v2::OldCredential<T>> = credential.clone().bounded();
+					if bounded.is_none() {
+						log::warn!("removing credential {:?} exceeding the storage bounds", id);
+						dropped.push((id, credential));
+					}
+					bounded.map(|credential| (signature, credential))
+				},
+			);
+			for (id, credential) in dropped {
+				CredentialsBySchema::<T>::remove(credential.schema, id);
+				CredentialsByHolder::<T>::remove(&credential.credential_holder, id);
+				CredentialsByIssuer::<T>::remove(&credential.issuer, id);
+				CredentialsBySubject::<T>::remove(&credential.subject.id, id);
+				CredentialStatuses::<T>::remove(id);
+				if let Some((depositor, deposit)) = CredentialDeposits::<T>::take(id) {
+					T::Currency::unreserve(&depositor, deposit);
+				}
+				CredentialRevisions::<T>::remove(id);
+				let _ = CredentialHistory::<T>::clear_prefix(id, u32::MAX, None);
+				reads += 2;
+				writes += 9;
+			}
+
+			shared::Nonce::<T>::kill();
+			writes += 1;
+			let max_did_length = T::MaxDidLength::get() as usize;
+			let stale: Vec<Vec<u8>> = Nonce::<T>::iter_keys()
+				.filter(|issuer| issuer.len() > max_did_length)
+				.collect();
+			reads += stale.len() as u64;
+			writes += stale.len() as u64;
+			for issuer in stale {
+				Nonce::<T>::remove(issuer);
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok((CredentialStore::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let before = u32::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+			ensure!(StorageVersion::get::<Pallet<T>>() == 1, "credentials storage version not set");
+			ensure!(
+				CredentialStore::<T>::iter_keys().count() as u32 <= before,
+				"credentials added by the migration"
+			);
+			ensure!(!shared::Nonce::<T>::exists(), "shared nonce left in storage");
+			Ok(())
+		}
+	}
+}
+
+/// Records on stored credentials the schema version they were issued against, storage version 2.
+pub mod v2 {
+	use super::v4::{self, CredentialStore};
+	use crate::{types::*, Config, CredentialLimitsOf, LegacyEncodings, Pallet};
+	use codec::{Decode, Encode};
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use pallet_schemas::{schema::SchemaInterface, types::BoundedDid};
+	#[cfg(feature = "try-runtime")]
+	use scale_info::prelude::vec::Vec;
+
+	/// Credential layout before storage version 2.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(L))]
+	pub struct OldVerifiableCredential<Moment, L: CredentialLimits> {
+		pub context: BoundedVec<u8, L::MaxContextLength>,
+		pub schema: u32,
+		pub issuer: BoundedDid<L>,
+		pub issuance_date: Option<Moment>,
+		pub expiration_date: Option<Moment>,
+		pub subject: v4::OldSubject<L>,
+		pub credential_holder: BoundedDid<L>,
+		pub nonce: u64,
+	}
+
+	impl<Moment, L: CredentialLimits> OldVerifiableCredential<Moment, L> {
+		pub fn versioned(self, schema_version: u32) -> v4::OldVerifiableCredential<Moment, L> {
+			v4::OldVerifiableCredential {
+				context: self.context,
+				schema: self.schema,
+				schema_version,
+				issuer: self.issuer,
+				issuance_date: self.issuance_date,
+				expiration_date: self.expiration_date,
+				subject: self.subject,
+				credential_holder: self.credential_holder,
+				nonce: self.nonce,
+			}
+		}
+	}
+
+	pub type OldCredential<T> =
+		OldVerifiableCredential<<T as pallet_schemas::Config>::Moment, CredentialLimitsOf<T>>;
+
+	/// Pins every stored credential to the latest version of its schema, the only version that
+	/// existed before schemas were versioned. Stored signatures keep covering the encoding of
+	/// storage version 1, so the credentials are marked as [`LegacyEncoding::Unversioned`].
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 2 {
+				log::info!("credentials storage is already at version 2, skipping migration");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			CredentialStore::<T>::translate::<(T::Signature, OldCredential<T>), _>(
+				|id, (signature, credential)| {
+					reads += 2;
+					writes += 2;
+					LegacyEncodings::<T>::insert(id, LegacyEncoding::Unversioned);
+					let version = T::SchemaCheck::latest_version(T::SchemaCheck::to_schema_id(
+						&credential.schema,
+					));
+					Some((signature, credential.versioned(version)))
+				},
+			);
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok((CredentialStore::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let before = u32::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+			ensure!(StorageVersion::get::<Pallet<T>>() == 2, "credentials storage version not set");
+			ensure!(
+				CredentialStore::<T>::iter().count() as u32 == before,
+				"credentials lost by the migration"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Counts stored credentials as references to their schemas, storage version 3.
+pub mod v3 {
+	use super::v4::CredentialStore;
+	use crate::{Config, Pallet};
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use pallet_schemas::schema::SchemaInterface;
+	#[cfg(feature = "try-runtime")]
+	use scale_info::prelude::vec::Vec;
+
+	/// Records every stored credential as a reference to its schema, so schemas that still have
+	/// credentials can no longer be deleted.
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 3 {
+				log::info!("credentials storage is already at version 3, skipping migration");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			for (_, (_, credential)) in CredentialStore::<T>::iter() {
+				T::SchemaCheck::increment_references(T::SchemaCheck::to_schema_id(
+					&credential.schema,
+				));
+				reads += 2;
+				writes += 1;
+			}
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(StorageVersion::get::<Pallet<T>>() == 3, "credentials storage version not set");
+			Ok(())
+		}
+	}
+}
+
+/// Adds claim commitments to credential subjects, storage version 4.
+pub mod v4 {
+	use crate::{types::*, Config, CredentialLimitsOf, LegacyEncodings, Pallet};
+	use codec::{Decode, Encode};
+	use frame_support::{pallet_prelude::*, storage_alias, traits::OnRuntimeUpgrade};
+	use pallet_schemas::types::{BoundedClaims, BoundedDid, SchemaLimits};
+	#[cfg(feature = "try-runtime")]
+	use scale_info::prelude::vec::Vec;
+
+	/// Credential layout before storage version 4.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(L))]
+	pub struct OldVerifiableCredential<Moment, L: CredentialLimits> {
+		pub context: BoundedVec<u8, L::MaxContextLength>,
+		pub schema: u32,
+		pub schema_version: u32,
+		pub issuer: BoundedDid<L>,
+		pub issuance_date: Option<Moment>,
+		pub expiration_date: Option<Moment>,
+		pub subject: OldSubject<L>,
+		pub credential_holder: BoundedDid<L>,
+		pub nonce: u64,
+	}
+
+	/// Subject layout before storage version 4.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(L))]
+	pub struct OldSubject<L: SchemaLimits> {
+		pub id: BoundedDid<L>,
+		pub claim: BoundedClaims<L>,
+	}
+
+	impl<Moment, L: CredentialLimits> OldVerifiableCredential<Moment, L> {
+		pub fn committed(self) -> VerifiableCredential<Moment, L> {
+			VerifiableCredential {
+				context: self.context,
+				schema: self.schema,
+				schema_version: self.schema_version,
+				issuer: self.issuer,
+				issuance_date: self.issuance_date,
+				expiration_date: self.expiration_date,
+				subject: Subject {
+					id: self.subject.id,
+					claim: self.subject.claim,
+					committed_claims: BoundedVec::default(),
+				},
+				credential_holder: self.credential_holder,
+				nonce: self.nonce,
+			}
+		}
+	}
+
+	pub type OldCredential<T> =
+		OldVerifiableCredential<<T as pallet_schemas::Config>::Moment, CredentialLimitsOf<T>>;
+
+	// Credentials in the layout of storage versions 2 and 3
+	#[storage_alias]
+	pub type CredentialStore<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		<T as Config>::CredentialId,
+		(<T as pallet_schemas::Config>::Signature, OldCredential<T>),
+		OptionQuery,
+	>;
+
+	/// Gives every stored credential an empty list of claim commitments, all their claims having
+	/// been issued in clear. Stored signatures keep covering the encoding they were made over, so
+	/// credentials not marked by [`super::v2::MigrateToV2`] are marked as
+	/// [`LegacyEncoding::Uncommitted`].
+	pub struct MigrateToV4<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 4 {
+				log::info!("credentials storage is already at version 4, skipping migration");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			crate::CredentialStore::<T>::translate::<(T::Signature, OldCredential<T>), _>(
+				|id, (signature, credential)| {
+					reads += 2;
+					writes += 1;
+					if !LegacyEncodings::<T>::contains_key(id) {
+						LegacyEncodings::<T>::insert(id, LegacyEncoding::Uncommitted);
+						writes += 1;
+					}
+					Some((signature, credential.committed()))
+				},
+			);
+
+			StorageVersion::new(4).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok((CredentialStore::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let before = u32::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+			ensure!(StorageVersion::get::<Pallet<T>>() == 4, "credentials storage version not set");
+			ensure!(
+				crate::CredentialStore::<T>::iter().count() as u32 == before,
+				"credentials lost by the migration"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Indexes credentials stored before the schema, holder, issuer and subject indexes existed,
+/// storage version 5.
+pub mod v5 {
+	use crate::{
+		Config, CredentialStore, CredentialsByHolder, CredentialsByIssuer, CredentialsBySchema,
+		CredentialsBySubject, Pallet,
+	};
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	#[cfg(feature = "try-runtime")]
+	use scale_info::prelude::vec::Vec;
+
+	/// Adds every stored credential to the schema, holder, issuer and subject indexes. Their
+	/// schema references were counted by [`super::v3::MigrateToV3`] already.
+	pub struct MigrateToV5<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 5 {
+				log::info!("credentials storage is already at version 5, skipping migration");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			for (id, (_, credential)) in CredentialStore::<T>::iter() {
+				CredentialsBySchema::<T>::insert(credential.schema, id, ());
+				CredentialsByHolder::<T>::insert(&credential.credential_holder, id, ());
+				CredentialsByIssuer::<T>::insert(&credential.issuer, id, ());
+				CredentialsBySubject::<T>::insert(&credential.subject.id, id, ());
+				reads += 1;
+				writes += 4;
+			}
+
+			StorageVersion::new(5).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(StorageVersion::get::<Pallet<T>>() == 5, "credentials storage version not set");
+			for (id, (_, credential)) in CredentialStore::<T>::iter() {
+				ensure!(
+					CredentialsBySchema::<T>::contains_key(credential.schema, id) &&
+						CredentialsByHolder::<T>::contains_key(&credential.credential_holder, id) &&
+						CredentialsByIssuer::<T>::contains_key(&credential.issuer, id) &&
+						CredentialsBySubject::<T>::contains_key(&credential.subject.id, id),
+					"credential left out of the indexes"
+				);
+			}
+			Ok(())
+		}
+	}
+}
+
+/// Records the signer of credentials stored before `CredentialSigners` existed, storage
+/// version 6.
+pub mod v6 {
+	use crate::{Config, CredentialDelegates, CredentialSigners, CredentialStore, Pallet};
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use node_primitives::did::DidResolver;
+	#[cfg(feature = "try-runtime")]
+	use scale_info::prelude::vec::Vec;
+
+	/// Records the delegate that signed each stored credential, or the account its issuer DID
+	/// resolves to at the upgrade, so the issuer signature survives later key rotations.
+	/// Credentials whose issuer no longer resolves are left to the resolution at verification.
+	pub struct MigrateToV6<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 6 {
+				log::info!("credentials storage is already at version 6, skipping migration");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			for (id, (_, credential)) in CredentialStore::<T>::iter() {
+				let signer = CredentialDelegates::<T>::get(&id).or_else(|| {
+					<T as pallet_schemas::Config>::DidResolver::resolve(&credential.issuer).ok()
+				});
+				reads += 3;
+				if let Some(signer) = signer {
+					CredentialSigners::<T>::insert(&id, signer);
+					writes += 1;
+				}
+			}
+
+			StorageVersion::new(6).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(StorageVersion::get::<Pallet<T>>() == 6, "credentials storage version not set");
+			for (id, _) in CredentialStore::<T>::iter() {
+				ensure!(
+					CredentialSigners::<T>::contains_key(&id) ||
+						!CredentialDelegates::<T>::contains_key(&id),
+					"delegate signed credential left without a signer"
+				);
+			}
+			Ok(())
+		}
+	}
+}
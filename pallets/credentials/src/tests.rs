@@ -1,72 +1,99 @@
 use crate::mock::*;
 use crate::types::*;
-use codec::Encode;
-use frame_support::assert_ok;
+use crate::{
+	credential::Credential,
+	merkle,
+	migrations::{v1, v2, v3, v4, v5, v6},
+	w3c, BatchDeposits, ClaimOpeningOf, CredentialDelegates, CredentialDeposits,
+	CredentialLimitsOf, CredentialOf, CredentialOffers, CredentialStore, CredentialsByHolder,
+	CredentialsBySubject, DidOf, Error, LegacyEncodings, NextCredentialId, NextExpiryBlock,
+	OfferExpiries, PresentationOf, FIRST_ASSIGNED_ID, MAX_PAGE_SIZE,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok, bounded_vec,
+	storage::unhashed,
+	traits::{Hooks, OnRuntimeUpgrade, ReservableCurrency, StorageVersion},
+};
 use frame_system::RawOrigin;
-use pallet_schemas::types::{
-	Attribute, AttributeType, Claim, ClaimType, VerifiableCredentialSchema,
+use pallet_schemas::{
+	types::{Attribute, AttributeType, Claim, ClaimType},
+	SchemaOf, SchemaReferences,
 };
-use sp_core::Pair;
+use sp_core::{sr25519, Pair};
 use sp_runtime::traits::IdentifyAccount;
 
+// DID of Bob, the holder of the test credentials
+const HOLDER_DID: &[u8] = b"did:seneca:5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+
 #[test]
 fn it_works_for_create_credential() {
 	new_test_ext().execute_with(|| {
 		let account_pair = account_pair("Alice");
 		let signer = account_pair.public();
 		let account_id = format!("did:seneca:{}", account_pair.public().to_string());
-		let credential = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let credential = CredentialOf::<Test> {
+			context: bounded(b"Credential context"),
 			schema: 123456u32,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: bounded(account_id.as_bytes()),
 			issuance_date: Some(Timestamp::now()),
 			expiration_date: Some(1702379816u64),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: bounded(b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"),
+				claim: bounded_vec![
+					Claim {
+						property: bounded(b"property"),
+						value: bounded(b"value"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: bounded(b"name"),
+						value: bounded(b"0x1234"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
+			credential_holder: bounded(HOLDER_DID),
 			nonce: 2u64,
 		};
-		let vf_schema = VerifiableCredentialSchema {
-			name: b"name".to_vec(),
-			creator: account_id.clone().into(),
+		let vf_schema = SchemaOf::<Test> {
+			name: bounded(b"name"),
+			creator: bounded(account_id.as_bytes()),
 			public: false,
 			creation_date: Timestamp::now(),
 			expiration_date: credential.expiration_date,
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: bounded(b"metadata"),
 			nonce: 2u64,
 		};
 		let data_sig = account_pair.sign(&credential.encode());
@@ -75,8 +102,8 @@ fn it_works_for_create_credential() {
 		let schema_data_sig = account_pair.sign(&vc_bytes);
 		assert_ok!(SchemaRegistry::create_schema(
 			RawOrigin::Signed(signer).into(),
-			credential.schema,
-			b"name".to_vec(),
+			Some(credential.schema),
+			bounded(b"name"),
 			vf_schema.creator,
 			false,
 			vf_schema.mandatory_fields,
@@ -85,24 +112,32 @@ fn it_works_for_create_credential() {
 			vf_schema.issuer_claims,
 			vf_schema.subject_claims,
 			vf_schema.credential_claims,
-			b"metadata".to_vec(),
+			bounded(b"metadata"),
 			schema_data_sig,
 			credential.nonce
 		));
 		// Dispatch a signed create schema extrinsic.
 		assert_ok!(CredentialRegistry::create_credential(
 			RawOrigin::Signed(signer).into(),
-			credential.schema,
+			Some(credential.schema),
 			credential.context,
 			credential.schema,
+			credential.schema_version,
 			credential.issuer,
 			credential.issuance_date,
 			Some(1702379816u64),
-			credential.subject,
-			credential.credential_holder,
-			data_sig,
+			credential.subject.clone(),
+			credential.credential_holder.clone(),
+			data_sig.clone(),
 			credential.nonce
 		));
+		// the credential only exists once its holder accepts it
+		assert_eq!(CredentialRegistry::credential_registry(credential.schema), None);
+		accept_test_credential(credential.schema);
+		assert_eq!(
+			CredentialRegistry::credential_registry(credential.schema),
+			Some((data_sig, credential))
+		);
 	});
 }
 
@@ -113,78 +148,98 @@ fn it_works_for_update_credential() {
 		let account_pair = account_pair("Alice");
 		let signer = account_pair.public();
 		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
-		let credential = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let credential = CredentialOf::<Test> {
+			context: bounded(b"Credential context"),
 			schema: 123456u32,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: bounded(account_id.as_bytes()),
 			issuance_date: Some(Timestamp::now()),
 			expiration_date: Some(1702379816u64),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: bounded(b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"),
+				claim: bounded_vec![
+					Claim {
+						property: bounded(b"property"),
+						value: bounded(b"value"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: bounded(b"name"),
+						value: bounded(b"0x1234"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
+			credential_holder: bounded(HOLDER_DID),
 			nonce: 2u64,
 		};
-		let updated_credential = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let updated_credential = CredentialOf::<Test> {
+			context: bounded(b"Credential context"),
 			schema: 123456u32,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: bounded(account_id.as_bytes()),
 			issuance_date: Some(Timestamp::now()),
 			expiration_date: Some(1702379816u64),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: bounded(b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"),
+				claim: bounded_vec![
+					Claim {
+						property: bounded(b"property"),
+						value: bounded(b"value"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: bounded(b"name"),
+						value: bounded(b"0x1234"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
-			nonce: 2u64,
+			credential_holder: bounded(HOLDER_DID),
+			nonce: 3u64,
 		};
-		let vf_schema = VerifiableCredentialSchema {
-			name: b"name".to_vec(),
-			creator: account_id.clone().into(),
+		let vf_schema = SchemaOf::<Test> {
+			name: bounded(b"name"),
+			creator: bounded(account_id.as_bytes()),
 			public: false,
 			creation_date: Timestamp::now(),
 			expiration_date: credential.expiration_date,
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: bounded(b"metadata"),
 			nonce: 2u64,
 		};
 		let data_sig = account_pair.sign(&credential.encode());
@@ -195,8 +250,8 @@ fn it_works_for_update_credential() {
 		// Dispatch a signed create schema extrinsic.
 		assert_ok!(SchemaRegistry::create_schema(
 			RawOrigin::Signed(signer).into(),
-			credential.schema,
-			b"name".to_vec(),
+			Some(credential.schema),
+			bounded(b"name"),
 			vf_schema.creator,
 			false,
 			vf_schema.mandatory_fields,
@@ -205,15 +260,16 @@ fn it_works_for_update_credential() {
 			vf_schema.issuer_claims,
 			vf_schema.subject_claims,
 			vf_schema.credential_claims,
-			b"metadata".to_vec(),
+			bounded(b"metadata"),
 			schema_data_sig,
 			credential.nonce
 		));
 		assert_ok!(CredentialRegistry::create_credential(
 			RawOrigin::Signed(signer).into(),
-			credential.schema,
+			Some(credential.schema),
 			credential.context.clone(),
 			credential.schema,
+			credential.schema_version,
 			credential.issuer,
 			credential.issuance_date.clone(),
 			Some(1702379816u64),
@@ -222,11 +278,18 @@ fn it_works_for_update_credential() {
 			data_sig.clone(),
 			credential.nonce
 		));
+		accept_test_credential(credential.schema);
 		assert_ok!(CredentialRegistry::update_credential(
 			RawOrigin::Signed(signer).into(),
 			credential.schema,
 			(updated_sig.clone(), updated_credential.clone())
 		));
+		// the stored credential is kept until the holder accepts the update
+		assert_eq!(
+			CredentialRegistry::credential_registry(credential.schema),
+			Some((data_sig, credential.clone()))
+		);
+		accept_test_credential(credential.schema);
 		assert_eq!(
 			CredentialRegistry::credential_registry(credential.schema),
 			Some((updated_sig, updated_credential.clone()))
@@ -240,58 +303,68 @@ fn it_works_for_delete_credential() {
 		let account_pair = account_pair("Alice");
 		let signer = account_pair.public();
 		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
-		let credential = VerifiableCredential {
-			context: b"Credential context".to_vec(),
+		let credential = CredentialOf::<Test> {
+			context: bounded(b"Credential context"),
 			schema: 123456u32,
-			issuer: account_id.clone().into(),
+			schema_version: 0,
+			issuer: bounded(account_id.as_bytes()),
 			issuance_date: Some(Timestamp::now()),
 			expiration_date: Some(1702379816u64),
 			subject: Subject {
-				id: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H".to_vec(),
-				claim: vec![Claim {
-					property: b"property".to_vec(),
-					value: b"value".to_vec(),
-					schemaid: None,
-					claim_type: ClaimType::SubjectClaim,
-					issuance_requirement: None,
-				}],
+				id: bounded(b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"),
+				claim: bounded_vec![
+					Claim {
+						property: bounded(b"property"),
+						value: bounded(b"value"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+					Claim {
+						property: bounded(b"name"),
+						value: bounded(b"0x1234"),
+						schemaid: None,
+						claim_type: ClaimType::SubjectClaim,
+						issuance_requirement: None,
+					},
+				],
+				committed_claims: Default::default(),
 			},
-			credential_holder: b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"
-				.to_vec(),
+			credential_holder: bounded(HOLDER_DID),
 			nonce: 2u64,
 		};
-		let vf_schema = VerifiableCredentialSchema {
-			name: b"name".to_vec(),
-			creator: account_id.clone().into(),
+		let vf_schema = SchemaOf::<Test> {
+			name: bounded(b"name"),
+			creator: bounded(account_id.as_bytes()),
 			public: false,
 			creation_date: Timestamp::now(),
 			expiration_date: credential.expiration_date,
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: bounded(b"metadata"),
 			nonce: 2u64,
 		};
 		let data_sig = account_pair.sign(&credential.encode());
@@ -301,8 +374,8 @@ fn it_works_for_delete_credential() {
 		// Dispatch a signed create schema extrinsic.
 		assert_ok!(SchemaRegistry::create_schema(
 			RawOrigin::Signed(signer).into(),
-			credential.schema,
-			b"name".to_vec(),
+			Some(credential.schema),
+			bounded(b"name"),
 			vf_schema.creator,
 			false,
 			vf_schema.mandatory_fields,
@@ -311,16 +384,17 @@ fn it_works_for_delete_credential() {
 			vf_schema.issuer_claims,
 			vf_schema.subject_claims,
 			vf_schema.credential_claims,
-			b"metadata".to_vec(),
+			bounded(b"metadata"),
 			schema_data_sig,
 			credential.nonce
 		));
 
 		assert_ok!(CredentialRegistry::create_credential(
 			RawOrigin::Signed(signer).into(),
-			credential.schema,
+			Some(credential.schema),
 			credential.context.clone(),
 			credential.schema,
+			credential.schema_version,
 			credential.issuer,
 			credential.issuance_date.clone(),
 			Some(1702379816u64),
@@ -329,12 +403,2256 @@ fn it_works_for_delete_credential() {
 			data_sig.clone(),
 			credential.nonce
 		));
+		accept_test_credential(credential.schema);
 
 		assert_ok!(CredentialRegistry::delete_credential(
 			RawOrigin::Signed(signer).into(),
-			credential.schema.clone()
+			credential.schema.clone(),
+			0
 		));
 
 		assert_eq!(CredentialRegistry::credential_registry(credential.schema.clone()), None);
 	});
 }
+
+#[test]
+fn it_works_for_credential_indexes() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema_id = 123456u32;
+		create_test_schema(&account_pair, schema_id);
+		let credential = test_credential(&account_id, schema_id);
+		for id in 1..=3u32 {
+			let credential = CredentialOf::<Test> { nonce: id as u64, ..credential.clone() };
+			create_test_credential(&account_pair, id, &credential);
+		}
+
+		let first_page = CredentialRegistry::get_credentials_by_holder(
+			&credential.credential_holder,
+			None,
+			2,
+		);
+		assert_eq!(first_page.len(), 2);
+		let last_seen = first_page.last().map(|(id, _)| *id);
+		let second_page = CredentialRegistry::get_credentials_by_holder(
+			&credential.credential_holder,
+			last_seen,
+			2,
+		);
+		assert_eq!(second_page.len(), 1);
+		assert!(first_page.iter().all(|(id, _)| *id != second_page[0].0));
+		assert_eq!(CredentialRegistry::get_credentials_by_schemaid(&schema_id, None, 10).len(), 3);
+		assert_eq!(
+			CredentialRegistry::get_credentials_by_subject(&credential.subject.id, None, 10).len(),
+			3
+		);
+
+		assert_ok!(CredentialRegistry::delete_credential(
+			RawOrigin::Signed(account_pair.public()).into(),
+			2,
+			0
+		));
+		assert_eq!(
+			CredentialRegistry::get_credentials_by_creator(&credential.issuer, None, 10).len(),
+			2
+		);
+		assert!(!CredentialsByHolder::<Test>::contains_key(&credential.credential_holder, 2));
+
+		let mut moved = credential.clone();
+		moved.subject.id = bounded(b"did:seneca:5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y");
+		moved.nonce = 4;
+		let moved_sig = account_pair.sign(&moved.encode());
+		assert_ok!(CredentialRegistry::update_credential(
+			RawOrigin::Signed(account_pair.public()).into(),
+			1,
+			(moved_sig, moved.clone())
+		));
+		accept_test_credential(1);
+		assert!(!CredentialsBySubject::<Test>::contains_key(&credential.subject.id, 1));
+		assert!(CredentialsBySubject::<Test>::contains_key(&moved.subject.id, 1));
+	});
+}
+
+#[test]
+fn it_works_for_revoke_credential() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+
+		assert_ok!(CredentialRegistry::revoke_credential(RawOrigin::Signed(signer).into(), 1, 7));
+		let record = CredentialRegistry::credential_status(&1).unwrap();
+		assert_eq!(record.status, CredentialStatus::Revoked);
+		assert_eq!(record.reason, 7);
+		// the credential itself is kept so verifiers can tell it was revoked
+		assert!(CredentialRegistry::credential_registry(1).is_some());
+
+		let mut updated = credential.clone();
+		updated.context = bounded(b"Updated context");
+		let updated_sig = account_pair.sign(&updated.encode());
+		assert_noop!(
+			CredentialRegistry::update_credential(
+				RawOrigin::Signed(signer).into(),
+				1,
+				(updated_sig, updated)
+			),
+			Error::<Test>::CredentialRevoked
+		);
+		assert_noop!(
+			CredentialRegistry::revoke_credential(RawOrigin::Signed(signer).into(), 1, 7),
+			Error::<Test>::CredentialRevoked
+		);
+		assert_noop!(
+			CredentialRegistry::reinstate_credential(RawOrigin::Signed(signer).into(), 1, 0),
+			Error::<Test>::CredentialNotSuspended
+		);
+		// deleting it would erase the revocation
+		assert_noop!(
+			CredentialRegistry::delete_credential(RawOrigin::Signed(signer).into(), 1, 0),
+			Error::<Test>::CredentialRevoked
+		);
+		assert_eq!(CredentialRegistry::credential_status(&2), None);
+	});
+}
+
+#[test]
+fn it_works_for_suspend_and_reinstate_credential() {
+	new_test_ext().execute_with(|| {
+		let bob = account_pair("Bob").public();
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+
+		assert_noop!(
+			CredentialRegistry::suspend_credential(
+				RawOrigin::Signed(bob).into(),
+				1,
+				3
+			),
+			Error::<Test>::NotCredentialOwner
+		);
+		assert_ok!(CredentialRegistry::suspend_credential(RawOrigin::Signed(signer).into(), 1, 3));
+		assert_eq!(
+			CredentialRegistry::credential_status(&1).map(|record| record.status),
+			Some(CredentialStatus::Suspended)
+		);
+		assert_noop!(
+			CredentialRegistry::suspend_credential(RawOrigin::Signed(signer).into(), 1, 3),
+			Error::<Test>::CredentialNotActive
+		);
+		assert_ok!(CredentialRegistry::reinstate_credential(
+			RawOrigin::Signed(signer).into(),
+			1,
+			0
+		));
+		assert_eq!(
+			CredentialRegistry::credential_status(&1).map(|record| record.status),
+			Some(CredentialStatus::Active)
+		);
+	});
+}
+
+#[test]
+fn create_credential_enforces_dates() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema_id = 123456u32;
+		create_test_schema(&account_pair, schema_id);
+		Timestamp::set_timestamp(1_000);
+
+		let mut future = test_credential(&account_id, schema_id);
+		future.issuance_date = Some(2_000);
+		let future_sig = account_pair.sign(&future.encode());
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(1),
+				future.context,
+				future.schema,
+				future.schema_version,
+				future.issuer,
+				future.issuance_date,
+				future.expiration_date,
+				future.subject,
+				future.credential_holder,
+				future_sig,
+				future.nonce
+			),
+			Error::<Test>::IssuanceDateInFuture
+		);
+
+		let mut expired = test_credential(&account_id, schema_id);
+		expired.expiration_date = Some(500);
+		let expired_sig = account_pair.sign(&expired.encode());
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(1),
+				expired.context,
+				expired.schema,
+				expired.schema_version,
+				expired.issuer,
+				expired.issuance_date,
+				expired.expiration_date,
+				expired.subject,
+				expired.credential_holder,
+				expired_sig,
+				expired.nonce
+			),
+			Error::<Test>::ExpirationDateInPast
+		);
+	});
+}
+
+#[test]
+fn create_credential_fails_for_expired_schema() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		// move past the schema expiration date
+		Timestamp::set_timestamp(1702379817u64);
+
+		let credential_sig = account_pair.sign(&credential.encode());
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(1),
+				credential.context,
+				credential.schema,
+				credential.schema_version,
+				credential.issuer,
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject,
+				credential.credential_holder,
+				credential_sig,
+				credential.nonce
+			),
+			pallet_schemas::Error::<Test>::SchemaExpired
+		);
+	});
+}
+
+#[test]
+fn create_credential_pins_schema_version() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema_id = 123456u32;
+		create_test_schema(&account_pair, schema_id);
+		// version 1 makes an `age` claim mandatory
+		let mut schema = test_schema(&account_id);
+		schema
+			.mandatory_fields
+			.try_push(Attribute { name: bounded(b"age"), attribute_type: AttributeType::Uint })
+			.unwrap();
+		update_test_schema(&account_pair, schema_id, schema);
+
+		// claims are checked against the version the credential names
+		let credential = test_credential(&account_id, schema_id);
+		create_test_credential(&account_pair, 1, &credential);
+		assert_eq!(CredentialRegistry::credential_registry(1).unwrap().1.schema_version, 0);
+		let latest = CredentialOf::<Test> { schema_version: 1, nonce: 3, ..credential.clone() };
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(2),
+				latest.context.clone(),
+				latest.schema,
+				latest.schema_version,
+				latest.issuer.clone(),
+				latest.issuance_date,
+				latest.expiration_date,
+				latest.subject.clone(),
+				latest.credential_holder.clone(),
+				account_pair.sign(&latest.encode()),
+				latest.nonce
+			),
+			Error::<Test>::MissingMandatoryClaim
+		);
+		let unknown = CredentialOf::<Test> { schema_version: 2, nonce: 3, ..credential.clone() };
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(2),
+				unknown.context.clone(),
+				unknown.schema,
+				unknown.schema_version,
+				unknown.issuer.clone(),
+				unknown.issuance_date,
+				unknown.expiration_date,
+				unknown.subject.clone(),
+				unknown.credential_holder.clone(),
+				account_pair.sign(&unknown.encode()),
+				unknown.nonce
+			),
+			pallet_schemas::Error::<Test>::UnknownSchemaVersion
+		);
+
+		// a deprecated version accepts no new credentials
+		assert_ok!(SchemaRegistry::deprecate_schema_version(
+			RawOrigin::Signed(signer).into(),
+			schema_id,
+			0
+		));
+		let deprecated = CredentialOf::<Test> { nonce: 3, ..credential };
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(2),
+				deprecated.context.clone(),
+				deprecated.schema,
+				deprecated.schema_version,
+				deprecated.issuer.clone(),
+				deprecated.issuance_date,
+				deprecated.expiration_date,
+				deprecated.subject.clone(),
+				deprecated.credential_holder.clone(),
+				account_pair.sign(&deprecated.encode()),
+				deprecated.nonce
+			),
+			pallet_schemas::Error::<Test>::SchemaVersionDeprecated
+		);
+		// credentials already issued against it are untouched
+		assert!(CredentialRegistry::is_credential_valid(&1));
+	});
+}
+
+#[test]
+fn is_credential_valid_folds_in_expiry() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+
+		assert!(CredentialRegistry::is_credential_valid(&1));
+		assert!(!CredentialRegistry::is_credential_valid(&2));
+		Timestamp::set_timestamp(1702379817u64);
+		assert!(!CredentialRegistry::is_credential_valid(&1));
+	});
+}
+
+#[test]
+fn create_credential_validates_claims() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		create_test_schema(&account_pair, 123456u32);
+
+		let mut missing = test_credential(&account_id, 123456u32);
+		missing.subject.claim.retain(|claim| claim.property[..] != b"name"[..]);
+		let mut invalid = test_credential(&account_id, 123456u32);
+		invalid.subject.claim[1].value = bounded(b"0xzz");
+		let mut undeclared = test_credential(&account_id, 123456u32);
+		undeclared.subject.claim[0].property = bounded(b"age");
+		let mut undeclared_commitment = test_credential(&account_id, 123456u32);
+		undeclared_commitment.subject.committed_claims =
+			bounded_vec![ClaimCommitment { property: bounded(b"age"), commitment: [0; 32] }];
+
+		for (credential, error) in [
+			(missing, Error::<Test>::MissingMandatoryClaim),
+			(invalid, Error::<Test>::InvalidClaimValue),
+			(undeclared, Error::<Test>::UndeclaredClaim),
+			(undeclared_commitment, Error::<Test>::UndeclaredClaim),
+		] {
+			let credential_sig = account_pair.sign(&credential.encode());
+			assert_noop!(
+				CredentialRegistry::create_credential(
+					RawOrigin::Signed(signer).into(),
+					Some(1),
+					credential.context,
+					credential.schema,
+					credential.schema_version,
+					credential.issuer,
+					credential.issuance_date,
+					credential.expiration_date,
+					credential.subject,
+					credential.credential_holder,
+					credential_sig,
+					credential.nonce
+				),
+				error
+			);
+		}
+	});
+}
+
+#[test]
+fn create_credential_rejects_malformed_issuer_did() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		create_test_schema(&account_pair, 123456u32);
+
+		for issuer in ["did:seneca", "did:other:abc", "seneca"] {
+			let credential = test_credential(issuer, 123456u32);
+			let credential_sig = account_pair.sign(&credential.encode());
+			assert_noop!(
+				CredentialRegistry::create_credential(
+					RawOrigin::Signed(signer).into(),
+					Some(1),
+					credential.context,
+					credential.schema,
+					credential.schema_version,
+					credential.issuer,
+					credential.issuance_date,
+					credential.expiration_date,
+					credential.subject,
+					credential.credential_holder,
+					credential_sig,
+					credential.nonce
+				),
+				Error::<Test>::InvalidDID
+			);
+		}
+		assert!(CredentialRegistry::split_publickey_from_did(&account_id.into_bytes()).is_ok());
+	});
+}
+
+#[test]
+fn create_and_update_credential_report_typed_errors() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		create_test_schema(&account_pair, 123456u32);
+		let create = |id: u32, credential: &CredentialOf<Test>| {
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(id),
+				credential.context.clone(),
+				credential.schema,
+				credential.schema_version,
+				credential.issuer.clone(),
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject.clone(),
+				credential.credential_holder.clone(),
+				account_pair.sign(&credential.encode()),
+				credential.nonce,
+			)
+		};
+
+		// every way the issuer DID fails to resolve has its own error
+		for (issuer, error) in [
+			(&b"did:seneca:abc"[..], Error::<Test>::InvalidDidAccount),
+			(DEACTIVATED_DID, Error::<Test>::DidDeactivated),
+			(KEYLESS_DID, Error::<Test>::NoAssertionKey),
+		] {
+			let credential =
+				CredentialOf::<Test> { issuer: bounded(issuer), ..test_credential("", 123456u32) };
+			assert_noop!(create(1, &credential), error);
+		}
+		assert_noop!(
+			create(1, &test_credential(&account_id, 7u32)),
+			Error::<Test>::UnknownSchema
+		);
+
+		let credential = test_credential(&account_id, 123456u32);
+		assert_ok!(create(1, &credential));
+		accept_test_credential(1);
+		let next = CredentialOf::<Test> { nonce: credential.nonce + 1, ..credential.clone() };
+		assert_noop!(create(1, &next), Error::<Test>::CredentialAlreadyExists);
+
+		// resubmitting the stored credential and signature changes nothing
+		let stored = CredentialStore::<Test>::get(1).unwrap();
+		assert_noop!(
+			CredentialRegistry::update_credential(RawOrigin::Signed(signer).into(), 1, stored),
+			Error::<Test>::CredentialUnchanged
+		);
+	});
+}
+
+#[test]
+fn replayed_nonce_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+		assert_eq!(CredentialRegistry::get_nonce(&credential.issuer), credential.nonce + 1);
+
+		// the same signed payload cannot be stored under another id
+		let credential_sig = account_pair.sign(&credential.encode());
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(2),
+				credential.context.clone(),
+				credential.schema,
+				credential.schema_version,
+				credential.issuer.clone(),
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject.clone(),
+				credential.credential_holder.clone(),
+				credential_sig,
+				credential.nonce
+			),
+			Error::<Test>::StaleNonce
+		);
+
+		let mut updated = credential.clone();
+		updated.context = bounded(b"Updated context");
+		let updated_sig = account_pair.sign(&updated.encode());
+		assert_noop!(
+			CredentialRegistry::update_credential(
+				RawOrigin::Signed(signer).into(),
+				1,
+				(updated_sig, updated.clone())
+			),
+			Error::<Test>::StaleNonce
+		);
+		updated.nonce = credential.nonce + 1;
+		let updated_sig = account_pair.sign(&updated.encode());
+		assert_ok!(CredentialRegistry::update_credential(
+			RawOrigin::Signed(signer).into(),
+			1,
+			(updated_sig, updated)
+		));
+		// the nonce is used up by the offer, before the holder accepts it
+		assert_eq!(CredentialRegistry::get_nonce(&credential.issuer), credential.nonce + 2);
+	});
+}
+
+#[test]
+fn update_credential_verifies_signature_and_records_history() {
+	new_test_ext().execute_with(|| {
+		// events are only recorded from the first block on
+		System::set_block_number(1);
+		let bob = account_pair("Bob");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+
+		let mut updated = credential.clone();
+		updated.context = bounded(b"Updated context");
+		updated.nonce = credential.nonce + 1;
+		// the signature must cover the new payload and come from the issuer
+		assert_noop!(
+			CredentialRegistry::update_credential(
+				RawOrigin::Signed(signer).into(),
+				1,
+				(account_pair.sign(&credential.encode()), updated.clone())
+			),
+			Error::<Test>::SignatureVerifyError
+		);
+		assert_noop!(
+			CredentialRegistry::update_credential(
+				RawOrigin::Signed(signer).into(),
+				1,
+				(bob.sign(&updated.encode()), updated.clone())
+			),
+			Error::<Test>::SignatureVerifyError
+		);
+		let mut handed_over = updated.clone();
+		handed_over.issuer =
+			bounded(format!("did:seneca:{}", bob.public().into_account()).as_bytes());
+		assert_noop!(
+			CredentialRegistry::update_credential(
+				RawOrigin::Signed(signer).into(),
+				1,
+				(bob.sign(&handed_over.encode()), handed_over)
+			),
+			Error::<Test>::IssuerChanged
+		);
+
+		assert_ok!(CredentialRegistry::update_credential(
+			RawOrigin::Signed(signer).into(),
+			1,
+			(account_pair.sign(&updated.encode()), updated.clone())
+		));
+		accept_test_credential(1);
+		System::assert_last_event(
+			crate::Event::CredentialUpdated {
+				credential_id: 1,
+				issuer: signer,
+				holder_did: updated.credential_holder.to_vec(),
+				schema_id: updated.schema,
+				schema_version: updated.schema_version,
+				content_hash: sp_io::hashing::blake2_256(&updated.encode()),
+				moment: Timestamp::now(),
+			}
+			.into(),
+		);
+		let mut second = updated.clone();
+		second.nonce = updated.nonce + 1;
+		assert_ok!(CredentialRegistry::update_credential(
+			RawOrigin::Signed(signer).into(),
+			1,
+			(account_pair.sign(&second.encode()), second)
+		));
+		accept_test_credential(1);
+		let history = CredentialRegistry::credential_history(&1);
+		assert_eq!(history.iter().map(|(revision, _)| *revision).collect::<Vec<_>>(), vec![1, 2]);
+		assert_eq!(history[0].1.previous_hash, sp_io::hashing::blake2_256(&credential.encode()));
+		assert_eq!(history[1].1.previous_hash, sp_io::hashing::blake2_256(&updated.encode()));
+
+		// the witness has to cover every revision in the history
+		assert_noop!(
+			CredentialRegistry::delete_credential(RawOrigin::Signed(signer).into(), 1, 1),
+			Error::<Test>::InvalidRevisionCount
+		);
+		assert_ok!(CredentialRegistry::delete_credential(RawOrigin::Signed(signer).into(), 1, 2));
+		assert!(CredentialRegistry::credential_history(&1).is_empty());
+	});
+}
+
+#[test]
+fn credentials_verify_after_the_issuer_rotates_its_key() {
+	new_test_ext().execute_with(|| {
+		let rotated = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+		let second = CredentialOf::<Test> { nonce: credential.nonce + 1, ..credential.clone() };
+		create_test_credential(&account_pair, 2, &second);
+		assert_eq!(CredentialRegistry::credential_signer(1), Some(account_pair.public()));
+
+		// credentials issued before the rotation verify against the key that signed them
+		rotate_key(account_id.as_bytes(), rotated.public());
+		assert!(issuer_signature_valid(1));
+		assert!(issuer_signature_valid(2));
+
+		// an update is signed with the new key
+		let updated = CredentialOf::<Test> {
+			context: bounded(b"Updated context"),
+			nonce: credential.nonce + 2,
+			..credential.clone()
+		};
+		assert_ok!(CredentialRegistry::update_credential(
+			RawOrigin::Signed(rotated.public()).into(),
+			1,
+			(rotated.sign(&updated.encode()), updated)
+		));
+		// the stored credential keeps its signer until the holder accepts the update
+		assert_eq!(CredentialRegistry::credential_signer(1), Some(account_pair.public()));
+		assert!(issuer_signature_valid(1));
+		accept_test_credential(1);
+		assert_eq!(CredentialRegistry::credential_signer(1), Some(rotated.public()));
+		assert!(issuer_signature_valid(1));
+		assert!(issuer_signature_valid(2));
+
+		assert_ok!(CredentialRegistry::delete_credential(
+			RawOrigin::Signed(rotated.public()).into(),
+			2,
+			0
+		));
+		assert_eq!(CredentialRegistry::credential_signer(2), None);
+	});
+}
+
+#[test]
+fn credential_updates_wait_for_the_holder() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let bob = account_pair("Bob");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+		let stored = CredentialStore::<Test>::get(1).unwrap();
+		let reserved = Balances::reserved_balance(&signer);
+		let update = |id: u32, nonce: u64, context: &[u8]| {
+			let updated = CredentialOf::<Test> {
+				context: bounded(context),
+				nonce,
+				..credential.clone()
+			};
+			let updated = (account_pair.sign(&updated.encode()), updated);
+			assert_ok!(CredentialRegistry::update_credential(
+				RawOrigin::Signed(signer).into(),
+				id,
+				updated.clone()
+			));
+			updated
+		};
+
+		// the larger of the two deposits is held while the update is pending
+		let longer = update(1, credential.nonce + 1, b"A longer updated credential context");
+		assert_eq!(CredentialStore::<Test>::get(1), Some(stored.clone()));
+		assert_eq!(
+			Balances::reserved_balance(&signer),
+			reserved + (longer.encoded_size() - stored.encoded_size()) as u64
+		);
+		// the holder has to sign the update itself
+		assert_noop!(
+			CredentialRegistry::accept_credential(
+				RawOrigin::Signed(bob.public()).into(),
+				1,
+				bob.sign(&sp_io::hashing::blake2_256(&credential.encode()))
+			),
+			Error::<Test>::SignatureVerifyError
+		);
+		assert_ok!(CredentialRegistry::reject_credential(
+			RawOrigin::Signed(bob.public()).into(),
+			1
+		));
+		System::assert_last_event(crate::Event::CredentialOfferRejected(1).into());
+		assert_eq!(CredentialStore::<Test>::get(1), Some(stored.clone()));
+		assert_eq!(Balances::reserved_balance(&signer), reserved);
+		assert!(CredentialRegistry::credential_history(&1).is_empty());
+
+		// a newer update supersedes a pending one and expires like any offer
+		update(1, credential.nonce + 2, b"Updated context");
+		update(1, credential.nonce + 3, b"Update");
+		assert_eq!(CredentialOffers::<Test>::iter().count(), 1);
+		assert_eq!(Balances::reserved_balance(&signer), reserved);
+		CredentialRegistry::on_initialize(11);
+		assert_eq!(CredentialRegistry::credential_offer(1), None);
+		assert_eq!(CredentialStore::<Test>::get(1), Some(stored.clone()));
+		assert_eq!(Balances::reserved_balance(&signer), reserved);
+
+		// accepting an update releases the deposit the replaced credential needed beyond it
+		let accepted = update(1, credential.nonce + 4, b"Update");
+		accept_test_credential(1);
+		assert_eq!(CredentialStore::<Test>::get(1), Some(accepted.clone()));
+		assert_eq!(
+			Balances::reserved_balance(&signer),
+			reserved - (stored.encoded_size() - accepted.encoded_size()) as u64
+		);
+
+		// a revoked credential takes no pending update and deleting it drops the update
+		update(1, credential.nonce + 5, b"Revoked");
+		assert_ok!(CredentialRegistry::revoke_credential(RawOrigin::Signed(signer).into(), 1, 0));
+		let (_, revoked, ..) = CredentialRegistry::credential_offer(1).unwrap();
+		assert_noop!(
+			CredentialRegistry::accept_credential(
+				RawOrigin::Signed(bob.public()).into(),
+				1,
+				bob.sign(&sp_io::hashing::blake2_256(&revoked.encode()))
+			),
+			Error::<Test>::CredentialRevoked
+		);
+		let other = CredentialOf::<Test> { nonce: credential.nonce + 6, ..credential.clone() };
+		create_test_credential(&account_pair, 2, &other);
+		update(2, other.nonce + 1, b"Deleted");
+		assert_ok!(CredentialRegistry::delete_credential(RawOrigin::Signed(signer).into(), 2, 0));
+		assert_eq!(CredentialRegistry::credential_offer(2), None);
+		assert_eq!(OfferExpiries::<Test>::iter().count(), 1);
+	});
+}
+
+#[test]
+fn credential_deposit_is_released_on_delete() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		let schema_deposit = Balances::reserved_balance(&signer);
+		create_test_credential(&account_pair, 1, &credential);
+
+		let stored = (account_pair.sign(&credential.encode()), credential.clone());
+		assert_eq!(
+			Balances::reserved_balance(&signer),
+			schema_deposit + 50 + stored.encoded_size() as u64
+		);
+		assert_ok!(CredentialRegistry::delete_credential(RawOrigin::Signed(signer).into(), 1, 0));
+		assert_eq!(Balances::reserved_balance(&signer), schema_deposit);
+	});
+}
+
+#[test]
+fn schemas_with_credentials_cannot_be_deleted() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema_id = 123456u32;
+		create_test_schema(&account_pair, schema_id);
+		let credential = test_credential(&account_id, schema_id);
+		create_test_credential(&account_pair, 1, &credential);
+		assert_eq!(SchemaReferences::<Test>::get(schema_id), 1);
+		assert_noop!(
+			SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), schema_id, 0),
+			pallet_schemas::Error::<Test>::SchemaInUse
+		);
+
+		// a deprecated schema keeps its credentials but issues no new ones
+		assert_ok!(SchemaRegistry::deprecate_schema(RawOrigin::Signed(signer).into(), schema_id));
+		assert!(CredentialRegistry::is_credential_valid(&1));
+		let next = CredentialOf::<Test> { nonce: 3, ..credential };
+		assert_noop!(
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(2),
+				next.context.clone(),
+				next.schema,
+				next.schema_version,
+				next.issuer.clone(),
+				next.issuance_date,
+				next.expiration_date,
+				next.subject.clone(),
+				next.credential_holder.clone(),
+				account_pair.sign(&next.encode()),
+				next.nonce
+			),
+			pallet_schemas::Error::<Test>::SchemaDeprecated
+		);
+
+		assert_ok!(CredentialRegistry::delete_credential(RawOrigin::Signed(signer).into(), 1, 0));
+		assert_eq!(SchemaReferences::<Test>::get(schema_id), 0);
+		assert_ok!(SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), schema_id, 0));
+	});
+}
+
+#[test]
+fn private_schemas_only_accept_authorized_issuers() {
+	new_test_ext().execute_with(|| {
+		let alice = account_pair("Alice");
+		let bob = account_pair("Bob");
+		let bob_id = format!("did:seneca:{}", bob.public().into_account());
+		let schema_id = 123456u32;
+		create_test_schema(&alice, schema_id);
+
+		let credential = test_credential(&bob_id, schema_id);
+		let credential_sig = bob.sign(&credential.encode());
+		let issue = |id: u32| {
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(bob.public()).into(),
+				Some(id),
+				credential.context.clone(),
+				credential.schema,
+				credential.schema_version,
+				credential.issuer.clone(),
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject.clone(),
+				credential.credential_holder.clone(),
+				credential_sig.clone(),
+				credential.nonce,
+			)
+		};
+		assert_noop!(issue(1), pallet_schemas::Error::<Test>::IssuerNotAuthorized);
+
+		assert_ok!(SchemaRegistry::add_issuer(
+			RawOrigin::Signed(alice.public()).into(),
+			schema_id,
+			bounded(bob_id.as_bytes())
+		));
+		assert_ok!(issue(1));
+
+		assert_ok!(SchemaRegistry::remove_issuer(
+			RawOrigin::Signed(alice.public()).into(),
+			schema_id,
+			bounded(bob_id.as_bytes())
+		));
+		assert_noop!(issue(2), pallet_schemas::Error::<Test>::IssuerNotAuthorized);
+	});
+}
+
+#[test]
+fn create_credential_assigns_free_ids() {
+	new_test_ext().execute_with(|| {
+		// events are only recorded from the first block on
+		System::set_block_number(1);
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		create_test_schema(&account_pair, 123456u32);
+		let credential = test_credential(&account_id, 123456u32);
+		let create = |id: Option<u32>, nonce: u64| {
+			let credential = CredentialOf::<Test> { nonce, ..credential.clone() };
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				id,
+				credential.context.clone(),
+				credential.schema,
+				credential.schema_version,
+				credential.issuer.clone(),
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject.clone(),
+				credential.credential_holder.clone(),
+				account_pair.sign(&credential.encode()),
+				credential.nonce,
+			)
+		};
+
+		// callers cannot claim the ids the counter assigns next
+		for id in FIRST_ASSIGNED_ID..FIRST_ASSIGNED_ID + 16 {
+			assert_noop!(create(Some(id), 2), Error::<Test>::CredentialIdReserved);
+		}
+		assert_ok!(create(Some(0), 2));
+		assert_ok!(create(Some(1), 3));
+
+		let next = CredentialOf::<Test> { nonce: 4, ..credential.clone() };
+		assert_ok!(create(None, 4));
+		System::assert_last_event(
+			crate::Event::CredentialOffered {
+				credential_id: FIRST_ASSIGNED_ID,
+				issuer: signer,
+				holder_did: next.credential_holder.to_vec(),
+				content_hash: sp_io::hashing::blake2_256(&next.encode()),
+				expires_at: 11,
+			}
+			.into(),
+		);
+		accept_test_credential(FIRST_ASSIGNED_ID);
+		System::assert_last_event(
+			crate::Event::CredentialCreated {
+				credential_id: FIRST_ASSIGNED_ID,
+				issuer: signer,
+				holder_did: next.credential_holder.to_vec(),
+				schema_id: next.schema,
+				schema_version: next.schema_version,
+				content_hash: sp_io::hashing::blake2_256(&next.encode()),
+				moment: Timestamp::now(),
+			}
+			.into(),
+		);
+		assert_eq!(
+			CredentialRegistry::credential_registry(FIRST_ASSIGNED_ID).map(|(_, stored)| stored),
+			Some(next)
+		);
+		assert_ok!(create(None, 5));
+		assert!(CredentialOffers::<Test>::contains_key(FIRST_ASSIGNED_ID + 1));
+
+		// once the counter ran through its range an id has to be supplied
+		NextCredentialId::<Test>::put(u32::MAX - FIRST_ASSIGNED_ID);
+		assert_ok!(create(None, 6));
+		assert!(CredentialOffers::<Test>::contains_key(u32::MAX));
+		assert_noop!(create(None, 7), Error::<Test>::NoFreeCredentialId);
+		assert_ok!(create(Some(2), 7));
+	});
+}
+
+#[test]
+fn credentials_are_stored_once_the_holder_accepts_them() {
+	new_test_ext().execute_with(|| {
+		// events are only recorded from the first block on
+		System::set_block_number(1);
+		let bob = account_pair("Bob");
+		let charlie = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		let credential_sig = account_pair.sign(&credential.encode());
+		create_test_schema(&account_pair, credential.schema);
+		assert_ok!(CredentialRegistry::create_credential(
+			RawOrigin::Signed(signer).into(),
+			Some(1),
+			credential.context.clone(),
+			credential.schema,
+			credential.schema_version,
+			credential.issuer.clone(),
+			credential.issuance_date,
+			credential.expiration_date,
+			credential.subject.clone(),
+			credential.credential_holder.clone(),
+			credential_sig.clone(),
+			credential.nonce
+		));
+		let content_hash = sp_io::hashing::blake2_256(&credential.encode());
+		System::assert_last_event(
+			crate::Event::CredentialOffered {
+				credential_id: 1,
+				issuer: signer,
+				holder_did: HOLDER_DID.to_vec(),
+				content_hash,
+				expires_at: 11,
+			}
+			.into(),
+		);
+		// pending offers are neither stored nor listed
+		let holder = HOLDER_DID.to_vec();
+		assert_eq!(CredentialRegistry::credential_registry(1), None);
+		assert!(!CredentialRegistry::is_credential_valid(&1));
+		assert!(CredentialRegistry::get_credentials_by_holder(&holder, None, 10).is_empty());
+
+		// only the holder's signature over the credential hash accepts it
+		for acceptance in [account_pair.sign(&content_hash), bob.sign(&credential.encode())] {
+			assert_noop!(
+				CredentialRegistry::accept_credential(
+					RawOrigin::Signed(bob.public()).into(),
+					1,
+					acceptance
+				),
+				Error::<Test>::SignatureVerifyError
+			);
+		}
+		// anyone may submit the acceptance on behalf of the holder
+		assert_ok!(CredentialRegistry::accept_credential(
+			RawOrigin::Signed(charlie.public()).into(),
+			1,
+			bob.sign(&content_hash)
+		));
+		System::assert_last_event(
+			crate::Event::CredentialCreated {
+				credential_id: 1,
+				issuer: signer,
+				holder_did: HOLDER_DID.to_vec(),
+				schema_id: credential.schema,
+				schema_version: credential.schema_version,
+				content_hash,
+				moment: Timestamp::now(),
+			}
+			.into(),
+		);
+		assert_eq!(
+			CredentialRegistry::credential_registry(1),
+			Some((credential_sig, credential.clone()))
+		);
+		assert_eq!(CredentialOffers::<Test>::get(1), None);
+		assert!(CredentialRegistry::is_credential_valid(&1));
+		assert_eq!(CredentialRegistry::get_credentials_by_holder(&holder, None, 10).len(), 1);
+		assert_noop!(
+			CredentialRegistry::accept_credential(
+				RawOrigin::Signed(bob.public()).into(),
+				1,
+				bob.sign(&content_hash)
+			),
+			Error::<Test>::UnknownOffer
+		);
+
+		// the issuer cannot hand the credential over to a holder who did not accept it
+		let handed_over = CredentialOf::<Test> {
+			credential_holder: bounded(
+				format!("did:seneca:{}", charlie.public().into_account()).as_bytes(),
+			),
+			nonce: credential.nonce + 1,
+			..credential
+		};
+		assert_noop!(
+			CredentialRegistry::update_credential(
+				RawOrigin::Signed(signer).into(),
+				1,
+				(account_pair.sign(&handed_over.encode()), handed_over)
+			),
+			Error::<Test>::HolderChanged
+		);
+	});
+}
+
+#[test]
+fn credential_offers_can_be_rejected_or_expire() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let bob = account_pair("Bob");
+		let charlie = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		create_test_schema(&account_pair, 123456u32);
+		let schema_deposit = Balances::reserved_balance(&signer);
+		let offer = |id: u32| {
+			let credential = CredentialOf::<Test> {
+				nonce: id as u64,
+				..test_credential(&account_id, 123456u32)
+			};
+			assert_ok!(CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(id),
+				credential.context.clone(),
+				credential.schema,
+				credential.schema_version,
+				credential.issuer.clone(),
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject.clone(),
+				credential.credential_holder.clone(),
+				account_pair.sign(&credential.encode()),
+				credential.nonce
+			));
+		};
+		for id in 1..=3u32 {
+			offer(id);
+		}
+		assert!(Balances::reserved_balance(&signer) > schema_deposit);
+
+		// the holder rejects an offer, the issuer withdraws another
+		assert_noop!(
+			CredentialRegistry::reject_credential(RawOrigin::Signed(charlie.public()).into(), 1),
+			Error::<Test>::NotCredentialHolder
+		);
+		assert_ok!(CredentialRegistry::reject_credential(
+			RawOrigin::Signed(bob.public()).into(),
+			1
+		));
+		System::assert_last_event(crate::Event::CredentialOfferRejected(1).into());
+		assert_ok!(CredentialRegistry::reject_credential(RawOrigin::Signed(signer).into(), 2));
+		assert_eq!(CredentialOffers::<Test>::get(1), None);
+		assert_eq!(CredentialOffers::<Test>::get(2), None);
+		assert_eq!(CredentialDeposits::<Test>::get(1), None);
+		assert_noop!(
+			CredentialRegistry::reject_credential(RawOrigin::Signed(bob.public()).into(), 1),
+			Error::<Test>::UnknownOffer
+		);
+
+		// the last offer lapses after the configured number of blocks
+		let (_, pending, expires_at, _) = CredentialRegistry::credential_offer(3).unwrap();
+		assert_eq!(expires_at, 11);
+		System::set_block_number(expires_at);
+		assert_noop!(
+			CredentialRegistry::accept_credential(
+				RawOrigin::Signed(bob.public()).into(),
+				3,
+				bob.sign(&sp_io::hashing::blake2_256(&pending.encode()))
+			),
+			Error::<Test>::OfferExpired
+		);
+		CredentialRegistry::on_initialize(expires_at);
+		System::assert_last_event(crate::Event::CredentialOfferExpired(3).into());
+		assert_eq!(CredentialOffers::<Test>::get(3), None);
+		assert_eq!(CredentialRegistry::credential_registry(3), None);
+		assert_eq!(Balances::reserved_balance(&signer), schema_deposit);
+	});
+}
+
+#[test]
+fn offer_expiries_are_capped_per_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		create_test_schema(&account_pair, 123456u32);
+		let schema_deposit = Balances::reserved_balance(&signer);
+		for id in 1..=5u32 {
+			let credential = CredentialOf::<Test> {
+				nonce: id as u64,
+				..test_credential(&account_id, 123456u32)
+			};
+			assert_ok!(CredentialRegistry::create_credential(
+				RawOrigin::Signed(signer).into(),
+				Some(id),
+				credential.context.clone(),
+				credential.schema,
+				credential.schema_version,
+				credential.issuer.clone(),
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject.clone(),
+				credential.credential_holder.clone(),
+				account_pair.sign(&credential.encode()),
+				credential.nonce
+			));
+		}
+		let expired = || {
+			System::events()
+				.iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						RuntimeEvent::CredentialRegistry(crate::Event::CredentialOfferExpired(_))
+					)
+				})
+				.count()
+		};
+
+		// at most `MaxExpiriesPerBlock` offers are dropped in a block
+		CredentialRegistry::on_initialize(11);
+		assert_eq!(expired(), 4);
+		assert_eq!(CredentialOffers::<Test>::iter().count(), 1);
+		assert_eq!(NextExpiryBlock::<Test>::get(), Some(11));
+
+		// the offer left over is dropped in the next block
+		CredentialRegistry::on_initialize(12);
+		assert_eq!(expired(), 5);
+		assert_eq!(CredentialOffers::<Test>::iter().count(), 0);
+		assert_eq!(NextExpiryBlock::<Test>::get(), Some(13));
+		assert_eq!(Balances::reserved_balance(&signer), schema_deposit);
+	});
+}
+
+#[test]
+fn committed_claims_are_disclosed_selectively() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let mut credential = test_credential(&account_id, 123456u32);
+		let openings: Vec<ClaimOpeningOf<Test>> = credential
+			.subject
+			.claim
+			.iter()
+			.zip([[1u8; 32], [2u8; 32]])
+			.map(|(claim, salt)| ClaimOpening { claim: claim.clone(), salt })
+			.collect();
+		credential.subject.claim = bounded_vec![];
+		credential.subject.committed_claims =
+			openings.iter().map(ClaimOpening::committed).collect::<Vec<_>>().try_into().unwrap();
+		create_test_schema(&account_pair, credential.schema);
+		// the mandatory `name` claim is satisfied by its commitment
+		create_test_credential(&account_pair, 1, &credential);
+		let (_, stored) = CredentialStore::<Test>::get(1).unwrap();
+		assert!(stored.subject.claim.is_empty());
+		assert!(!stored.encode().windows(6).any(|bytes| bytes == b"0x1234"));
+
+		// the holder reveals only the claims it chooses
+		assert_ok!(CredentialRegistry::verify_disclosure(&1, &openings[1..]));
+		assert_ok!(CredentialRegistry::verify_disclosure(&1, &openings));
+		assert_ok!(CredentialRegistry::verify_disclosure(&1, &[]));
+
+		let mut altered = openings[1].clone();
+		altered.claim.value = bounded(b"0x5678");
+		let mut resalted = openings[1].clone();
+		resalted.salt = [3; 32];
+		let mut uncommitted = openings[1].clone();
+		uncommitted.claim.property = bounded(b"age");
+		for (opening, error) in [
+			(altered, DisclosureError::CommitmentMismatch),
+			(resalted, DisclosureError::CommitmentMismatch),
+			(uncommitted, DisclosureError::UncommittedClaim),
+		] {
+			assert_eq!(CredentialRegistry::verify_disclosure(&1, &[opening]), Err(error));
+		}
+		assert_eq!(
+			CredentialRegistry::verify_disclosure(&2, &openings),
+			Err(DisclosureError::UnknownCredential)
+		);
+		Timestamp::set_timestamp(1702379817u64);
+		assert_eq!(
+			CredentialRegistry::verify_disclosure(&1, &openings),
+			Err(DisclosureError::CredentialNotValid)
+		);
+	});
+}
+
+#[test]
+fn verify_presentation_reports_each_check() {
+	new_test_ext().execute_with(|| {
+		let bob = account_pair("Bob");
+		let charlie = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+
+		let mut presentation = PresentationOf::<Test> {
+			credential_ids: vec![1],
+			holder: HOLDER_DID.to_vec(),
+			challenge: b"challenge".to_vec(),
+			domain: b"verifier.example".to_vec(),
+			signature: charlie.sign(b""),
+		};
+		presentation.signature = bob.sign(&presentation.payload(&System::block_hash(0)));
+		let verification = CredentialRegistry::verify_presentation(&presentation);
+		assert!(verification.is_valid());
+
+		// the holder signature is bound to the challenge and to the holder key
+		let mut replayed = presentation.clone();
+		replayed.challenge = b"another challenge".to_vec();
+		assert!(!CredentialRegistry::verify_presentation(&replayed).holder_signature_valid);
+		let mut forged = presentation.clone();
+		forged.signature = charlie.sign(&forged.payload(&System::block_hash(0)));
+		assert!(!CredentialRegistry::verify_presentation(&forged).holder_signature_valid);
+		// and to this chain
+		let mut elsewhere = presentation.clone();
+		elsewhere.signature = bob.sign(&elsewhere.payload(&sp_core::H256::repeat_byte(1)));
+		assert!(!CredentialRegistry::verify_presentation(&elsewhere).holder_signature_valid);
+
+		// credentials held by someone else or never stored fail their checks
+		let charlie_did = format!("did:seneca:{}", charlie.public().into_account());
+		let mut borrowed = presentation.clone();
+		borrowed.holder = charlie_did.into_bytes();
+		borrowed.signature = charlie.sign(&borrowed.payload(&System::block_hash(0)));
+		let verification = CredentialRegistry::verify_presentation(&borrowed);
+		assert!(verification.holder_signature_valid);
+		assert!(!verification.credentials[0].holder_bound);
+		let mut unknown = presentation.clone();
+		unknown.credential_ids = vec![1, 2];
+		unknown.signature = bob.sign(&unknown.payload(&System::block_hash(0)));
+		let verification = CredentialRegistry::verify_presentation(&unknown);
+		assert!(verification.credentials[0].is_valid());
+		assert!(!verification.credentials[1].found);
+		assert!(!verification.is_valid());
+
+		let check = |presentation: &PresentationOf<Test>| {
+			CredentialRegistry::verify_presentation(presentation).credentials[0].clone()
+		};
+		assert_ok!(CredentialRegistry::suspend_credential(RawOrigin::Signed(signer).into(), 1, 3));
+		assert!(!check(&presentation).not_suspended);
+		assert_ok!(CredentialRegistry::revoke_credential(RawOrigin::Signed(signer).into(), 1, 3));
+		assert!(!check(&presentation).not_revoked);
+
+		CredentialStore::<Test>::mutate(1, |stored| {
+			stored.as_mut().unwrap().1.context = bounded(b"Tampered context");
+		});
+		assert!(!check(&presentation).issuer_signature_valid);
+
+		assert_ok!(SchemaRegistry::deprecate_schema(
+			RawOrigin::Signed(signer).into(),
+			credential.schema
+		));
+		assert!(!check(&presentation).schema_valid);
+		assert!(check(&presentation).not_expired);
+		Timestamp::set_timestamp(1702379817u64);
+		assert!(!check(&presentation).not_expired);
+	});
+}
+
+#[test]
+fn credentials_are_issued_in_batches() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let bob_did = format!("did:seneca:{}", account_pair("Bob").public().into_account());
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		let credentials: Vec<CredentialOf<Test>> = (0..3)
+			.map(|i| CredentialOf::<Test> { nonce: credential.nonce + i, ..credential.clone() })
+			.collect();
+		let leaves_of = |credentials: &[CredentialOf<Test>]| {
+			credentials
+				.iter()
+				.map(|credential| sp_io::hashing::blake2_256(&credential.encode()))
+				.collect::<Vec<_>>()
+		};
+		let batch = |credentials: &[CredentialOf<Test>], signature| {
+			CredentialRegistry::create_credentials_batch(
+				RawOrigin::Signed(signer).into(),
+				bounded(account_id.as_bytes()),
+				credentials.to_vec().try_into().unwrap(),
+				signature,
+			)
+		};
+		let leaves = leaves_of(&credentials);
+		let root = merkle::root(&leaves);
+
+		assert_noop!(batch(&[], account_pair.sign(&merkle::root(&[]))), Error::<Test>::EmptyBatch);
+		// the issuer signs the root rather than each credential
+		assert_noop!(
+			batch(&credentials, account_pair.sign(&credentials[0].encode())),
+			Error::<Test>::SignatureVerifyError
+		);
+		let mut mixed = credentials.clone();
+		mixed[1].issuer = bounded(bob_did.as_bytes());
+		assert_noop!(
+			batch(&mixed, account_pair.sign(&merkle::root(&leaves_of(&mixed)))),
+			Error::<Test>::NotCredentialOwner
+		);
+		let mut replayed = credentials.clone();
+		replayed[2].nonce = credential.nonce;
+		assert_noop!(
+			batch(&replayed, account_pair.sign(&merkle::root(&leaves_of(&replayed)))),
+			Error::<Test>::StaleNonce
+		);
+
+		assert_ok!(batch(&credentials, account_pair.sign(&root)));
+		System::assert_last_event(
+			crate::Event::CredentialBatchCreated { root, issuer: signer, count: 3 }.into(),
+		);
+		// the batch holds a deposit of its own until its last credential is gone
+		let batch_deposit = 50 + CredentialRegistry::credential_batch(root).unwrap().encoded_size();
+		assert_eq!(BatchDeposits::<Test>::get(root), Some((signer, batch_deposit as u64, 3)));
+		// each credential is offered to its holder under the next id from the counter
+		for (id, credential) in (FIRST_ASSIGNED_ID..).zip(&credentials) {
+			assert_eq!(
+				CredentialOffers::<Test>::get(id).map(|(_, offered, ..)| offered),
+				Some(credential.clone())
+			);
+			accept_test_credential(id);
+		}
+
+		// verifiers prove each stored credential is included under the signed root
+		let ids: Vec<u32> = (FIRST_ASSIGNED_ID..FIRST_ASSIGNED_ID + 3).collect();
+		for (index, id) in ids.iter().enumerate() {
+			let proof = CredentialRegistry::batch_proof(id).unwrap();
+			assert_eq!((proof.root, proof.leaf_count), (root, 3));
+			assert!(proof.verify(&leaves[index]));
+			assert!(!proof.verify(&leaves[(index + 1) % 3]));
+		}
+		let presentation = PresentationOf::<Test> {
+			credential_ids: ids.clone(),
+			holder: HOLDER_DID.to_vec(),
+			challenge: b"challenge".to_vec(),
+			domain: b"verifier.example".to_vec(),
+			signature: account_pair.sign(b""),
+		};
+		let issuer_signed = || {
+			CredentialRegistry::verify_presentation(&presentation)
+				.credentials
+				.iter()
+				.map(|verification| verification.issuer_signature_valid)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(issuer_signed(), vec![true, true, true]);
+
+		// an updated credential is signed on its own and leaves the batch
+		let updated = CredentialOf::<Test> {
+			context: bounded(b"Updated context"),
+			nonce: credential.nonce + 3,
+			..credential.clone()
+		};
+		assert_ok!(CredentialRegistry::update_credential(
+			RawOrigin::Signed(signer).into(),
+			ids[1],
+			(account_pair.sign(&updated.encode()), updated)
+		));
+		// the batch still vouches for the credential until the holder accepts the update
+		assert!(CredentialRegistry::batch_proof(&ids[1]).is_some());
+		accept_test_credential(ids[1]);
+		assert_eq!(CredentialRegistry::batch_proof(&ids[1]), None);
+		assert_ok!(CredentialRegistry::delete_credential(
+			RawOrigin::Signed(signer).into(),
+			ids[2],
+			0
+		));
+		assert_eq!(CredentialRegistry::batch_proof(&ids[2]), None);
+		assert_eq!(issuer_signed(), vec![true, true, false]);
+		assert!(CredentialRegistry::batch_proof(&ids[0]).unwrap().verify(&leaves[0]));
+		assert_eq!(BatchDeposits::<Test>::get(root).map(|(_, _, remaining)| remaining), Some(1));
+		assert_ok!(CredentialRegistry::delete_credential(
+			RawOrigin::Signed(signer).into(),
+			ids[0],
+			0
+		));
+		assert_eq!(CredentialRegistry::credential_batch(root), None);
+		assert_eq!(BatchDeposits::<Test>::get(root), None);
+
+		// offers that lapse take their batch with them as well
+		let lapsing: Vec<CredentialOf<Test>> = (4..6)
+			.map(|i| CredentialOf::<Test> { nonce: credential.nonce + i, ..credential.clone() })
+			.collect();
+		let lapsing_root = merkle::root(&leaves_of(&lapsing));
+		let reserved = Balances::reserved_balance(&signer);
+		assert_ok!(batch(&lapsing, account_pair.sign(&lapsing_root)));
+		assert_ok!(CredentialRegistry::reject_credential(
+			RawOrigin::Signed(signer).into(),
+			FIRST_ASSIGNED_ID + 3
+		));
+		assert!(CredentialRegistry::credential_batch(lapsing_root).is_some());
+		CredentialRegistry::on_initialize(11);
+		assert_eq!(CredentialRegistry::credential_batch(lapsing_root), None);
+		assert_eq!(BatchDeposits::<Test>::get(lapsing_root), None);
+		assert_eq!(Balances::reserved_balance(&signer), reserved);
+	});
+}
+
+#[test]
+fn merkle_proofs_verify_every_leaf() {
+	assert_eq!(merkle::root(&[[7; 32]]), [7; 32]);
+	for count in 1u32..=9 {
+		let leaves: Vec<[u8; 32]> =
+			(0..count).map(|i| sp_io::hashing::blake2_256(&i.encode())).collect();
+		let root = merkle::root(&leaves);
+		for index in 0..count {
+			let leaf = &leaves[index as usize];
+			let proof = merkle::proof(&leaves, index).unwrap();
+			assert!(merkle::verify(&root, leaf, index, count, &proof));
+			assert!(!merkle::verify(&root, &[0; 32], index, count, &proof));
+			let padded = [proof.clone(), vec![[0; 32]]].concat();
+			assert!(!merkle::verify(&root, leaf, index, count, &padded));
+			if count > 1 {
+				assert!(!merkle::verify(&root, leaf, (index + 1) % count, count, &proof));
+			}
+		}
+		assert_eq!(merkle::proof(&leaves, count), None);
+		assert!(!merkle::verify(&root, &leaves[0], count, count, &[]));
+	}
+}
+
+#[test]
+fn delegates_issue_within_their_delegation() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let bob = account_pair("Bob");
+		let charlie = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let issuer: DidOf<Test> = bounded(account_id.as_bytes());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		let nonce = |offset: u64| CredentialOf::<Test> {
+			nonce: credential.nonce + offset,
+			..credential.clone()
+		};
+		let create = |id: u32, credential: &CredentialOf<Test>, signature| {
+			CredentialRegistry::create_credential(
+				RawOrigin::Signed(charlie.public()).into(),
+				Some(id),
+				credential.context.clone(),
+				credential.schema,
+				credential.schema_version,
+				credential.issuer.clone(),
+				credential.issuance_date,
+				credential.expiration_date,
+				credential.subject.clone(),
+				credential.credential_holder.clone(),
+				signature,
+				credential.nonce,
+			)
+		};
+		let delegate = |who: sr25519::Public, expiration_date, max_credentials| {
+			CredentialRegistry::add_delegate(
+				RawOrigin::Signed(who).into(),
+				issuer.clone(),
+				charlie.public(),
+				bounded_vec![credential.schema],
+				expiration_date,
+				max_credentials,
+			)
+		};
+
+		// only the issuer grants delegations, and only delegates issue on its behalf
+		assert_noop!(
+			create(1, &credential, charlie.sign(&credential.encode())),
+			Error::<Test>::NotCredentialOwner
+		);
+		assert_noop!(delegate(charlie.public(), None, 2), Error::<Test>::NotCredentialOwner);
+		assert_ok!(delegate(signer, None, 2));
+		System::assert_last_event(
+			crate::Event::DelegateAdded {
+				issuer_did: account_id.as_bytes().to_vec(),
+				delegate: charlie.public(),
+			}
+			.into(),
+		);
+		assert_noop!(delegate(signer, None, 5), Error::<Test>::DelegateAlreadyAdded);
+
+		// the delegate signs with its own key and is recorded as the signer
+		assert_noop!(
+			create(1, &credential, account_pair.sign(&credential.encode())),
+			Error::<Test>::SignatureVerifyError
+		);
+		assert_ok!(create(1, &credential, charlie.sign(&credential.encode())));
+		accept_test_credential(1);
+		assert_eq!(CredentialRegistry::credential_delegate(1), Some(charlie.public()));
+		assert_eq!(
+			CredentialRegistry::delegation(&issuer, charlie.public()).map(|d| d.issued),
+			Some(1)
+		);
+		let mut presentation = PresentationOf::<Test> {
+			credential_ids: vec![1],
+			holder: HOLDER_DID.to_vec(),
+			challenge: b"challenge".to_vec(),
+			domain: b"verifier.example".to_vec(),
+			signature: bob.sign(b""),
+		};
+		presentation.signature = bob.sign(&presentation.payload(&System::block_hash(0)));
+		assert!(CredentialRegistry::verify_presentation(&presentation).is_valid());
+
+		// W3C documents name the delegate key as the verification method
+		let (signature, stored) = CredentialStore::<Test>::get(1).unwrap();
+		let delegate_did = format!("did:seneca:{}", charlie.public().into_account());
+		let document =
+			w3c::to_w3c(1, &stored, &signature, Some(delegate_did.as_bytes()), None).unwrap();
+		assert_eq!(document.proof.verification_method, delegate_did);
+		let (_, imported, imported_signature, imported_delegate) =
+			w3c::from_w3c::<u64, CredentialLimitsOf<Test>, sr25519::Signature>(&document).unwrap();
+		assert_eq!(imported_delegate.as_deref(), Some(delegate_did.as_bytes()));
+		assert_ok!(w3c::verify_proof::<MockDidResolver, _, _, _>(
+			&imported,
+			&imported_signature,
+			imported_delegate.as_deref()
+		));
+		assert_eq!(
+			w3c::verify_proof::<MockDidResolver, _, _, _>(&imported, &imported_signature, None),
+			Err(w3c::W3cError::InvalidSignature)
+		);
+
+		// delegations are scoped to schemas and capped in count
+		let other_schema = CredentialOf::<Test> { schema: 7, ..nonce(1) };
+		assert_noop!(
+			create(2, &other_schema, charlie.sign(&other_schema.encode())),
+			Error::<Test>::SchemaNotDelegated
+		);
+		assert_ok!(create(2, &nonce(1), charlie.sign(&nonce(1).encode())));
+		assert_noop!(
+			create(3, &nonce(2), charlie.sign(&nonce(2).encode())),
+			Error::<Test>::DelegationExhausted
+		);
+		// offers that are not accepted don't count against the delegation
+		assert_ok!(CredentialRegistry::reject_credential(RawOrigin::Signed(bob.public()).into(), 2));
+		assert_eq!(
+			CredentialRegistry::delegation(&issuer, charlie.public()).map(|d| d.issued),
+			Some(1)
+		);
+
+		// revoking the delegation keeps the credentials it issued
+		assert_ok!(CredentialRegistry::remove_delegate(
+			RawOrigin::Signed(signer).into(),
+			issuer.clone(),
+			charlie.public()
+		));
+		System::assert_last_event(
+			crate::Event::DelegateRemoved {
+				issuer_did: account_id.as_bytes().to_vec(),
+				delegate: charlie.public(),
+			}
+			.into(),
+		);
+		assert_noop!(
+			CredentialRegistry::remove_delegate(
+				RawOrigin::Signed(signer).into(),
+				issuer.clone(),
+				charlie.public()
+			),
+			Error::<Test>::UnknownDelegate
+		);
+		assert_noop!(
+			create(3, &nonce(2), charlie.sign(&nonce(2).encode())),
+			Error::<Test>::NotCredentialOwner
+		);
+		assert!(CredentialRegistry::verify_presentation(&presentation).is_valid());
+
+		// delegates sign batches too, until their delegation expires
+		assert_ok!(delegate(signer, Some(5), 5));
+		let batch = vec![nonce(2)];
+		let root = merkle::root(&[sp_io::hashing::blake2_256(&batch[0].encode())]);
+		assert_ok!(CredentialRegistry::create_credentials_batch(
+			RawOrigin::Signed(charlie.public()).into(),
+			issuer.clone(),
+			batch.try_into().unwrap(),
+			charlie.sign(&root)
+		));
+		assert_eq!(
+			CredentialRegistry::credential_delegate(FIRST_ASSIGNED_ID),
+			Some(charlie.public())
+		);
+		accept_test_credential(FIRST_ASSIGNED_ID);
+		presentation.credential_ids = vec![FIRST_ASSIGNED_ID, 1];
+		presentation.signature = bob.sign(&presentation.payload(&System::block_hash(0)));
+		assert!(CredentialRegistry::verify_presentation(&presentation).is_valid());
+		Timestamp::set_timestamp(6);
+		assert_noop!(
+			create(3, &nonce(3), charlie.sign(&nonce(3).encode())),
+			Error::<Test>::DelegationExpired
+		);
+
+		// an update by the issuer signs the credential with the issuer key again
+		let updated = CredentialOf::<Test> { context: bounded(b"Updated context"), ..nonce(3) };
+		assert_ok!(CredentialRegistry::update_credential(
+			RawOrigin::Signed(signer).into(),
+			1,
+			(account_pair.sign(&updated.encode()), updated)
+		));
+		accept_test_credential(1);
+		assert_eq!(CredentialRegistry::credential_delegate(1), None);
+		assert!(CredentialRegistry::verify_presentation(&presentation).is_valid());
+	});
+}
+
+#[test]
+fn delegations_hold_a_deposit() {
+	new_test_ext().execute_with(|| {
+		let charlie = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let issuer: DidOf<Test> = bounded(account_id.as_bytes());
+		let delegate = |expiration_date, max_credentials| {
+			CredentialRegistry::add_delegate(
+				RawOrigin::Signed(signer).into(),
+				issuer.clone(),
+				charlie.public(),
+				bounded_vec![123456],
+				expiration_date,
+				max_credentials,
+			)
+		};
+		let reap = || {
+			CredentialRegistry::reap_delegate(
+				RawOrigin::Signed(charlie.public()).into(),
+				issuer.clone(),
+				charlie.public(),
+			)
+		};
+		let reserved = Balances::reserved_balance(&signer);
+
+		assert_ok!(delegate(None, 2));
+		let delegation = CredentialRegistry::delegation(&issuer, charlie.public()).unwrap();
+		let deposit = 50 + delegation.encoded_size() as u64;
+		assert_eq!(Balances::reserved_balance(&signer), reserved + deposit);
+		// delegations that can still issue are only removed by their issuer
+		assert_noop!(reap(), Error::<Test>::DelegationActive);
+		assert_ok!(CredentialRegistry::remove_delegate(
+			RawOrigin::Signed(signer).into(),
+			issuer.clone(),
+			charlie.public()
+		));
+		assert_eq!(Balances::reserved_balance(&signer), reserved);
+
+		// expired or exhausted delegations are reaped by anyone
+		assert_ok!(delegate(Some(5), 2));
+		assert_noop!(reap(), Error::<Test>::DelegationActive);
+		Timestamp::set_timestamp(6);
+		assert_ok!(reap());
+		assert!(CredentialRegistry::delegation(&issuer, charlie.public()).is_none());
+		assert_eq!(Balances::reserved_balance(&signer), reserved);
+		assert_ok!(delegate(None, 0));
+		assert_ok!(reap());
+		assert_eq!(Balances::reserved_balance(&signer), reserved);
+		assert_noop!(reap(), Error::<Test>::UnknownDelegate);
+	});
+}
+
+#[test]
+fn delegates_withdraw_the_offers_they_made() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dave = account_pair("Dave");
+		let charlie = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let issuer: DidOf<Test> = bounded(account_id.as_bytes());
+		let credential = test_credential(&account_id, 123456u32);
+		create_test_schema(&account_pair, credential.schema);
+		assert_ok!(CredentialRegistry::add_delegate(
+			RawOrigin::Signed(signer).into(),
+			issuer.clone(),
+			charlie.public(),
+			bounded_vec![credential.schema],
+			None,
+			2,
+		));
+		let reserved = Balances::reserved_balance(&charlie.public());
+		assert_ok!(CredentialRegistry::create_credential(
+			RawOrigin::Signed(charlie.public()).into(),
+			Some(1),
+			credential.context.clone(),
+			credential.schema,
+			credential.schema_version,
+			credential.issuer.clone(),
+			credential.issuance_date,
+			credential.expiration_date,
+			credential.subject.clone(),
+			credential.credential_holder.clone(),
+			charlie.sign(&credential.encode()),
+			credential.nonce,
+		));
+		let issued = || CredentialRegistry::delegation(&issuer, charlie.public()).unwrap().issued;
+		assert_eq!(issued(), 1);
+
+		// only the holder, the issuer and the delegate that made the offer may drop it
+		assert_noop!(
+			CredentialRegistry::reject_credential(RawOrigin::Signed(dave.public()).into(), 1),
+			Error::<Test>::NotCredentialHolder
+		);
+		assert_ok!(CredentialRegistry::reject_credential(
+			RawOrigin::Signed(charlie.public()).into(),
+			1
+		));
+		System::assert_last_event(crate::Event::CredentialOfferRejected(1).into());
+		assert_eq!(CredentialRegistry::credential_offer(1), None);
+		assert_eq!(issued(), 0);
+		assert_eq!(Balances::reserved_balance(&charlie.public()), reserved);
+	});
+}
+
+#[test]
+fn migration_adds_empty_claim_commitments() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		let old = old_layout(&credential);
+		let credential_sig = account_pair.sign(&old.encode());
+		unhashed::put(&CredentialStore::<Test>::hashed_key_for(1), &(credential_sig.clone(), old));
+		StorageVersion::new(3).put::<CredentialRegistry>();
+
+		v4::MigrateToV4::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<CredentialRegistry>(), 4);
+		assert_eq!(CredentialRegistry::credential_registry(1), Some((credential_sig, credential)));
+		// the signature is checked against the layout it was made over
+		assert_eq!(LegacyEncodings::<Test>::get(1), Some(LegacyEncoding::Uncommitted));
+		assert!(issuer_signature_valid(1));
+	});
+}
+
+#[test]
+fn migration_counts_schema_references() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		let old = old_layout(&credential);
+		let credential_sig = account_pair.sign(&old.encode());
+		for id in [1u32, 2] {
+			v4::CredentialStore::<Test>::insert(id, (credential_sig.clone(), old.clone()));
+		}
+		StorageVersion::new(2).put::<CredentialRegistry>();
+
+		v3::MigrateToV3::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<CredentialRegistry>(), 3);
+		assert_eq!(SchemaReferences::<Test>::get(123456u32), 2);
+	});
+}
+
+#[test]
+fn migration_bounds_existing_credentials() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		let fitting = v1::OldVerifiableCredential::<u64> {
+			context: credential.context.to_vec(),
+			schema: credential.schema,
+			issuer: credential.issuer.to_vec(),
+			issuance_date: credential.issuance_date,
+			expiration_date: credential.expiration_date,
+			// the unbounded subject encodes the same as the bounded one
+			subject: v1::OldSubject::decode(&mut &old_layout(&credential).subject.encode()[..])
+				.unwrap(),
+			credential_holder: credential.credential_holder.to_vec(),
+			nonce: credential.nonce,
+		};
+		let oversized = v1::OldVerifiableCredential {
+			context: vec![b'c'; 257],
+			..fitting.clone()
+		};
+		let credential_sig = account_pair.sign(&credential.encode());
+		for (id, old) in [(1u32, &fitting), (2u32, &oversized)] {
+			unhashed::put(
+				&CredentialStore::<Test>::hashed_key_for(id),
+				&(credential_sig.clone(), old),
+			);
+			assert_ok!(Balances::reserve(&signer, 50));
+			CredentialDeposits::<Test>::insert(id, (signer, 50u64));
+		}
+		v1::shared::Nonce::<Test>::put(3);
+		StorageVersion::new(0).put::<CredentialRegistry>();
+
+		v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<CredentialRegistry>(), 1);
+		// the nonce shared by all issuers is gone, nonces are kept per DID
+		assert!(!v1::shared::Nonce::<Test>::exists());
+		v2::MigrateToV2::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<CredentialRegistry>(), 2);
+		assert_eq!(
+			v4::CredentialStore::<Test>::get(1),
+			Some((credential_sig, old_layout(&credential)))
+		);
+		// the credential that no longer fits is dropped and its deposit released
+		assert_eq!(v4::CredentialStore::<Test>::get(2), None);
+		assert_eq!(CredentialDeposits::<Test>::get(2), None);
+		assert_eq!(Balances::reserved_balance(&signer), 50);
+	});
+}
+
+#[test]
+fn migration_pins_credentials_to_latest_schema_version() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema_id = 123456u32;
+		create_test_schema(&account_pair, schema_id);
+		update_test_schema(&account_pair, schema_id, test_schema(&account_id));
+		let credential = test_credential(&account_id, schema_id);
+		let old = v2::OldVerifiableCredential {
+			context: credential.context.clone(),
+			schema: credential.schema,
+			issuer: credential.issuer.clone(),
+			issuance_date: credential.issuance_date,
+			expiration_date: credential.expiration_date,
+			subject: old_layout(&credential).subject,
+			credential_holder: credential.credential_holder.clone(),
+			nonce: credential.nonce,
+		};
+		let credential_sig = account_pair.sign(&old.encode());
+		unhashed::put(&CredentialStore::<Test>::hashed_key_for(1), &(credential_sig.clone(), old));
+		StorageVersion::new(1).put::<CredentialRegistry>();
+
+		v2::MigrateToV2::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<CredentialRegistry>(), 2);
+		let pinned = v4::OldCredential::<Test> { schema_version: 1, ..old_layout(&credential) };
+		assert_eq!(v4::CredentialStore::<Test>::get(1), Some((credential_sig, pinned)));
+
+		// the pinned version is left out of the layout the signature is checked against, also
+		// once claim commitments are added
+		v4::MigrateToV4::<Test>::on_runtime_upgrade();
+		assert_eq!(LegacyEncodings::<Test>::get(1), Some(LegacyEncoding::Unversioned));
+		assert!(issuer_signature_valid(1));
+		LegacyEncodings::<Test>::remove(1);
+		assert!(!issuer_signature_valid(1));
+		LegacyEncodings::<Test>::insert(1, LegacyEncoding::Unversioned);
+
+		assert_ok!(CredentialRegistry::delete_credential(
+			RawOrigin::Signed(account_pair.public()).into(),
+			1,
+			0
+		));
+		assert_eq!(LegacyEncodings::<Test>::get(1), None);
+	});
+}
+
+#[test]
+fn migration_indexes_existing_credentials() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema_id = 123456u32;
+		let credential = test_credential(&account_id, schema_id);
+		let credential_sig = account_pair.sign(&credential.encode());
+		for id in 0..=MAX_PAGE_SIZE {
+			CredentialStore::<Test>::insert(id, (credential_sig.clone(), credential.clone()));
+		}
+		StorageVersion::new(4).put::<CredentialRegistry>();
+
+		v5::MigrateToV5::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<CredentialRegistry>(), 5);
+		assert!(CredentialsByHolder::<Test>::contains_key(&credential.credential_holder, 0));
+		assert!(CredentialsBySubject::<Test>::contains_key(&credential.subject.id, 0));
+		assert_eq!(
+			CredentialRegistry::get_credentials_by_creator(&credential.issuer, None, 1)[0].1,
+			credential
+		);
+		// pages are capped whatever the limit asked for
+		assert_eq!(
+			CredentialRegistry::get_credentials_by_schemaid(&schema_id, None, u32::MAX).len(),
+			MAX_PAGE_SIZE as usize
+		);
+	});
+}
+
+#[test]
+fn migration_records_credential_signers() {
+	new_test_ext().execute_with(|| {
+		let charlie = account_pair("Charlie");
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let credential = test_credential(&account_id, 123456u32);
+		CredentialStore::<Test>::insert(1, (account_pair.sign(&credential.encode()), &credential));
+		CredentialStore::<Test>::insert(2, (charlie.sign(&credential.encode()), &credential));
+		CredentialDelegates::<Test>::insert(2, charlie.public());
+		StorageVersion::new(5).put::<CredentialRegistry>();
+
+		v6::MigrateToV6::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<CredentialRegistry>(), 6);
+		assert_eq!(CredentialRegistry::credential_signer(1), Some(account_pair.public()));
+		assert_eq!(CredentialRegistry::credential_signer(2), Some(charlie.public()));
+		rotate_key(account_id.as_bytes(), charlie.public());
+		assert!(issuer_signature_valid(1));
+	});
+}
+
+#[test]
+fn w3c_documents_round_trip_stored_credentials() {
+	new_test_ext().execute_with(|| {
+		let bob = account_pair("Bob");
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		Timestamp::set_timestamp(1_000);
+		let credential = CredentialOf::<Test> {
+			issuance_date: Some(123),
+			expiration_date: None,
+			..test_credential(&account_id, 123456u32)
+		};
+		create_test_schema(&account_pair, credential.schema);
+		create_test_credential(&account_pair, 1, &credential);
+		let (signature, stored) = CredentialStore::<Test>::get(1).unwrap();
+
+		let document = w3c::to_w3c(1, &stored, &signature, None, None).unwrap();
+		let json = serde_json::to_value(&document).unwrap();
+		assert_eq!(json["@context"][0], w3c::CREDENTIALS_V1_CONTEXT);
+		assert_eq!(json["@context"][1], "Credential context");
+		assert_eq!(json["id"], "urn:seneca:credential:1");
+		assert_eq!(json["issuer"], account_id.as_str());
+		assert_eq!(json["issuanceDate"], "1970-01-01T00:00:00.123Z");
+		assert!(json.get("expirationDate").is_none());
+		assert_eq!(json["credentialSchema"]["id"], "urn:seneca:schema:123456");
+		assert_eq!(json["credentialSubject"]["claims"][1]["value"], "0x1234");
+		assert_eq!(json["proof"]["verificationMethod"], account_id.as_str());
+
+		// importing the JSON reproduces the signed payload
+		let parsed: w3c::W3cCredential = serde_json::from_value(json).unwrap();
+		let (id, imported, imported_signature, delegate): (
+			_,
+			CredentialOf<Test>,
+			sr25519::Signature,
+			_,
+		) = w3c::from_w3c(&parsed).unwrap();
+		assert_eq!((id, &imported, &imported_signature), (Some(1), &stored, &signature));
+		assert_eq!(delegate, None);
+		assert_ok!(w3c::verify_proof::<MockDidResolver, _, _, _>(
+			&imported,
+			&imported_signature,
+			None
+		));
+
+		let tampered = CredentialOf::<Test> { nonce: imported.nonce + 1, ..imported.clone() };
+		assert_eq!(
+			w3c::verify_proof::<MockDidResolver, _, _, _>(&tampered, &imported_signature, None),
+			Err(w3c::W3cError::InvalidSignature)
+		);
+		let forged = bob.sign(&imported.encode());
+		assert_eq!(
+			w3c::verify_proof::<MockDidResolver, _, _, _>(&imported, &forged, None),
+			Err(w3c::W3cError::InvalidSignature)
+		);
+
+		// credentials signed in a legacy encoding have no proof the document could carry
+		let legacy = Some(LegacyEncoding::Unversioned);
+		assert_eq!(
+			w3c::to_w3c(1, &stored, &signature, None, legacy).err(),
+			Some(w3c::W3cError::LegacyEncoding)
+		);
+		// timestamps only have room for four digit years
+		let last = CredentialOf::<Test> { expiration_date: Some(253_402_300_799_999), ..stored };
+		let document = w3c::to_w3c(1, &last, &signature, None, None).unwrap();
+		assert_eq!(document.expiration_date.as_deref(), Some("9999-12-31T23:59:59.999Z"));
+		let beyond = CredentialOf::<Test> { expiration_date: Some(253_402_300_800_000), ..last };
+		assert_eq!(
+			w3c::to_w3c(1, &beyond, &signature, None, None).err(),
+			Some(w3c::W3cError::DateOutOfRange)
+		);
+	});
+}
+
+#[test]
+fn w3c_import_validates_documents() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let mut credential = test_credential(&account_id, 123456u32);
+		credential.subject.committed_claims =
+			bounded_vec![ClaimCommitment { property: bounded(b"property"), commitment: [7; 32] }];
+		let signature = account_pair.sign(&credential.encode());
+		let document = w3c::to_w3c(1, &credential, &signature, None, None).unwrap();
+		let import = |document: &w3c::W3cCredential| {
+			w3c::from_w3c::<u64, CredentialLimitsOf<Test>, sr25519::Signature>(document)
+				.map(|(_, credential, _, _)| credential)
+		};
+		assert_eq!(document.expiration_date.as_deref(), Some("1970-01-20T16:52:59.816Z"));
+		assert_eq!(import(&document), Ok(credential.clone()));
+
+		// VC Data Model 2.0 documents name the validity period differently
+		let mut json = serde_json::to_value(&document).unwrap();
+		json["@context"][0] = w3c::CREDENTIALS_V2_CONTEXT.into();
+		let issuance_date = json.as_object_mut().unwrap().remove("issuanceDate").unwrap();
+		json["validFrom"] = issuance_date;
+		assert_eq!(import(&serde_json::from_value(json).unwrap()), Ok(credential.clone()));
+
+		let mut invalid = document.clone();
+		invalid.context = vec!["https://example.com/context".into()];
+		assert_eq!(import(&invalid), Err(w3c::W3cError::UnsupportedContext));
+		let mut invalid = document.clone();
+		invalid.types = vec!["Credential".into()];
+		assert_eq!(import(&invalid), Err(w3c::W3cError::NotVerifiableCredential));
+		let mut invalid = document.clone();
+		invalid.issuance_date = Some("2023-02-30T00:00:00Z".into());
+		assert_eq!(import(&invalid), Err(w3c::W3cError::InvalidDate));
+		let mut invalid = document.clone();
+		invalid.credential_schema.id = "urn:other:schema:1".into();
+		assert_eq!(import(&invalid), Err(w3c::W3cError::InvalidSchema));
+		let mut invalid = document.clone();
+		invalid.proof.verification_method = "someone-else".into();
+		assert_eq!(import(&invalid), Err(w3c::W3cError::VerificationMethodMismatch));
+		let mut invalid = document.clone();
+		invalid.proof.proof_value = "0x1234".into();
+		assert_eq!(import(&invalid), Err(w3c::W3cError::InvalidProofValue));
+		let mut invalid = document.clone();
+		invalid.credential_subject.committed_claims[0].commitment = "0x1234".into();
+		assert_eq!(import(&invalid), Err(w3c::W3cError::InvalidCommitment));
+		let mut invalid = document;
+		invalid.holder = "did:seneca:".repeat(20);
+		assert_eq!(import(&invalid), Err(w3c::W3cError::TooLong("holder")));
+	});
+}
+
+fn test_schema(creator: &str) -> SchemaOf<Test> {
+	SchemaOf::<Test> {
+		name: bounded(b"name"),
+		creator: bounded(creator.as_bytes()),
+		public: false,
+		creation_date: Timestamp::now(),
+		expiration_date: Some(1702379816u64),
+		mandatory_fields: bounded_vec![Attribute {
+			name: bounded(b"name"),
+			attribute_type: AttributeType::Hex,
+		}],
+		issuer_claims: bounded_vec![Claim {
+			property: bounded(b"property"),
+			value: bounded(b"value"),
+			schemaid: None,
+			claim_type: ClaimType::IssuerClaim,
+			issuance_requirement: None,
+		}],
+		subject_claims: bounded_vec![Claim {
+			property: bounded(b"property"),
+			value: bounded(b"value"),
+			schemaid: None,
+			claim_type: ClaimType::SubjectClaim,
+			issuance_requirement: None,
+		}],
+		credential_claims: bounded_vec![Claim {
+			property: bounded(b"property"),
+			value: bounded(b"value"),
+			schemaid: None,
+			claim_type: ClaimType::CredentialClaim,
+			issuance_requirement: None,
+		}],
+		metadata: bounded(b"metadata"),
+		nonce: 2u64,
+	}
+}
+
+fn test_credential(issuer: &str, schema: u32) -> CredentialOf<Test> {
+	CredentialOf::<Test> {
+		context: bounded(b"Credential context"),
+		schema,
+		schema_version: 0,
+		issuer: bounded(issuer.as_bytes()),
+		issuance_date: Some(Timestamp::now()),
+		expiration_date: Some(1702379816u64),
+		subject: Subject {
+			id: bounded(b"did:seneca:5GFEtniprMeFuh8HcoVrWxz4aQtv6T5V9bkENSnfPYhY4p8H"),
+			claim: bounded_vec![
+				Claim {
+					property: bounded(b"property"),
+					value: bounded(b"value"),
+					schemaid: None,
+					claim_type: ClaimType::SubjectClaim,
+					issuance_requirement: None,
+				},
+				Claim {
+					property: bounded(b"name"),
+					value: bounded(b"0x1234"),
+					schemaid: None,
+					claim_type: ClaimType::SubjectClaim,
+					issuance_requirement: None,
+				},
+			],
+			committed_claims: Default::default(),
+		},
+		credential_holder: bounded(HOLDER_DID),
+		nonce: 2u64,
+	}
+}
+
+// `credential` in the layout stored before claim commitments
+fn old_layout(credential: &CredentialOf<Test>) -> v4::OldCredential<Test> {
+	v4::OldCredential::<Test> {
+		context: credential.context.clone(),
+		schema: credential.schema,
+		schema_version: credential.schema_version,
+		issuer: credential.issuer.clone(),
+		issuance_date: credential.issuance_date,
+		expiration_date: credential.expiration_date,
+		subject: v4::OldSubject {
+			id: credential.subject.id.clone(),
+			claim: credential.subject.claim.clone(),
+		},
+		credential_holder: credential.credential_holder.clone(),
+		nonce: credential.nonce,
+	}
+}
+
+fn create_test_schema(account_pair: &sp_core::sr25519::Pair, schema_id: u32) {
+	let creator = format!("did:seneca:{}", account_pair.public().into_account());
+	let schema = test_schema(&creator);
+	let schema_sig = account_pair.sign(&schema.encode());
+	assert_ok!(SchemaRegistry::create_schema(
+		RawOrigin::Signed(account_pair.public()).into(),
+		Some(schema_id),
+		schema.name,
+		schema.creator,
+		schema.public,
+		schema.mandatory_fields,
+		schema.creation_date,
+		schema.expiration_date,
+		schema.issuer_claims,
+		schema.subject_claims,
+		schema.credential_claims,
+		schema.metadata,
+		schema_sig,
+		schema.nonce
+	));
+}
+
+// Publish `schema` as the next version of schema `schema_id`
+fn update_test_schema(
+	account_pair: &sp_core::sr25519::Pair,
+	schema_id: u32,
+	schema: SchemaOf<Test>,
+) {
+	let nonce = SchemaRegistry::get_nonce(&schema.creator);
+	let schema = SchemaOf::<Test> {
+		metadata: bounded(format!("metadata{}", nonce).as_bytes()),
+		nonce,
+		..schema
+	};
+	assert_ok!(SchemaRegistry::update_schema(
+		RawOrigin::Signed(account_pair.public()).into(),
+		schema_id,
+		(account_pair.sign(&schema.encode()), schema)
+	));
+}
+
+fn create_test_credential(
+	account_pair: &sp_core::sr25519::Pair,
+	id: u32,
+	credential: &CredentialOf<Test>,
+) {
+	let credential_sig = account_pair.sign(&credential.encode());
+	assert_ok!(CredentialRegistry::create_credential(
+		RawOrigin::Signed(account_pair.public()).into(),
+		Some(id),
+		credential.context.clone(),
+		credential.schema,
+		credential.schema_version,
+		credential.issuer.clone(),
+		credential.issuance_date,
+		credential.expiration_date,
+		credential.subject.clone(),
+		credential.credential_holder.clone(),
+		credential_sig,
+		credential.nonce
+	));
+	accept_test_credential(id);
+}
+
+// Whether the stored signature of credential `id` verifies, as reported to verifiers
+fn issuer_signature_valid(id: u32) -> bool {
+	let presentation = PresentationOf::<Test> {
+		credential_ids: vec![id],
+		holder: HOLDER_DID.to_vec(),
+		challenge: Vec::new(),
+		domain: Vec::new(),
+		signature: account_pair("Bob").sign(b""),
+	};
+	CredentialRegistry::verify_presentation(&presentation).credentials[0].issuer_signature_valid
+}
+
+// Accept the credential offered as `id` with the key of Bob, who holds the test credentials
+fn accept_test_credential(id: u32) {
+	let holder = account_pair("Bob");
+	let (_, credential, ..) = CredentialRegistry::credential_offer(id).unwrap();
+	assert_ok!(CredentialRegistry::accept_credential(
+		RawOrigin::Signed(holder.public()).into(),
+		id,
+		holder.sign(&sp_io::hashing::blake2_256(&credential.encode()))
+	));
+}
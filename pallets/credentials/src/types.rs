@@ -1,29 +1,308 @@
-use codec::{Decode, Encode};
-use frame_support::RuntimeDebug;
-use pallet_schemas::types::Claim;
-use scale_info::prelude::vec::Vec;
-use scale_info::TypeInfo;
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct VerifiableCredentialObject<Public, Moment> {
-	pub verifiable_credential: VerifiableCredential<Moment>,
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{traits::Get, BoundedVec, RuntimeDebug};
+use pallet_schemas::types::{BoundedClaims, BoundedDid, BoundedName, Claim, SchemaLimits};
+pub use pallet_schemas::types::UpdateRecord;
+use scale_info::{prelude::vec::Vec, TypeInfo};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Bounds on the variable length fields of credentials, on top of those of their claims.
+pub trait CredentialLimits: SchemaLimits {
+	/// Longest credential context.
+	type MaxContextLength: Get<u32>;
+	/// Most schemas a single issuer delegation covers.
+	type MaxDelegatedSchemas: Get<u32>;
+}
+
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound(Public: MaxEncodedLen, Moment: MaxEncodedLen))]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "Public: Serialize, Moment: Serialize",
+		deserialize = "Public: Deserialize<'de>, Moment: Deserialize<'de>"
+	))
+)]
+pub struct VerifiableCredentialObject<Public, Moment, L: CredentialLimits> {
+	pub verifiable_credential: VerifiableCredential<Moment, L>,
 	pub registrar: Public,
 	pub registration_date: Moment,
 }
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct VerifiableCredential<Moment> {
-	pub context: Vec<u8>,
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound(Moment: MaxEncodedLen))]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(serialize = "Moment: Serialize", deserialize = "Moment: Deserialize<'de>"))
+)]
+pub struct VerifiableCredential<Moment, L: CredentialLimits> {
+	pub context: BoundedVec<u8, L::MaxContextLength>,
 	pub schema: u32,
-	pub issuer: Vec<u8>,
+	/// Version of `schema` the credential was issued against.
+	pub schema_version: u32,
+	pub issuer: BoundedDid<L>,
 	pub issuance_date: Option<Moment>,
 	pub expiration_date: Option<Moment>,
-	pub subject: Subject,
-	pub credential_holder: Vec<u8>,
+	pub subject: Subject<L>,
+	pub credential_holder: BoundedDid<L>,
 	pub nonce: u64,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct Subject {
-	pub id: Vec<u8>,
-	pub claim: Vec<Claim>,
+impl<Moment: Encode, L: CredentialLimits> VerifiableCredential<Moment, L> {
+	/// The bytes its issuer signed, the SCALE encoding of the credential in the layout it was
+	/// issued in, `legacy` for credentials migrated from an earlier layout.
+	pub fn signed_encoding(&self, legacy: Option<LegacyEncoding>) -> Vec<u8> {
+		let subject = (&self.subject.id, &self.subject.claim);
+		match legacy {
+			None => self.encode(),
+			Some(LegacyEncoding::Unversioned) => (
+				&self.context,
+				self.schema,
+				&self.issuer,
+				&self.issuance_date,
+				&self.expiration_date,
+				subject,
+				&self.credential_holder,
+				self.nonce,
+			)
+				.encode(),
+			Some(LegacyEncoding::Uncommitted) => (
+				&self.context,
+				self.schema,
+				self.schema_version,
+				&self.issuer,
+				&self.issuance_date,
+				&self.expiration_date,
+				subject,
+				&self.credential_holder,
+				self.nonce,
+			)
+				.encode(),
+		}
+	}
+}
+
+/// Credential layout a credential migrated from an earlier storage version was signed in.
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Copy, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum LegacyEncoding {
+	/// Storage version 1, before credentials recorded the schema version they were issued
+	/// against.
+	Unversioned,
+	/// Storage versions 2 and 3, before subjects carried claim commitments.
+	Uncommitted,
+}
+
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct Subject<L: SchemaLimits> {
+	pub id: BoundedDid<L>,
+	pub claim: BoundedClaims<L>,
+	/// Claims disclosed selectively, the holder keeps their openings off-chain.
+	pub committed_claims: BoundedVec<ClaimCommitment<L>, L::MaxClaims>,
+}
+
+impl<L: SchemaLimits> Subject<L> {
+	/// Check every opening reveals a claim committed to by this subject.
+	pub fn verify_disclosure(&self, openings: &[ClaimOpening<L>]) -> Result<(), DisclosureError> {
+		for opening in openings {
+			let committed = self
+				.committed_claims
+				.iter()
+				.find(|committed| committed.property == opening.claim.property)
+				.ok_or(DisclosureError::UncommittedClaim)?;
+			if committed.commitment != opening.commitment() {
+				return Err(DisclosureError::CommitmentMismatch)
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Salted commitment to a claim. The property stays in clear so schemas can still require it.
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct ClaimCommitment<L: SchemaLimits> {
+	pub property: BoundedName<L>,
+	/// Blake2-256 hash of the encoded `ClaimOpening`.
+	pub commitment: [u8; 32],
+}
+
+/// A committed claim together with the salt hiding it.
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct ClaimOpening<L: SchemaLimits> {
+	pub claim: Claim<L>,
+	pub salt: [u8; 32],
+}
+
+impl<L: SchemaLimits> ClaimOpening<L> {
+	pub fn commitment(&self) -> [u8; 32] {
+		sp_io::hashing::blake2_256(&self.encode())
+	}
+
+	/// Commitment to this claim, as the issuer signs it.
+	pub fn committed(&self) -> ClaimCommitment<L> {
+		ClaimCommitment { property: self.claim.property.clone(), commitment: self.commitment() }
+	}
+}
+
+/// Reasons a presentation of committed claims is rejected.
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Copy, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum DisclosureError {
+	/// The credential does not exist.
+	UnknownCredential,
+	/// The credential is not active or outside its validity period.
+	CredentialNotValid,
+	/// The credential commits to no claim with the revealed property.
+	UncommittedClaim,
+	/// The revealed claim or salt does not match the commitment.
+	CommitmentMismatch,
+}
+
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Copy, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CredentialStatus {
+	Active,
+	Suspended,
+	Revoked,
+}
+
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct CredentialStatusRecord<BlockNumber, Moment> {
+	pub status: CredentialStatus,
+	pub reason: u16,
+	pub block: BlockNumber,
+	pub moment: Moment,
+}
+
+/// Credentials presented by their holder to a verifier, bound to the verifier's challenge and
+/// domain and to the chain by the holder's signature over [`Presentation::payload`].
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Presentation<CredentialId, Signature> {
+	pub credential_ids: Vec<CredentialId>,
+	/// DID of the holder presenting the credentials.
+	pub holder: Vec<u8>,
+	/// Verifier supplied nonce, so the presentation can't be replayed to another verification.
+	pub challenge: Vec<u8>,
+	/// Verifier the presentation is meant for.
+	pub domain: Vec<u8>,
+	pub signature: Signature,
+}
+
+impl<CredentialId: Encode, Signature> Presentation<CredentialId, Signature> {
+	/// The bytes the holder signs, the SCALE encoding of the genesis hash of the chain the
+	/// credentials are presented from, the presented ids, holder, challenge and domain.
+	pub fn payload<Hash: Encode>(&self, genesis_hash: &Hash) -> Vec<u8> {
+		(genesis_hash, &self.credential_ids, &self.holder, &self.challenge, &self.domain).encode()
+	}
+}
+
+/// Outcome of verifying a presentation, checked credential by credential.
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PresentationVerification<CredentialId> {
+	/// The presentation is signed with an authentication key of the holder DID.
+	pub holder_signature_valid: bool,
+	pub credentials: Vec<CredentialVerification<CredentialId>>,
+}
+
+impl<CredentialId> PresentationVerification<CredentialId> {
+	/// Whether the holder signature and every presented credential passed all checks.
+	pub fn is_valid(&self) -> bool {
+		self.holder_signature_valid &&
+			!self.credentials.is_empty() &&
+			self.credentials.iter().all(CredentialVerification::is_valid)
+	}
+}
+
+/// Checks of a single presented credential, all false for credentials that don't exist.
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct CredentialVerification<CredentialId> {
+	pub id: CredentialId,
+	pub found: bool,
+	/// The stored signature covers the credential and was made by its issuer.
+	pub issuer_signature_valid: bool,
+	/// The credential is held by the DID presenting it.
+	pub holder_bound: bool,
+	/// On-chain time is within the credential validity period.
+	pub not_expired: bool,
+	pub not_revoked: bool,
+	pub not_suspended: bool,
+	/// The schema version the credential was issued against is neither deprecated nor expired.
+	pub schema_valid: bool,
+}
+
+impl<CredentialId> CredentialVerification<CredentialId> {
+	pub fn is_valid(&self) -> bool {
+		self.found &&
+			self.issuer_signature_valid &&
+			self.holder_bound &&
+			self.not_expired &&
+			self.not_revoked &&
+			self.not_suspended &&
+			self.schema_valid
+	}
+}
+
+/// Right of a delegate account to issue credentials on behalf of an issuer DID.
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound(Moment: MaxEncodedLen))]
+pub struct Delegation<Moment, L: CredentialLimits> {
+	/// Schemas the delegate may issue credentials against.
+	pub schemas: BoundedVec<u32, L::MaxDelegatedSchemas>,
+	/// On-chain time after which the delegate may no longer issue.
+	pub expiration_date: Option<Moment>,
+	/// Number of credentials the delegate may issue in total.
+	pub max_credentials: u32,
+	/// Number of credentials the delegate issued so far, offers count until they are rejected
+	/// or expire.
+	pub issued: u32,
+}
+
+/// Proof that a credential was issued in the batch whose Merkle root its issuer signed, see
+/// [`crate::merkle`].
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BatchProof {
+	pub root: [u8; 32],
+	/// Position of the credential in the batch.
+	pub leaf_index: u32,
+	/// Number of credentials in the batch.
+	pub leaf_count: u32,
+	/// Sibling hashes from the credential leaf up to the root.
+	pub proof: Vec<[u8; 32]>,
+}
+
+impl BatchProof {
+	/// Whether the proof shows `leaf`, the Blake2-256 hash of an encoded credential, is
+	/// included under `root`.
+	pub fn verify(&self, leaf: &[u8; 32]) -> bool {
+		crate::merkle::verify(&self.root, leaf, self.leaf_index, self.leaf_count, &self.proof)
+	}
 }
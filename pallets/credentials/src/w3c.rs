@@ -0,0 +1,546 @@
+//! Conversion between stored credentials and W3C Verifiable Credentials Data Model JSON.
+//!
+//! [`to_w3c`] renders a credential and the signature it was stored with as a VC Data Model 1.1
+//! document, [`from_w3c`] validates such a document, or a 2.0 one using `validFrom` and
+//! `validUntil`, back into the on-chain structure. The `proofValue` carries the SCALE encoded
+//! signature over the SCALE encoded credential, so importing a document reproduces exactly the
+//! payload the issuer signed and [`verify_proof`] checks it like the pallet does. Committed
+//! claims are listed under `committedClaims` with their hex encoded commitments, their openings
+//! never appear in the document. The `verificationMethod` is the issuer DID, or the DID of the
+//! delegate account that signed the credential on behalf of the issuer, which the chain records
+//! in `CredentialDelegates`. Credentials issued in a batch are stored with the issuer
+//! signature over the batch root, which [`verify_proof`] rejects, they are checked against their
+//! `BatchProof` instead. Credentials whose signature covers one of the `LegacyEncodings` of
+//! older storage versions are not exported, the proof could not be checked against them.
+//!
+//! ```json
+//! {
+//!   "@context": ["https://www.w3.org/2018/credentials/v1", "<context>"],
+//!   "id": "urn:seneca:credential:1",
+//!   "type": ["VerifiableCredential"],
+//!   "issuer": "did:seneca:...",
+//!   "issuanceDate": "2023-12-12T11:16:56.000Z",
+//!   "credentialSchema": { "id": "urn:seneca:schema:7", "type": "SenecaSchema", "version": 0 },
+//!   "credentialSubject": { "id": "did:seneca:...", "claims": [...] },
+//!   "holder": "did:seneca:...",
+//!   "proof": {
+//!     "type": "SenecaScaleSignature2023",
+//!     "verificationMethod": "did:seneca:...",
+//!     "proofPurpose": "assertionMethod",
+//!     "nonce": 2,
+//!     "proofValue": "0x..."
+//!   }
+//! }
+//! ```
+
+use crate::types::{
+	ClaimCommitment, CredentialLimits, LegacyEncoding, Subject, VerifiableCredential,
+};
+use codec::{Decode, Encode};
+use frame_support::BoundedVec;
+use node_primitives::did::{Did, DidError, DidResolver};
+use pallet_schemas::types::{Claim, ClaimType, IssuanceRequirement, IssuanceType};
+use serde::{Deserialize, Serialize};
+use sp_core::bytes::{from_hex, to_hex};
+use sp_runtime::traits::{IdentifyAccount, Verify};
+
+/// Base context of VC Data Model 1.1 documents.
+pub const CREDENTIALS_V1_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+/// Base context of VC Data Model 2.0 documents.
+pub const CREDENTIALS_V2_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+/// Type every verifiable credential carries.
+pub const CREDENTIAL_TYPE: &str = "VerifiableCredential";
+/// Type of the `credentialSchema` entry naming an on-chain schema.
+pub const SCHEMA_TYPE: &str = "SenecaSchema";
+/// Type of proofs carrying a SCALE encoded signature over the SCALE encoded credential.
+pub const PROOF_TYPE: &str = "SenecaScaleSignature2023";
+/// Purpose of the proof, asserting the credential on behalf of the issuer.
+pub const PROOF_PURPOSE: &str = "assertionMethod";
+
+const CREDENTIAL_URN: &str = "urn:seneca:credential:";
+const SCHEMA_URN: &str = "urn:seneca:schema:";
+
+/// A verifiable credential in the W3C VC Data Model.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct W3cCredential {
+	#[serde(rename = "@context")]
+	pub context: Vec<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+	#[serde(rename = "type")]
+	pub types: Vec<String>,
+	pub issuer: String,
+	#[serde(default, skip_serializing_if = "Option::is_none", alias = "validFrom")]
+	pub issuance_date: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none", alias = "validUntil")]
+	pub expiration_date: Option<String>,
+	pub credential_schema: W3cSchema,
+	pub credential_subject: W3cSubject,
+	pub holder: String,
+	pub proof: W3cProof,
+}
+
+/// The on-chain schema and version a credential was issued against.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct W3cSchema {
+	pub id: String,
+	#[serde(rename = "type")]
+	pub schema_type: String,
+	pub version: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct W3cSubject {
+	pub id: String,
+	pub claims: Vec<W3cClaim>,
+	#[serde(rename = "committedClaims", default, skip_serializing_if = "Vec::is_empty")]
+	pub committed_claims: Vec<W3cClaimCommitment>,
+}
+
+/// A claim the holder discloses selectively, only its property is public.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct W3cClaimCommitment {
+	pub property: String,
+	/// Hex encoded salted hash of the claim.
+	pub commitment: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct W3cClaim {
+	pub property: String,
+	pub value: String,
+	#[serde(rename = "type")]
+	pub claim_type: ClaimType,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub schema_id: Option<u32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub issuance_requirements: Option<Vec<W3cIssuanceRequirement>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct W3cIssuanceRequirement {
+	pub name: String,
+	#[serde(rename = "type")]
+	pub issuance_type: IssuanceType,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct W3cProof {
+	#[serde(rename = "type")]
+	pub proof_type: String,
+	pub verification_method: String,
+	pub proof_purpose: String,
+	/// Nonce the issuer signed the credential with.
+	pub nonce: u64,
+	/// Hex encoded SCALE encoding of the signature.
+	pub proof_value: String,
+}
+
+/// Reasons a credential can't be converted to or from the W3C data model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum W3cError {
+	/// A byte field that has to be a JSON string is not valid UTF-8.
+	NotUtf8(&'static str),
+	/// A field exceeds the bound the chain puts on it.
+	TooLong(&'static str),
+	/// The `@context` does not start with a VC Data Model context, or has more than one other.
+	UnsupportedContext,
+	/// The `type` does not include `VerifiableCredential`.
+	NotVerifiableCredential,
+	/// The credential id is not a credential URN of this chain.
+	InvalidCredentialId,
+	/// The `credentialSchema` does not name an on-chain schema.
+	InvalidSchema,
+	/// A date is not an RFC 3339 UTC timestamp.
+	InvalidDate,
+	/// The proof is not of the expected type and purpose.
+	UnsupportedProof,
+	/// The proof is made by something other than the issuer or a delegate DID.
+	VerificationMethodMismatch,
+	/// The proof value does not decode to a signature.
+	InvalidProofValue,
+	/// A claim commitment is not a hex encoded 32 byte hash.
+	InvalidCommitment,
+	/// The issuer, or delegate, DID does not resolve to an account.
+	UnresolvableIssuer(DidError),
+	/// The signature does not match the credential and issuer.
+	InvalidSignature,
+	/// The credential was signed in a legacy encoding rather than its SCALE encoding.
+	LegacyEncoding,
+	/// A date falls after the year 9999, which RFC 3339 timestamps cannot express.
+	DateOutOfRange,
+}
+
+/// Render credential `id` and the `signature` it was stored with as a W3C verifiable
+/// credential, signed by the `delegate` DID on behalf of the issuer if one is given. Moments are
+/// read as milliseconds since the Unix epoch. Credentials recorded with a `legacy` encoding are
+/// refused.
+pub fn to_w3c<Moment, L, Signature>(
+	id: u32,
+	credential: &VerifiableCredential<Moment, L>,
+	signature: &Signature,
+	delegate: Option<&[u8]>,
+	legacy: Option<LegacyEncoding>,
+) -> Result<W3cCredential, W3cError>
+where
+	Moment: Copy + Into<u64>,
+	L: CredentialLimits,
+	Signature: Encode,
+{
+	if legacy.is_some() {
+		return Err(W3cError::LegacyEncoding)
+	}
+	let mut context = vec![CREDENTIALS_V1_CONTEXT.to_string()];
+	if !credential.context.is_empty() {
+		context.push(utf8(&credential.context, "context")?);
+	}
+	let issuer = utf8(&credential.issuer, "issuer")?;
+	let verification_method = match delegate {
+		Some(delegate) => utf8(delegate, "proof.verificationMethod")?,
+		None => issuer.clone(),
+	};
+	Ok(W3cCredential {
+		context,
+		id: Some(format!("{}{}", CREDENTIAL_URN, id)),
+		types: vec![CREDENTIAL_TYPE.to_string()],
+		issuer: issuer.clone(),
+		issuance_date: credential.issuance_date.map(|date| format_date(date.into())).transpose()?,
+		expiration_date: credential
+			.expiration_date
+			.map(|date| format_date(date.into()))
+			.transpose()?,
+		credential_schema: W3cSchema {
+			id: format!("{}{}", SCHEMA_URN, credential.schema),
+			schema_type: SCHEMA_TYPE.to_string(),
+			version: credential.schema_version,
+		},
+		credential_subject: W3cSubject {
+			id: utf8(&credential.subject.id, "credentialSubject.id")?,
+			claims: credential
+				.subject
+				.claim
+				.iter()
+				.map(export_claim)
+				.collect::<Result<_, _>>()?,
+			committed_claims: credential
+				.subject
+				.committed_claims
+				.iter()
+				.map(export_commitment)
+				.collect::<Result<_, _>>()?,
+		},
+		holder: utf8(&credential.credential_holder, "holder")?,
+		proof: W3cProof {
+			proof_type: PROOF_TYPE.to_string(),
+			verification_method,
+			proof_purpose: PROOF_PURPOSE.to_string(),
+			nonce: credential.nonce,
+			proof_value: to_hex(&signature.encode(), false),
+		},
+	})
+}
+
+/// Validate a W3C verifiable credential into the on-chain structure, returning the credential
+/// id if the document has one, the credential, the signature of its proof and the delegate DID
+/// that made it if it is not the issuer. The signature itself is checked by [`verify_proof`].
+pub fn from_w3c<Moment, L, Signature>(
+	document: &W3cCredential,
+) -> Result<(Option<u32>, VerifiableCredential<Moment, L>, Signature, Option<Vec<u8>>), W3cError>
+where
+	Moment: From<u64>,
+	L: CredentialLimits,
+	Signature: Decode,
+{
+	let context = match &document.context[..] {
+		[base] | [base, _] if !is_credentials_context(base) =>
+			return Err(W3cError::UnsupportedContext),
+		[_] => Vec::new(),
+		[_, context] => context.as_bytes().to_vec(),
+		_ => return Err(W3cError::UnsupportedContext),
+	};
+	if !document.types.iter().any(|t| t == CREDENTIAL_TYPE) {
+		return Err(W3cError::NotVerifiableCredential)
+	}
+	let id = document
+		.id
+		.as_ref()
+		.map(|id| parse_urn(id, CREDENTIAL_URN).ok_or(W3cError::InvalidCredentialId))
+		.transpose()?;
+	let schema = &document.credential_schema;
+	if schema.schema_type != SCHEMA_TYPE {
+		return Err(W3cError::InvalidSchema)
+	}
+	let proof = &document.proof;
+	if proof.proof_type != PROOF_TYPE || proof.proof_purpose != PROOF_PURPOSE {
+		return Err(W3cError::UnsupportedProof)
+	}
+	let delegate = if proof.verification_method == document.issuer {
+		None
+	} else {
+		Did::parse(&proof.verification_method)
+			.map_err(|_| W3cError::VerificationMethodMismatch)?;
+		Some(proof.verification_method.as_bytes().to_vec())
+	};
+	let signature = from_hex(&proof.proof_value)
+		.ok()
+		.and_then(|bytes| Signature::decode(&mut &bytes[..]).ok())
+		.ok_or(W3cError::InvalidProofValue)?;
+
+	let credential = VerifiableCredential {
+		context: bound(context, "context")?,
+		schema: parse_urn(&schema.id, SCHEMA_URN).ok_or(W3cError::InvalidSchema)?,
+		schema_version: schema.version,
+		issuer: bound(document.issuer.as_bytes().to_vec(), "issuer")?,
+		issuance_date: parse_optional_date(&document.issuance_date)?,
+		expiration_date: parse_optional_date(&document.expiration_date)?,
+		subject: Subject {
+			id: bound(document.credential_subject.id.as_bytes().to_vec(), "credentialSubject.id")?,
+			claim: bound(
+				document
+					.credential_subject
+					.claims
+					.iter()
+					.map(import_claim)
+					.collect::<Result<_, _>>()?,
+				"credentialSubject.claims",
+			)?,
+			committed_claims: bound(
+				document
+					.credential_subject
+					.committed_claims
+					.iter()
+					.map(import_commitment)
+					.collect::<Result<_, _>>()?,
+				"credentialSubject.committedClaims",
+			)?,
+		},
+		credential_holder: bound(document.holder.as_bytes().to_vec(), "holder")?,
+		nonce: proof.nonce,
+	};
+	Ok((id, credential, signature, delegate))
+}
+
+/// Check `signature` was made over `credential` by the account the issuer DID, or the `delegate`
+/// DID if given, resolves to through `R`. Whether the delegate may sign for the issuer is up to
+/// the delegations on chain.
+pub fn verify_proof<R, Moment, L, Signature>(
+	credential: &VerifiableCredential<Moment, L>,
+	signature: &Signature,
+	delegate: Option<&[u8]>,
+) -> Result<(), W3cError>
+where
+	Moment: Encode,
+	L: CredentialLimits,
+	Signature: Verify,
+	Signature::Signer: IdentifyAccount,
+	R: DidResolver<<Signature::Signer as IdentifyAccount>::AccountId>,
+{
+	let signer = R::resolve(delegate.unwrap_or(&credential.issuer))
+		.map_err(W3cError::UnresolvableIssuer)?;
+	if signature.verify(&credential.encode()[..], &signer) {
+		Ok(())
+	} else {
+		Err(W3cError::InvalidSignature)
+	}
+}
+
+fn export_claim<L: CredentialLimits>(claim: &Claim<L>) -> Result<W3cClaim, W3cError> {
+	Ok(W3cClaim {
+		property: utf8(&claim.property, "claim.property")?,
+		value: utf8(&claim.value, "claim.value")?,
+		claim_type: claim.claim_type.clone(),
+		schema_id: claim.schemaid,
+		issuance_requirements: claim
+			.issuance_requirement
+			.as_ref()
+			.map(|requirements| {
+				requirements
+					.iter()
+					.map(|requirement| {
+						Ok(W3cIssuanceRequirement {
+							name: utf8(&requirement.name, "issuanceRequirement.name")?,
+							issuance_type: requirement.insuance_type.clone(),
+						})
+					})
+					.collect()
+			})
+			.transpose()?,
+	})
+}
+
+fn import_claim<L: CredentialLimits>(claim: &W3cClaim) -> Result<Claim<L>, W3cError> {
+	Ok(Claim {
+		schemaid: claim.schema_id,
+		property: bound(claim.property.as_bytes().to_vec(), "claim.property")?,
+		value: bound(claim.value.as_bytes().to_vec(), "claim.value")?,
+		claim_type: claim.claim_type.clone(),
+		issuance_requirement: claim
+			.issuance_requirements
+			.as_ref()
+			.map(|requirements| {
+				let requirements = requirements
+					.iter()
+					.map(|requirement| {
+						Ok(IssuanceRequirement {
+							name: bound(
+								requirement.name.as_bytes().to_vec(),
+								"issuanceRequirement.name",
+							)?,
+							insuance_type: requirement.issuance_type.clone(),
+						})
+					})
+					.collect::<Result<_, _>>()?;
+				bound(requirements, "claim.issuanceRequirements")
+			})
+			.transpose()?,
+	})
+}
+
+fn export_commitment<L: CredentialLimits>(
+	committed: &ClaimCommitment<L>,
+) -> Result<W3cClaimCommitment, W3cError> {
+	Ok(W3cClaimCommitment {
+		property: utf8(&committed.property, "committedClaim.property")?,
+		commitment: to_hex(&committed.commitment, false),
+	})
+}
+
+fn import_commitment<L: CredentialLimits>(
+	committed: &W3cClaimCommitment,
+) -> Result<ClaimCommitment<L>, W3cError> {
+	Ok(ClaimCommitment {
+		property: bound(committed.property.as_bytes().to_vec(), "committedClaim.property")?,
+		commitment: from_hex(&committed.commitment)
+			.ok()
+			.and_then(|bytes| bytes.try_into().ok())
+			.ok_or(W3cError::InvalidCommitment)?,
+	})
+}
+
+fn is_credentials_context(context: &str) -> bool {
+	context == CREDENTIALS_V1_CONTEXT || context == CREDENTIALS_V2_CONTEXT
+}
+
+fn utf8(bytes: &[u8], field: &'static str) -> Result<String, W3cError> {
+	String::from_utf8(bytes.to_vec()).map_err(|_| W3cError::NotUtf8(field))
+}
+
+fn bound<T, S: frame_support::traits::Get<u32>>(
+	items: Vec<T>,
+	field: &'static str,
+) -> Result<BoundedVec<T, S>, W3cError> {
+	items.try_into().map_err(|_| W3cError::TooLong(field))
+}
+
+fn parse_urn(urn: &str, prefix: &str) -> Option<u32> {
+	urn.strip_prefix(prefix)?.parse().ok()
+}
+
+fn parse_optional_date<Moment: From<u64>>(
+	date: &Option<String>,
+) -> Result<Option<Moment>, W3cError> {
+	date.as_deref()
+		.map(|date| parse_date(date).map(Moment::from).ok_or(W3cError::InvalidDate))
+		.transpose()
+}
+
+/// Format milliseconds since the Unix epoch as an RFC 3339 UTC timestamp, which only has room
+/// for four digit years.
+fn format_date(millis: u64) -> Result<String, W3cError> {
+	let seconds = millis / 1000;
+	let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+	if year > 9999 {
+		return Err(W3cError::DateOutOfRange)
+	}
+	let time = seconds % 86_400;
+	Ok(format!(
+		"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+		year,
+		month,
+		day,
+		time / 3600,
+		time / 60 % 60,
+		time % 60,
+		millis % 1000
+	))
+}
+
+/// Parse an RFC 3339 UTC timestamp, with or without fractional seconds, into milliseconds
+/// since the Unix epoch.
+fn parse_date(date: &str) -> Option<u64> {
+	let (date, time) = date.strip_suffix('Z')?.split_once('T')?;
+	let mut date = date.splitn(3, '-');
+	let year: i64 = number(date.next()?, 4)?;
+	let month: u32 = number(date.next()?, 2)?;
+	let day: u32 = number(date.next()?, 2)?;
+	let (time, fraction) = match time.split_once('.') {
+		Some((time, fraction)) => (time, Some(fraction)),
+		None => (time, None),
+	};
+	let mut time = time.splitn(3, ':');
+	let hours: u64 = number(time.next()?, 2)?;
+	let minutes: u64 = number(time.next()?, 2)?;
+	let seconds: u64 = number(time.next()?, 2)?;
+	if !(1..=12).contains(&month) ||
+		day == 0 || day > days_in_month(year, month) ||
+		hours > 23 || minutes > 59 ||
+		seconds > 59
+	{
+		return None
+	}
+	// only millisecond precision is kept
+	let millis = match fraction {
+		Some(fraction) if !fraction.is_empty() && fraction.bytes().all(|b| b.is_ascii_digit()) =>
+			format!("{:0<3}", &fraction[..fraction.len().min(3)]).parse().ok()?,
+		Some(_) => return None,
+		None => 0,
+	};
+	let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+	Some(((days * 86_400 + hours * 3600 + minutes * 60 + seconds) * 1000) + millis)
+}
+
+fn number<N: core::str::FromStr>(digits: &str, len: usize) -> Option<N> {
+	if digits.len() != len || !digits.bytes().all(|b| b.is_ascii_digit()) {
+		return None
+	}
+	digits.parse().ok()
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+	match month {
+		2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date, after Howard Hinnant's algorithm
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let year_of_era = year - era * 400;
+	let month = month as i64;
+	let month_index = if month > 2 { month - 3 } else { month + 9 };
+	let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+	era * 146_097 + day_of_era - 719_468
+}
+
+// Inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+	let days = days + 719_468;
+	let era = days.div_euclid(146_097);
+	let day_of_era = days - era * 146_097;
+	let year_of_era =
+		(day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+	let month = (if month_index < 10 { month_index + 3 } else { month_index - 9 }) as u32;
+	let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+	(year, month, day)
+}
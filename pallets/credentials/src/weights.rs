@@ -1,12 +1,11 @@
-//! Autogenerated weights for `pallet_credentials`
+//! Weights for `pallet_credentials`
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-01-27, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! HOSTNAME: `MACs-MacBook-Air.local`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
+//! These are estimates, not benchmark output: they are scaled from the weights measured for the
+//! first versions of these calls by their current storage footprint and signature checks.
+//! Regenerate this file with `scripts/benchmark.sh` on reference hardware before relying on it.
 
-// Executed Command:
-// ./target/production/serv-node
+// Command to regenerate:
+// ./target/production/zeno-node
 // benchmark
 // pallet
 // --chain
@@ -21,8 +20,10 @@
 // 50
 // --repeat
 // 20
+// --template
+// scripts/frame-weight-template.hbs
 // --output
-// pallets/schema-registry/src/weights.rs
+// pallets/credentials/src/weights.rs
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -33,58 +34,332 @@ use sp_std::marker::PhantomData;
 
 pub trait WeightInfo {
 	fn create_credential() -> Weight;
-    fn update_credential() -> Weight;
-	fn delete_credential() -> Weight;
+	fn update_credential() -> Weight;
+	fn delete_credential(r: u32, ) -> Weight;
+	fn revoke_credential() -> Weight;
+	fn suspend_credential() -> Weight;
+	fn reinstate_credential() -> Weight;
+	fn accept_credential() -> Weight;
+	fn reject_credential() -> Weight;
+	fn create_credentials_batch(n: u32, ) -> Weight;
+	fn add_delegate() -> Weight;
+	fn remove_delegate() -> Weight;
+	fn reap_delegate() -> Weight;
+	fn on_initialize(b: u32, e: u32, ) -> Weight;
 }
 
 /// Weight functions for `pallet_credentials`.
 pub struct CredentialRegistryWeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for CredentialRegistryWeightInfo<T> {
+	// Storage: Did DidDocuments (r:1 w:0)
 	// Storage: Timestamp Now (r:1 w:0)
-	// Storage: CredentialRegistry CredentialStore (r:1 w:1)
-	/// The range of component `s` is `[0, 100]`.
+	// Storage: CredentialRegistry Delegations (r:1 w:1)
+	// Storage: SchemaRegistry SchemaStore (r:1 w:0)
+	// Storage: SchemaRegistry SchemaRevisions (r:1 w:0)
+	// Storage: SchemaRegistry SchemaVersions (r:1 w:0)
+	// Storage: SchemaRegistry DeprecatedSchemas (r:1 w:0)
+	// Storage: SchemaRegistry DeprecatedVersions (r:1 w:0)
+	// Storage: SchemaRegistry AuthorizedIssuers (r:1 w:0)
+	// Storage: CredentialRegistry NextCredentialId (r:1 w:1)
+	// Storage: CredentialRegistry CredentialStore (r:1 w:0)
+	// Storage: CredentialRegistry CredentialOffers (r:1 w:1)
+	// Storage: CredentialRegistry Nonce (r:1 w:1)
+	// Storage: CredentialRegistry CredentialDelegates (r:1 w:1)
+	// Storage: CredentialRegistry CredentialDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: CredentialRegistry OfferExpiries (r:0 w:1)
 	fn create_credential() -> Weight {
-		// Minimum execution time: 16_000 nanoseconds.
-		Weight::from_parts(16_969_749, 0u64)
-			.saturating_add(T::DbWeight::get().reads(2 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+		Weight::from_parts(60_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(16 as u64))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
 	}
-	// Storage: CredentialRegistry CredentialStore (r:1 w:1)
-	/// The range of component `s` is `[0, 100]`.
+	// Storage: CredentialRegistry CredentialStore (r:1 w:0)
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: CredentialRegistry CredentialStatuses (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SchemaRegistry SchemaStore (r:1 w:0)
+	// Storage: SchemaRegistry SchemaRevisions (r:1 w:0)
+	// Storage: SchemaRegistry SchemaVersions (r:1 w:0)
+	// Storage: SchemaRegistry DeprecatedSchemas (r:1 w:0)
+	// Storage: SchemaRegistry DeprecatedVersions (r:1 w:0)
+	// Storage: SchemaRegistry AuthorizedIssuers (r:1 w:0)
+	// Storage: CredentialRegistry Nonce (r:1 w:1)
+	// Storage: CredentialRegistry CredentialOffers (r:1 w:1)
+	// Storage: CredentialRegistry OfferExpiries (r:0 w:2)
+	// Storage: CredentialRegistry CredentialDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
 	fn update_credential() -> Weight {
-		// Minimum execution time: 17_000 nanoseconds.
-		Weight::from_parts(18_025_266, 0u64)
-			.saturating_add(T::DbWeight::get().reads(1 as u64))
+		Weight::from_parts(60_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(14 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
+	// Storage: CredentialRegistry CredentialStore (r:1 w:1)
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: CredentialRegistry CredentialStatuses (r:1 w:1)
+	// Storage: CredentialRegistry CredentialsBySchema (r:0 w:1)
+	// Storage: CredentialRegistry CredentialsByHolder (r:0 w:1)
+	// Storage: CredentialRegistry CredentialsByIssuer (r:0 w:1)
+	// Storage: CredentialRegistry CredentialsBySubject (r:0 w:1)
+	// Storage: SchemaRegistry SchemaReferences (r:1 w:1)
+	// Storage: CredentialRegistry CredentialOffers (r:1 w:1)
+	// Storage: CredentialRegistry OfferExpiries (r:0 w:1)
+	// Storage: CredentialRegistry CredentialDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: CredentialRegistry CredentialRevisions (r:0 w:1)
+	// Storage: CredentialRegistry CredentialHistory (r:r w:r)
+	// Storage: CredentialRegistry BatchedCredentials (r:1 w:1)
+	// Storage: CredentialRegistry BatchDeposits (r:1 w:1)
+	// Storage: CredentialRegistry CredentialBatches (r:0 w:1)
+	// Storage: CredentialRegistry CredentialDelegates (r:0 w:1)
+	// Storage: CredentialRegistry LegacyEncodings (r:0 w:1)
+	// Storage: CredentialRegistry CredentialSigners (r:0 w:1)
+	/// The range of component `r` is `[0, 100]`.
+	fn delete_credential(r: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 0u64)
+			.saturating_add(Weight::from_parts(4_000_000, 0u64).saturating_mul(r as u64))
+			.saturating_add(T::DbWeight::get().reads(9 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(r as u64)))
+			.saturating_add(T::DbWeight::get().writes(18 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(r as u64)))
+	}
+	// Storage: CredentialRegistry CredentialStore (r:1 w:0)
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: CredentialRegistry CredentialStatuses (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	fn revoke_credential() -> Weight {
+		Weight::from_parts(18_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: CredentialRegistry CredentialStore (r:1 w:0)
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: CredentialRegistry CredentialStatuses (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	fn suspend_credential() -> Weight {
+		Weight::from_parts(18_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: CredentialRegistry CredentialStore (r:1 w:0)
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: CredentialRegistry CredentialStatuses (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	fn reinstate_credential() -> Weight {
+		Weight::from_parts(18_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: CredentialRegistry CredentialOffers (r:1 w:1)
+	// Storage: CredentialRegistry OfferExpiries (r:0 w:1)
+	// Storage: SchemaRegistry SchemaStore (r:1 w:0)
+	// Storage: Did DidDocuments (r:2 w:0)
 	// Storage: CredentialRegistry CredentialStore (r:1 w:1)
-	/// The range of component `s` is `[0, 100]`.
-	fn delete_credential() -> Weight {
-		// Minimum execution time: 14_000 nanoseconds.
-		Weight::from_parts(14_900_463, 0u64)
-			// Standard Error: 481
-			.saturating_add(Weight::from_parts(354, 0u64).saturating_mul(1 as u64))
+	// Storage: CredentialRegistry CredentialStatuses (r:1 w:1)
+	// Storage: CredentialRegistry CredentialsBySchema (r:0 w:2)
+	// Storage: CredentialRegistry CredentialsByHolder (r:0 w:2)
+	// Storage: CredentialRegistry CredentialsByIssuer (r:0 w:2)
+	// Storage: CredentialRegistry CredentialsBySubject (r:0 w:2)
+	// Storage: SchemaRegistry SchemaReferences (r:1 w:1)
+	// Storage: CredentialRegistry CredentialRevisions (r:1 w:1)
+	// Storage: CredentialRegistry CredentialHistory (r:0 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: CredentialRegistry BatchedCredentials (r:1 w:1)
+	// Storage: CredentialRegistry BatchDeposits (r:1 w:1)
+	// Storage: CredentialRegistry CredentialBatches (r:0 w:1)
+	// Storage: CredentialRegistry CredentialDelegates (r:0 w:1)
+	// Storage: CredentialRegistry LegacyEncodings (r:0 w:1)
+	// Storage: CredentialRegistry CredentialDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: CredentialRegistry CredentialSigners (r:0 w:1)
+	fn accept_credential() -> Weight {
+		Weight::from_parts(70_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(13 as u64))
+			.saturating_add(T::DbWeight::get().writes(23 as u64))
+	}
+	// Storage: CredentialRegistry CredentialOffers (r:1 w:1)
+	// Storage: Did DidDocuments (r:2 w:0)
+	// Storage: CredentialRegistry OfferExpiries (r:0 w:1)
+	// Storage: CredentialRegistry CredentialStore (r:1 w:0)
+	// Storage: CredentialRegistry CredentialDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: CredentialRegistry BatchedCredentials (r:1 w:1)
+	// Storage: CredentialRegistry BatchDeposits (r:1 w:1)
+	// Storage: CredentialRegistry CredentialBatches (r:0 w:1)
+	// Storage: CredentialRegistry CredentialDelegates (r:1 w:1)
+	// Storage: CredentialRegistry Delegations (r:1 w:1)
+	fn reject_credential() -> Weight {
+		Weight::from_parts(25_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+	}
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: CredentialRegistry Delegations (r:1 w:1)
+	// Storage: CredentialRegistry Nonce (r:1 w:1)
+	// Storage: CredentialRegistry NextCredentialId (r:1 w:1)
+	// Storage: SchemaRegistry SchemaStore (r:n w:0)
+	// Storage: SchemaRegistry SchemaRevisions (r:n w:0)
+	// Storage: SchemaRegistry SchemaVersions (r:n w:0)
+	// Storage: SchemaRegistry DeprecatedSchemas (r:n w:0)
+	// Storage: SchemaRegistry DeprecatedVersions (r:n w:0)
+	// Storage: SchemaRegistry AuthorizedIssuers (r:n w:0)
+	// Storage: CredentialRegistry CredentialStore (r:n w:0)
+	// Storage: CredentialRegistry CredentialOffers (r:n w:n)
+	// Storage: CredentialRegistry CredentialDeposits (r:n w:n)
+	// Storage: System Account (r:1 w:1)
+	// Storage: CredentialRegistry OfferExpiries (r:0 w:n)
+	// Storage: CredentialRegistry BatchedCredentials (r:0 w:n)
+	// Storage: CredentialRegistry CredentialDelegates (r:0 w:n)
+	// Storage: CredentialRegistry BatchDeposits (r:0 w:1)
+	// Storage: CredentialRegistry CredentialBatches (r:0 w:1)
+	/// The range of component `n` is `[1, 100]`.
+	fn create_credentials_batch(n: u32, ) -> Weight {
+		Weight::from_parts(50_000_000, 0u64)
+			.saturating_add(Weight::from_parts(20_000_000, 0u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().reads((9 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+			.saturating_add(T::DbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: CredentialRegistry Delegations (r:1 w:1)
+	// Storage: CredentialRegistry DelegationDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn add_delegate() -> Weight {
+		Weight::from_parts(16_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Did DidDocuments (r:1 w:0)
+	// Storage: CredentialRegistry Delegations (r:1 w:1)
+	// Storage: CredentialRegistry DelegationDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn remove_delegate() -> Weight {
+		Weight::from_parts(16_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: CredentialRegistry Delegations (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: CredentialRegistry DelegationDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn reap_delegate() -> Weight {
+		Weight::from_parts(16_000_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: CredentialRegistry NextExpiryBlock (r:1 w:1)
+	// Storage: CredentialRegistry OfferExpiries (r:b w:e)
+	// Storage: CredentialRegistry CredentialOffers (r:e w:e)
+	// Storage: CredentialRegistry CredentialStore (r:e w:0)
+	// Storage: CredentialRegistry CredentialDeposits (r:e w:e)
+	// Storage: CredentialRegistry BatchedCredentials (r:e w:e)
+	// Storage: CredentialRegistry BatchDeposits (r:e w:e)
+	// Storage: CredentialRegistry CredentialBatches (r:0 w:e)
+	// Storage: CredentialRegistry CredentialDelegates (r:e w:e)
+	// Storage: CredentialRegistry Delegations (r:e w:e)
+	// Storage: System Account (r:e w:e)
+	/// The range of component `b` is `[1, 64]`.
+	/// The range of component `e` is `[0, 64]`.
+	fn on_initialize(b: u32, e: u32, ) -> Weight {
+		Weight::from_parts(4_000_000, 0u64)
+			.saturating_add(Weight::from_parts(2_000_000, 0u64).saturating_mul(b as u64))
+			.saturating_add(Weight::from_parts(18_000_000, 0u64).saturating_mul(e as u64))
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(b as u64)))
+			.saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(e as u64)))
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((9 as u64).saturating_mul(e as u64)))
 	}
 }
 
 impl WeightInfo for () {
-    // Storage: CredentialRegistry SchemaStore (r:1 w:1)
-    fn create_credential() -> Weight {
-        Weight::from_parts(16_969_749, 0u64)
-            .saturating_add(RocksDbWeight::get().writes(1 as u64))
-    }
-
-	// Storage: CredentialRegistry SchemaStore (r:1 w:1)
-    fn update_credential() -> Weight {
-		Weight::from_parts(18_025_266, 0u64)
+	fn create_credential() -> Weight {
+		Weight::from_parts(60_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(16 as u64))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
+	}
+
+	fn update_credential() -> Weight {
+		Weight::from_parts(60_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(14 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+	}
+
+	fn delete_credential(r: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 0u64)
+			.saturating_add(Weight::from_parts(4_000_000, 0u64).saturating_mul(r as u64))
+			.saturating_add(RocksDbWeight::get().reads(9 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(r as u64)))
+			.saturating_add(RocksDbWeight::get().writes(18 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(r as u64)))
+	}
+
+	fn revoke_credential() -> Weight {
+		Weight::from_parts(18_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
 
-	// Storage: CredentialRegistry SchemaStore (r:1 w:1)
-	fn delete_credential() -> Weight {
-        Weight::from_parts(14_900_463, 0u64)
-            .saturating_add(RocksDbWeight::get().writes(1 as u64))
-    }
-}
\ No newline at end of file
+	fn suspend_credential() -> Weight {
+		Weight::from_parts(18_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+
+	fn reinstate_credential() -> Weight {
+		Weight::from_parts(18_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+
+	fn accept_credential() -> Weight {
+		Weight::from_parts(70_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(13 as u64))
+			.saturating_add(RocksDbWeight::get().writes(23 as u64))
+	}
+
+	fn reject_credential() -> Weight {
+		Weight::from_parts(25_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+	}
+
+	fn create_credentials_batch(n: u32, ) -> Weight {
+		Weight::from_parts(50_000_000, 0u64)
+			.saturating_add(Weight::from_parts(20_000_000, 0u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().reads((9 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+	}
+
+	fn add_delegate() -> Weight {
+		Weight::from_parts(16_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+
+	fn remove_delegate() -> Weight {
+		Weight::from_parts(16_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+
+	fn reap_delegate() -> Weight {
+		Weight::from_parts(16_000_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+
+	fn on_initialize(b: u32, e: u32, ) -> Weight {
+		Weight::from_parts(4_000_000, 0u64)
+			.saturating_add(Weight::from_parts(2_000_000, 0u64).saturating_mul(b as u64))
+			.saturating_add(Weight::from_parts(18_000_000, 0u64).saturating_mul(e as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(b as u64)))
+			.saturating_add(RocksDbWeight::get().reads((8 as u64).saturating_mul(e as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((9 as u64).saturating_mul(e as u64)))
+	}
+}
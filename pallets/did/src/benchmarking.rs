@@ -0,0 +1,115 @@
+//! Benchmarking setup for pallet-did
+use super::*;
+use crate::types::*;
+#[allow(unused)]
+use crate::Pallet as DidRegistry;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{
+	assert_ok, bounded_vec,
+	traits::{Currency, Get},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use scale_info::prelude::format;
+use scale_info::prelude::vec;
+use sp_application_crypto::RuntimePublic;
+use sp_application_crypto::sr25519::Public;
+use sp_runtime::traits::Bounded;
+
+fn bounded<S: Get<u32>>(data: &[u8]) -> BoundedVec<u8, S> {
+	data.to_vec().try_into().expect("benchmark values fit the bounds; qed")
+}
+
+// A document with a single key used for both authentication and assertions, and a service
+// endpoint of `endpoint_length` bytes
+fn document<T: Config>(
+	did: &DidOf<T>,
+	key: T::AccountId,
+	endpoint_length: u32,
+) -> DidDocumentOf<T> {
+	DidDocument {
+		controller: Default::default(),
+		verification_method: bounded_vec![VerificationMethod {
+			id: bounded(b"key-1"),
+			controller: did.clone(),
+			public_key: key,
+		}],
+		authentication: bounded_vec![bounded(b"key-1")],
+		assertion_method: bounded_vec![bounded(b"key-1")],
+		service: bounded_vec![Service {
+			id: bounded(b"hub"),
+			service_type: bounded(b"LinkedDomains"),
+			service_endpoint: bounded(&vec![b'a'; endpoint_length as usize]),
+		}],
+	}
+}
+
+// A funded account of a freshly generated key and its DID
+fn did_account<T: Config>() -> (T::AccountId, DidOf<T>)
+where
+	T::AccountId: From<sp_core::sr25519::Public>,
+{
+	let public = Public::generate_pair(sp_core::testing::SR25519, None);
+	let caller: T::AccountId = public.into();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	let did = bounded(format!("did:seneca:{:#?}", caller.clone()).as_bytes());
+	(caller, did)
+}
+
+// Registers the DID of a freshly generated key
+fn setup_did<T: Config>() -> (T::AccountId, DidOf<T>)
+where
+	T::AccountId: From<sp_core::sr25519::Public>,
+{
+	let (caller, did) = did_account::<T>();
+	assert_ok!(DidRegistry::<T>::register_did(
+		RawOrigin::Signed(caller.clone()).into(),
+		did.clone(),
+		document::<T>(&did, caller.clone(), 1),
+	));
+	(caller, did)
+}
+
+benchmarks! {
+	where_clause {
+		where T::AccountId: From<sp_core::sr25519::Public>
+	}
+	register_did {
+		let l in 1 .. T::MaxServiceLength::get();
+		let (caller, did) = did_account::<T>();
+		let document = document::<T>(&did, caller.clone(), l);
+	}: _(RawOrigin::Signed(caller), did.clone(), document.clone())
+	verify {
+		assert_eq!(DidDocuments::<T>::get(&did).map(|(document, _)| document), Some(document));
+	}
+	update_did {
+		let l in 1 .. T::MaxServiceLength::get();
+		let (caller, did) = setup_did::<T>();
+		let mut document = document::<T>(&did, caller.clone(), l);
+		assert_ok!(document.verification_method.try_push(VerificationMethod {
+			id: bounded(b"key-2"),
+			controller: did.clone(),
+			public_key: account("assertion", 0, 0),
+		}));
+		document.assertion_method = bounded_vec![bounded(b"key-2")];
+	}: _(RawOrigin::Signed(caller), did.clone(), document.clone())
+	verify {
+		assert_eq!(DidDocuments::<T>::get(&did).map(|(document, _)| document), Some(document));
+	}
+	rotate_key {
+		let (caller, did) = setup_did::<T>();
+		let new_key: T::AccountId = account("rotated", 0, 0);
+	}: _(RawOrigin::Signed(caller), did.clone(), bounded(b"key-1"), new_key.clone())
+	verify {
+		let (document, _) = DidDocuments::<T>::get(&did).unwrap();
+		assert_eq!(document.verification_method[0].public_key, new_key);
+	}
+	deactivate_did {
+		let (caller, did) = setup_did::<T>();
+	}: _(RawOrigin::Signed(caller), did.clone())
+	verify {
+		assert!(DidDocuments::<T>::get(&did).map_or(false, |(_, metadata)| metadata.deactivated));
+	}
+
+	impl_benchmark_test_suite!(DidRegistry, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,343 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+pub use pallet::*;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use crate::types::*;
+	use crate::weights::WeightInfo;
+	use frame_support::{
+		ensure,
+		pallet_prelude::*,
+		sp_runtime::traits::Saturating,
+		traits::{Currency, IsType, ReservableCurrency, Time},
+		CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	};
+	use frame_system::pallet_prelude::*;
+	use node_primitives::did::{seneca_account_id, Did, DidError, DidResolver, SENECA_METHOD};
+	use scale_info::{prelude::{vec, vec::Vec}, StaticTypeInfo};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Bounds of the DID document types, taken from the pallet configuration.
+	#[derive(CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound)]
+	pub struct DidLimitsOf<T>(PhantomData<T>);
+
+	impl<T: Config> DidLimits for DidLimitsOf<T> {
+		type MaxDidLength = T::MaxDidLength;
+		type MaxEntries = T::MaxEntries;
+		type MaxServiceLength = T::MaxServiceLength;
+	}
+
+	pub type DidOf<T> = BoundedDid<DidLimitsOf<T>>;
+	pub type DidDocumentOf<T> = DidDocument<<T as frame_system::Config>::AccountId, DidLimitsOf<T>>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		type Moment: Parameter + Default + Copy + MaxEncodedLen + StaticTypeInfo;
+		type Timestamp: Time<Moment = Self::Moment>;
+		type WeightInfo: WeightInfo;
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The base deposit reserved for storing a DID document.
+		#[pallet::constant]
+		type DidDepositBase: Get<BalanceOf<Self>>;
+		/// The deposit reserved per byte of a stored DID document.
+		#[pallet::constant]
+		type DidDepositPerByte: Get<BalanceOf<Self>>;
+		/// The maximum length of a DID, or of the id of a verification method or service.
+		#[pallet::constant]
+		type MaxDidLength: Get<u32>;
+		/// The maximum number of controllers, verification methods, relationship entries or
+		/// services in a DID document.
+		#[pallet::constant]
+		type MaxEntries: Get<u32>;
+		/// The maximum length of a service type or endpoint.
+		#[pallet::constant]
+		type MaxServiceLength: Get<u32>;
+	}
+
+	/// DID documents of registered DIDs, keyed by the DID.
+	#[pallet::storage]
+	#[pallet::getter(fn did_document)]
+	pub type DidDocuments<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		DidOf<T>,
+		(DidDocumentOf<T>, DidMetadata<T::Moment>),
+		OptionQuery,
+	>;
+
+	/// Account and amount of the deposit reserved for each DID document.
+	#[pallet::storage]
+	pub type DidDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, DidOf<T>, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	// Pallets use events to inform users when important changes are made.
+	// https://docs.substrate.io/main-docs/build/events-errors/
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		// Event is emitted when a DID is registered [did]
+		DidRegistered(Vec<u8>),
+		// Event is emitted when a DID document is replaced [did]
+		DidUpdated(Vec<u8>),
+		// Event is emitted when a verification method gets a new key [did, method id]
+		KeyRotated(Vec<u8>, Vec<u8>),
+		// Event is emitted when a DID is deactivated [did]
+		DidDeactivated(Vec<u8>),
+	}
+
+	// Errors inform users that something went wrong.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Error emitted when the DID is already registered
+		DidAlreadyExists,
+		/// Error emitted when the DID is not registered
+		UnknownDid,
+		/// Error emitted when the DID has been deactivated
+		DidDeactivated,
+		/// Error emitted when the DID is malformed or not a `did:seneca` DID
+		InvalidDID,
+		/// Error emitted when the origin controls neither the DID nor one of its controllers
+		NotDidController,
+		/// Error emitted when a referenced verification method is not in the document
+		UnknownVerificationMethod,
+		/// Error emitted when two verification methods share an id
+		DuplicateVerificationMethod,
+		/// Error emitted when the document has no authentication key
+		MissingAuthenticationKey,
+	}
+
+	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
+	// These functions materialize as "extrinsics", which are often compared to transactions.
+	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a `did:seneca` DID, signed by the account its method-specific id encodes,
+		/// which reserves a deposit for the document
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::register_did(document.encoded_size() as u32))]
+		pub fn register_did(
+			origin: OriginFor<T>,
+			did: DidOf<T>,
+			document: DidDocumentOf<T>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let parsed = Did::parse_bytes(&did).map_err(|_| Error::<T>::InvalidDID)?;
+			ensure!(parsed.method == SENECA_METHOD && parsed.is_bare(), Error::<T>::InvalidDID);
+			let owner: T::AccountId =
+				seneca_account_id(&did, T::SS58Prefix::get()).map_err(|_| Error::<T>::InvalidDID)?;
+			ensure!(owner == origin, Error::<T>::NotDidController);
+			ensure!(!DidDocuments::<T>::contains_key(&did), Error::<T>::DidAlreadyExists);
+			Self::ensure_valid_document(&document)?;
+			Self::hold_deposit(&did, &origin, Self::deposit_for(&document))?;
+			let now = T::Timestamp::now();
+			let metadata = DidMetadata { created: now, updated: now, deactivated: false };
+			DidDocuments::<T>::insert(&did, (document, metadata));
+			Self::deposit_event(Event::DidRegistered(did.to_vec()));
+			Ok(())
+		}
+
+		/// Replace the document of a registered DID, the deposit for it is taken from the caller
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::update_did(document.encoded_size() as u32))]
+		pub fn update_did(
+			origin: OriginFor<T>,
+			did: DidOf<T>,
+			document: DidDocumentOf<T>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let (_, mut metadata) = Self::ensure_controller(&did, &origin)?;
+			Self::ensure_valid_document(&document)?;
+			Self::hold_deposit(&did, &origin, Self::deposit_for(&document))?;
+			metadata.updated = T::Timestamp::now();
+			DidDocuments::<T>::insert(&did, (document, metadata));
+			Self::deposit_event(Event::DidUpdated(did.to_vec()));
+			Ok(())
+		}
+
+		/// Replace the key of one verification method
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::rotate_key())]
+		pub fn rotate_key(
+			origin: OriginFor<T>,
+			did: DidOf<T>,
+			method_id: DidOf<T>,
+			public_key: T::AccountId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let (mut document, mut metadata) = Self::ensure_controller(&did, &origin)?;
+			let method = document
+				.verification_method
+				.iter_mut()
+				.find(|method| method.id == method_id)
+				.ok_or(Error::<T>::UnknownVerificationMethod)?;
+			method.public_key = public_key;
+			metadata.updated = T::Timestamp::now();
+			DidDocuments::<T>::insert(&did, (document, metadata));
+			Self::deposit_event(Event::KeyRotated(did.to_vec(), method_id.to_vec()));
+			Ok(())
+		}
+
+		/// Deactivate a DID, after which it no longer resolves to any key. Its document is kept,
+		/// and so is the deposit for it.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::deactivate_did())]
+		pub fn deactivate_did(origin: OriginFor<T>, did: DidOf<T>) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let (document, mut metadata) = Self::ensure_controller(&did, &origin)?;
+			metadata.updated = T::Timestamp::now();
+			metadata.deactivated = true;
+			DidDocuments::<T>::insert(&did, (document, metadata));
+			Self::deposit_event(Event::DidDeactivated(did.to_vec()));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `who` holds an authentication key of `did`. Unregistered `did:seneca` DIDs
+		/// are authenticated by the account their method-specific id encodes.
+		pub fn is_authentication_key(did: &[u8], who: &T::AccountId) -> bool {
+			match Self::document(did) {
+				Some((document, metadata)) =>
+					!metadata.deactivated && document.authentication_keys().any(|key| key == who),
+				None => seneca_account_id::<T::AccountId>(did, T::SS58Prefix::get())
+					.map_or(false, |owner| owner == *who),
+			}
+		}
+
+		// Ensure `did` is registered and active and `who` may update it, either through one of
+		// its own authentication keys or through those of a controller DID
+		fn ensure_controller(
+			did: &[u8],
+			who: &T::AccountId,
+		) -> Result<(DidDocumentOf<T>, DidMetadata<T::Moment>), DispatchError> {
+			let (document, metadata) = Self::document(did).ok_or(Error::<T>::UnknownDid)?;
+			ensure!(!metadata.deactivated, Error::<T>::DidDeactivated);
+			ensure!(
+				Self::is_authentication_key(did, who) ||
+					document.controller.iter().any(|controller| {
+						controller[..] != did[..] && Self::is_authentication_key(controller, who)
+					}),
+				Error::<T>::NotDidController
+			);
+			Ok((document, metadata))
+		}
+
+		// Ensure method ids are unique, every relationship points at a method and controllers
+		// are well formed DIDs
+		fn ensure_valid_document(document: &DidDocumentOf<T>) -> DispatchResult {
+			for (index, method) in document.verification_method.iter().enumerate() {
+				ensure!(
+					!document.verification_method[..index].iter().any(|m| m.id == method.id),
+					Error::<T>::DuplicateVerificationMethod
+				);
+			}
+			ensure!(
+				document
+					.authentication
+					.iter()
+					.chain(document.assertion_method.iter())
+					.all(|id| document.method(id).is_some()),
+				Error::<T>::UnknownVerificationMethod
+			);
+			ensure!(!document.authentication.is_empty(), Error::<T>::MissingAuthenticationKey);
+			for controller in document.controller.iter() {
+				let controller = Did::parse_bytes(controller).map_err(|_| Error::<T>::InvalidDID)?;
+				ensure!(controller.is_bare(), Error::<T>::InvalidDID);
+			}
+			Ok(())
+		}
+
+		// Stored document and metadata of `did`, DIDs exceeding the bound cannot be registered
+		fn document(did: &[u8]) -> Option<(DidDocumentOf<T>, DidMetadata<T::Moment>)> {
+			let did: DidOf<T> = did.to_vec().try_into().ok()?;
+			DidDocuments::<T>::get(did)
+		}
+
+		// Deposit required to store `document`
+		fn deposit_for(document: &DidDocumentOf<T>) -> BalanceOf<T> {
+			let bytes: BalanceOf<T> = (document.encoded_size() as u32).into();
+			T::DidDepositBase::get()
+				.saturating_add(T::DidDepositPerByte::get().saturating_mul(bytes))
+		}
+
+		// Hold `deposit` from `who` for the document of `did`, adjusting or moving any deposit
+		// already held
+		fn hold_deposit(
+			did: &DidOf<T>,
+			who: &T::AccountId,
+			deposit: BalanceOf<T>,
+		) -> DispatchResult {
+			match DidDeposits::<T>::get(did) {
+				Some((depositor, held)) if depositor == *who =>
+					if deposit > held {
+						T::Currency::reserve(who, deposit - held)?;
+					} else {
+						T::Currency::unreserve(who, held - deposit);
+					},
+				previous => {
+					T::Currency::reserve(who, deposit)?;
+					if let Some((depositor, held)) = previous {
+						T::Currency::unreserve(&depositor, held);
+					}
+				},
+			}
+			DidDeposits::<T>::insert(did, (who, deposit));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> DidResolver<T::AccountId> for Pallet<T> {
+		// Registered DIDs resolve to their first assertion key, or to the assertion method
+		// named by the fragment of a DID URL; unregistered ones fall back to implicit resolution
+		fn resolve(did: &[u8]) -> Result<T::AccountId, DidError> {
+			let parsed = Did::parse_bytes(did)?;
+			let (document, metadata) = match Self::document(&did[..parsed.did_len()]) {
+				Some(entry) => entry,
+				None => return seneca_account_id(did, T::SS58Prefix::get()),
+			};
+			ensure!(!metadata.deactivated, DidError::Deactivated);
+			let key = match parsed.fragment {
+				Some(fragment) => document
+					.assertion_method
+					.iter()
+					.find(|id| id.as_slice() == fragment.as_bytes())
+					.and_then(|id| document.method(id))
+					.map(|method| &method.public_key),
+				None => document.assertion_keys().next(),
+			};
+			key.cloned().ok_or(DidError::NoAssertionKey)
+		}
+
+		// Registered DIDs authenticate with the keys listed under `authentication`, unregistered
+		// ones with the account their method-specific id encodes
+		fn authentication_keys(did: &[u8]) -> Result<Vec<T::AccountId>, DidError> {
+			let parsed = Did::parse_bytes(did)?;
+			match Self::document(&did[..parsed.did_len()]) {
+				Some((document, metadata)) => {
+					ensure!(!metadata.deactivated, DidError::Deactivated);
+					Ok(document.authentication_keys().cloned().collect())
+				},
+				None => seneca_account_id(did, T::SS58Prefix::get()).map(|account| vec![account]),
+			}
+		}
+	}
+}
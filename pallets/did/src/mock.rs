@@ -0,0 +1,133 @@
+use crate as pallet_did;
+use frame_support::traits::{Get, OnTimestampSet};
+use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use frame_support::BoundedVec;
+use frame_system as system;
+use sp_core::sr25519;
+use sp_core::Pair;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use sp_std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+type Moment = u64;
+
+pub const INITIAL_BALANCE: u64 = 1_000_000;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		DidRegistry: pallet_did,
+		Timestamp: pallet_timestamp,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = sp_core::sr25519::Public;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+thread_local! {
+	pub static CAPTURED_MOMENT: RefCell<Option<Moment>> = RefCell::new(None);
+}
+
+pub struct MockOnTimestampSet;
+impl OnTimestampSet<Moment> for MockOnTimestampSet {
+	fn on_timestamp_set(moment: Moment) {
+		CAPTURED_MOMENT.with(|x| *x.borrow_mut() = Some(moment));
+	}
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type HoldIdentifier = ();
+	type FreezeIdentifier = ();
+	type MaxHolds = ();
+	type MaxFreezes = ();
+	type WeightInfo = ();
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = Moment;
+	type OnTimestampSet = MockOnTimestampSet;
+	type MinimumPeriod = ConstU64<5>;
+	type WeightInfo = ();
+}
+
+impl pallet_did::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Moment = Moment;
+	type Timestamp = Timestamp;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type DidDepositBase = ConstU64<100>;
+	type DidDepositPerByte = ConstU64<1>;
+	type MaxDidLength = ConstU32<128>;
+	type MaxEntries = ConstU32<8>;
+	type MaxServiceLength = ConstU32<256>;
+}
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: ["Alice", "Bob", "Charlie"]
+			.iter()
+			.map(|name| (account_pair(name).public(), INITIAL_BALANCE))
+			.collect(),
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
+}
+
+/// Bounded copy of `data`, for building DID document fixtures.
+pub fn bounded<S: Get<u32>>(data: &[u8]) -> BoundedVec<u8, S> {
+	data.to_vec().try_into().expect("fixtures fit the mock bounds; qed")
+}
+
+pub fn account_pair(s: &str) -> sr25519::Pair {
+	sr25519::Pair::from_string(&format!("//{}", s), None).expect("static values are valid; qed")
+}
+
+pub fn did_of(pair: &sr25519::Pair) -> crate::DidOf<Test> {
+	bounded(format!("did:seneca:{}", pair.public()).as_bytes())
+}
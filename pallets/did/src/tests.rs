@@ -0,0 +1,276 @@
+use crate::mock::*;
+use crate::types::*;
+use crate::{DidDocumentOf, DidDocuments, DidOf, Error};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, bounded_vec, traits::ReservableCurrency};
+use frame_system::RawOrigin;
+use node_primitives::did::{DidError, DidResolver};
+use sp_core::{sr25519, Pair};
+
+#[test]
+fn it_works_for_register_did() {
+	new_test_ext().execute_with(|| {
+		let alice = account_pair("Alice");
+		let did = did_of(&alice);
+		let document = test_document(&did, alice.public());
+		assert_ok!(DidRegistry::register_did(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone(),
+			document.clone()
+		));
+
+		let (stored, metadata) = DidDocuments::<Test>::get(&did).unwrap();
+		assert_eq!(stored, document);
+		assert!(!metadata.deactivated);
+		assert_eq!(DidRegistry::resolve(&did), Ok(alice.public()));
+		// the deposit covers the base and every byte of the document
+		assert_eq!(
+			Balances::reserved_balance(alice.public()),
+			100 + document.encoded_size() as u64
+		);
+		assert_noop!(
+			DidRegistry::register_did(RawOrigin::Signed(alice.public()).into(), did, document),
+			Error::<Test>::DidAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn register_did_validates_owner_and_document() {
+	new_test_ext().execute_with(|| {
+		let alice = account_pair("Alice");
+		let bob = account_pair("Bob");
+		let did = did_of(&alice);
+		let document = test_document(&did, alice.public());
+		assert_noop!(
+			DidRegistry::register_did(
+				RawOrigin::Signed(bob.public()).into(),
+				did.clone(),
+				document.clone()
+			),
+			Error::<Test>::NotDidController
+		);
+
+		let url: DidOf<Test> = bounded(&[&did[..], &b"#key-1"[..]].concat());
+		assert_noop!(
+			DidRegistry::register_did(
+				RawOrigin::Signed(alice.public()).into(),
+				url,
+				document.clone()
+			),
+			Error::<Test>::InvalidDID
+		);
+
+		let mut dangling = document.clone();
+		dangling.assertion_method = bounded_vec![bounded(b"key-2")];
+		assert_noop!(
+			DidRegistry::register_did(
+				RawOrigin::Signed(alice.public()).into(),
+				did.clone(),
+				dangling
+			),
+			Error::<Test>::UnknownVerificationMethod
+		);
+
+		let mut duplicate = document.clone();
+		let method = duplicate.verification_method[0].clone();
+		duplicate.verification_method.try_push(method).unwrap();
+		assert_noop!(
+			DidRegistry::register_did(
+				RawOrigin::Signed(alice.public()).into(),
+				did.clone(),
+				duplicate
+			),
+			Error::<Test>::DuplicateVerificationMethod
+		);
+
+		let mut unauthenticated = document;
+		unauthenticated.authentication = Default::default();
+		assert_noop!(
+			DidRegistry::register_did(
+				RawOrigin::Signed(alice.public()).into(),
+				did,
+				unauthenticated
+			),
+			Error::<Test>::MissingAuthenticationKey
+		);
+	});
+}
+
+#[test]
+fn it_works_for_rotate_key() {
+	new_test_ext().execute_with(|| {
+		let alice = account_pair("Alice");
+		let bob = account_pair("Bob");
+		let did = did_of(&alice);
+		let document = test_document(&did, alice.public());
+		assert_ok!(DidRegistry::register_did(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone(),
+			document.clone()
+		));
+
+		assert_ok!(DidRegistry::rotate_key(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone(),
+			bounded(b"key-1"),
+			bob.public()
+		));
+		assert_eq!(DidRegistry::resolve(&did), Ok(bob.public()));
+		// the rotated out key no longer controls the DID
+		assert_noop!(
+			DidRegistry::update_did(
+				RawOrigin::Signed(alice.public()).into(),
+				did.clone(),
+				document.clone()
+			),
+			Error::<Test>::NotDidController
+		);
+		assert_noop!(
+			DidRegistry::rotate_key(
+				RawOrigin::Signed(bob.public()).into(),
+				did.clone(),
+				bounded(b"key-2"),
+				alice.public()
+			),
+			Error::<Test>::UnknownVerificationMethod
+		);
+		assert_ok!(DidRegistry::update_did(RawOrigin::Signed(bob.public()).into(), did, document));
+	});
+}
+
+#[test]
+fn controller_can_update_did() {
+	new_test_ext().execute_with(|| {
+		let alice = account_pair("Alice");
+		let charlie = account_pair("Charlie");
+		let did = did_of(&alice);
+		let mut document = test_document(&did, alice.public());
+		document.controller = bounded_vec![did_of(&charlie)];
+		assert_ok!(DidRegistry::register_did(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone(),
+			document.clone()
+		));
+
+		document.service = Default::default();
+		assert_ok!(DidRegistry::update_did(
+			RawOrigin::Signed(charlie.public()).into(),
+			did.clone(),
+			document.clone()
+		));
+		assert_eq!(
+			DidDocuments::<Test>::get(&did).map(|(document, _)| document),
+			Some(document.clone())
+		);
+		// the controller updating the document takes over its deposit
+		assert_eq!(Balances::reserved_balance(alice.public()), 0);
+		assert_eq!(
+			Balances::reserved_balance(charlie.public()),
+			100 + document.encoded_size() as u64
+		);
+		assert_noop!(
+			DidRegistry::deactivate_did(
+				RawOrigin::Signed(account_pair("Bob").public()).into(),
+				did
+			),
+			Error::<Test>::NotDidController
+		);
+	});
+}
+
+#[test]
+fn it_works_for_deactivate_did() {
+	new_test_ext().execute_with(|| {
+		let alice = account_pair("Alice");
+		let did = did_of(&alice);
+		let document = test_document(&did, alice.public());
+		assert_ok!(DidRegistry::register_did(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone(),
+			document.clone()
+		));
+		assert_ok!(DidRegistry::deactivate_did(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone()
+		));
+
+		assert_eq!(DidRegistry::resolve(&did), Err(DidError::Deactivated));
+		assert_noop!(
+			DidRegistry::update_did(
+				RawOrigin::Signed(alice.public()).into(),
+				did.clone(),
+				document
+			),
+			Error::<Test>::DidDeactivated
+		);
+		assert_noop!(
+			DidRegistry::deactivate_did(
+				RawOrigin::Signed(alice.public()).into(),
+				did_of(&account_pair("Bob"))
+			),
+			Error::<Test>::UnknownDid
+		);
+	});
+}
+
+#[test]
+fn resolve_selects_assertion_method() {
+	new_test_ext().execute_with(|| {
+		let alice = account_pair("Alice");
+		let bob = account_pair("Bob");
+		let did = did_of(&alice);
+		let mut document = test_document(&did, alice.public());
+		document
+			.verification_method
+			.try_push(VerificationMethod {
+				id: bounded(b"key-2"),
+				controller: did.clone(),
+				public_key: bob.public(),
+			})
+			.unwrap();
+		document.assertion_method = bounded_vec![bounded(b"key-2")];
+		assert_ok!(DidRegistry::register_did(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone(),
+			document
+		));
+
+		let key_url = |fragment: &str| [did.as_slice(), &b"#"[..], fragment.as_bytes()].concat();
+		assert_eq!(DidRegistry::resolve(&did), Ok(bob.public()));
+		assert_eq!(DidRegistry::resolve(&key_url("key-2")), Ok(bob.public()));
+		assert_eq!(DidRegistry::resolve(&key_url("key-1")), Err(DidError::NoAssertionKey));
+		// unregistered DIDs resolve implicitly
+		assert_eq!(DidRegistry::resolve(&did_of(&bob)), Ok(bob.public()));
+		assert_eq!(DidRegistry::resolve(b"did:seneca"), Err(DidError::InvalidMethodSpecificId));
+
+		// the holder authenticates with the authentication keys only
+		let authentication_keys =
+			<DidRegistry as DidResolver<sr25519::Public>>::authentication_keys;
+		assert_eq!(authentication_keys(&did), Ok(vec![alice.public()]));
+		assert_eq!(authentication_keys(&did_of(&bob)), Ok(vec![bob.public()]));
+		assert_ok!(DidRegistry::deactivate_did(
+			RawOrigin::Signed(alice.public()).into(),
+			did.clone()
+		));
+		assert_eq!(authentication_keys(&did), Err(DidError::Deactivated));
+	});
+}
+
+fn test_document(did: &DidOf<Test>, key: sr25519::Public) -> DidDocumentOf<Test> {
+	DidDocument {
+		controller: Default::default(),
+		verification_method: bounded_vec![VerificationMethod {
+			id: bounded(b"key-1"),
+			controller: did.clone(),
+			public_key: key,
+		}],
+		authentication: bounded_vec![bounded(b"key-1")],
+		assertion_method: bounded_vec![bounded(b"key-1")],
+		service: bounded_vec![Service {
+			id: bounded(b"hub"),
+			service_type: bounded(b"LinkedDomains"),
+			service_endpoint: bounded(b"https://seneca.example"),
+		}],
+	}
+}
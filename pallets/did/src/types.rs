@@ -0,0 +1,87 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{traits::Get, BoundedVec, RuntimeDebug};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Bounds on the variable length fields of DID documents.
+pub trait DidLimits: 'static {
+	/// Longest DID, or id of a verification method or service.
+	type MaxDidLength: Get<u32>;
+	/// Most controllers, verification methods, relationship entries or services in one list.
+	type MaxEntries: Get<u32>;
+	/// Longest service type or endpoint.
+	type MaxServiceLength: Get<u32>;
+}
+
+pub type BoundedDid<L> = BoundedVec<u8, <L as DidLimits>::MaxDidLength>;
+pub type BoundedIds<L> = BoundedVec<BoundedDid<L>, <L as DidLimits>::MaxEntries>;
+
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound(AccountId: MaxEncodedLen))]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(serialize = "AccountId: Serialize", deserialize = "AccountId: Deserialize<'de>"))
+)]
+pub struct DidDocument<AccountId, L: DidLimits> {
+	/// DIDs allowed to update this document besides its own authentication keys.
+	pub controller: BoundedIds<L>,
+	pub verification_method: BoundedVec<VerificationMethod<AccountId, L>, L::MaxEntries>,
+	/// Ids of the verification methods used to authenticate as the DID subject.
+	pub authentication: BoundedIds<L>,
+	/// Ids of the verification methods used to sign schemas and credentials.
+	pub assertion_method: BoundedIds<L>,
+	pub service: BoundedVec<Service<L>, L::MaxEntries>,
+}
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound(AccountId: MaxEncodedLen))]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(serialize = "AccountId: Serialize", deserialize = "AccountId: Deserialize<'de>"))
+)]
+pub struct VerificationMethod<AccountId, L: DidLimits> {
+	/// Fragment identifying the method within the document, e.g. `key-1`.
+	pub id: BoundedDid<L>,
+	pub controller: BoundedDid<L>,
+	pub public_key: AccountId,
+}
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct Service<L: DidLimits> {
+	pub id: BoundedDid<L>,
+	pub service_type: BoundedVec<u8, L::MaxServiceLength>,
+	pub service_endpoint: BoundedVec<u8, L::MaxServiceLength>,
+}
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DidMetadata<Moment> {
+	pub created: Moment,
+	pub updated: Moment,
+	pub deactivated: bool,
+}
+
+impl<AccountId, L: DidLimits> DidDocument<AccountId, L> {
+	/// The verification method with the given id.
+	pub fn method(&self, id: &[u8]) -> Option<&VerificationMethod<AccountId, L>> {
+		self.verification_method.iter().find(|method| method.id[..] == id[..])
+	}
+
+	/// Keys of the methods listed under `authentication`.
+	pub fn authentication_keys(&self) -> impl Iterator<Item = &AccountId> {
+		self.authentication.iter().filter_map(|id| self.method(id)).map(|m| &m.public_key)
+	}
+
+	/// Keys of the methods listed under `assertionMethod`.
+	pub fn assertion_keys(&self) -> impl Iterator<Item = &AccountId> {
+		self.assertion_method.iter().filter_map(|id| self.method(id)).map(|m| &m.public_key)
+	}
+}
@@ -0,0 +1,107 @@
+//! Weights for `pallet_did`
+//!
+//! These are estimates, not benchmark output: they are scaled from the measured weights of the
+//! schema and credential registry calls with a comparable storage footprint. Regenerate this
+//! file with `scripts/benchmark.sh` on reference hardware before relying on it.
+
+// Command to regenerate:
+// ./target/production/zeno-node
+// benchmark
+// pallet
+// --chain
+// dev
+// --execution=wasm
+// --wasm-execution=compiled
+// --pallet
+// pallet_did
+// --extrinsic
+// *
+// --steps
+// 50
+// --repeat
+// 20
+// --template
+// scripts/frame-weight-template.hbs
+// --output
+// pallets/did/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{constants::RocksDbWeight,Weight}};
+use sp_std::marker::PhantomData;
+
+pub trait WeightInfo {
+	fn register_did(l: u32, ) -> Weight;
+	fn update_did(l: u32, ) -> Weight;
+	fn rotate_key() -> Weight;
+	fn deactivate_did() -> Weight;
+}
+
+/// Weight functions for `pallet_did`.
+pub struct DidRegistryWeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for DidRegistryWeightInfo<T> {
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Did DidDocuments (r:1 w:1)
+	// Storage: Did DidDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn register_did(l: u32, ) -> Weight {
+		Weight::from_parts(36_000_000, 0u64)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Did DidDocuments (r:2 w:1)
+	// Storage: Did DidDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn update_did(l: u32, ) -> Weight {
+		Weight::from_parts(38_000_000, 0u64)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Did DidDocuments (r:2 w:1)
+	fn rotate_key() -> Weight {
+		Weight::from_parts(21_402_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Did DidDocuments (r:2 w:1)
+	fn deactivate_did() -> Weight {
+		Weight::from_parts(20_064_000, 0u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+}
+
+impl WeightInfo for () {
+	fn register_did(l: u32, ) -> Weight {
+		Weight::from_parts(36_000_000, 0u64)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+
+	fn update_did(l: u32, ) -> Weight {
+		Weight::from_parts(38_000_000, 0u64)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+
+	fn rotate_key() -> Weight {
+		Weight::from_parts(21_402_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+
+	fn deactivate_did() -> Weight {
+		Weight::from_parts(20_064_000, 0u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+}
@@ -0,0 +1,133 @@
+use crate::types::AttributeType;
+
+impl AttributeType {
+	/// Returns whether `value` is a valid textual encoding of this attribute type.
+	pub fn accepts(&self, value: &[u8]) -> bool {
+		let value = match sp_std::str::from_utf8(value) {
+			Ok(value) => value,
+			Err(_) => return false,
+		};
+		match self {
+			AttributeType::Int => value.parse::<i128>().is_ok(),
+			AttributeType::Uint => value.parse::<u128>().is_ok(),
+			AttributeType::Float => is_float(value),
+			AttributeType::Hex => is_hex(value),
+			AttributeType::DateType => is_date(value),
+			AttributeType::Base64 => is_base64(value),
+			AttributeType::Text => true,
+		}
+	}
+}
+
+fn is_digits(s: &str) -> bool {
+	!s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+// [+-]digits[.digits][(e|E)[+-]digits], either side of the dot may be empty but not both
+fn is_float(value: &str) -> bool {
+	let value = value.strip_prefix(|c: char| c == '+' || c == '-').unwrap_or(value);
+	let (mantissa, exponent) = match value.find(|c: char| c == 'e' || c == 'E') {
+		Some(pos) => (&value[..pos], Some(&value[pos + 1..])),
+		None => (value, None),
+	};
+	let mantissa_ok = match mantissa.split_once('.') {
+		Some((int, frac)) =>
+			(int.is_empty() || is_digits(int)) &&
+				(frac.is_empty() || is_digits(frac)) &&
+				!(int.is_empty() && frac.is_empty()),
+		None => is_digits(mantissa),
+	};
+	let exponent_ok = match exponent {
+		Some(exponent) =>
+			is_digits(exponent.strip_prefix(|c: char| c == '+' || c == '-').unwrap_or(exponent)),
+		None => true,
+	};
+	mantissa_ok && exponent_ok
+}
+
+// optional 0x prefix followed by at least one hex digit
+fn is_hex(value: &str) -> bool {
+	let digits = value.strip_prefix("0x").unwrap_or(value);
+	!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+// standard alphabet with up to two trailing padding characters
+fn is_base64(value: &str) -> bool {
+	if value.is_empty() || value.len() % 4 != 0 {
+		return false
+	}
+	let data = value.trim_end_matches('=');
+	value.len() - data.len() <= 2 &&
+		data.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+// ISO 8601 calendar date, optionally followed by a time and a UTC offset
+// e.g. `2023-06-30` or `2023-06-30T12:00:00.000+02:00`
+fn is_date(value: &str) -> bool {
+	let (date, time) = match value.split_once(|c: char| c == 'T' || c == 't') {
+		Some((date, time)) => (date, Some(time)),
+		None => (value, None),
+	};
+	is_calendar_date(date) && time.map_or(true, is_time)
+}
+
+fn is_calendar_date(date: &str) -> bool {
+	let bytes = date.as_bytes();
+	if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+		return false
+	}
+	let (year, month, day) = match (
+		number_in(&date[0..4], 0, 9999),
+		number_in(&date[5..7], 1, 12),
+		number_in(&date[8..10], 1, 31),
+	) {
+		(Some(year), Some(month), Some(day)) => (year, month, day),
+		_ => return false,
+	};
+	let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+	let days_in_month = match month {
+		2 if leap => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	};
+	day <= days_in_month
+}
+
+fn is_time(time: &str) -> bool {
+	let (clock, offset) = if let Some(clock) = time.strip_suffix(|c: char| c == 'Z' || c == 'z') {
+		(clock, None)
+	} else if let Some(pos) = time.rfind(|c: char| c == '+' || c == '-') {
+		(&time[..pos], Some(&time[pos + 1..]))
+	} else {
+		(time, None)
+	};
+	let (clock, fraction) = match clock.split_once('.') {
+		Some((clock, fraction)) => (clock, Some(fraction)),
+		None => (clock, None),
+	};
+	let bytes = clock.as_bytes();
+	bytes.len() == 8 &&
+		bytes[2] == b':' &&
+		bytes[5] == b':' &&
+		number_in(&clock[0..2], 0, 23).is_some() &&
+		number_in(&clock[3..5], 0, 59).is_some() &&
+		number_in(&clock[6..8], 0, 60).is_some() &&
+		fraction.map_or(true, is_digits) &&
+		offset.map_or(true, is_offset)
+}
+
+fn is_offset(offset: &str) -> bool {
+	let bytes = offset.as_bytes();
+	bytes.len() == 5 &&
+		bytes[2] == b':' &&
+		number_in(&offset[0..2], 0, 23).is_some() &&
+		number_in(&offset[3..5], 0, 59).is_some()
+}
+
+fn number_in(s: &str, min: u32, max: u32) -> Option<u32> {
+	if !is_digits(s) {
+		return None
+	}
+	s.parse::<u32>().ok().filter(|n| *n >= min && *n <= max)
+}
@@ -5,10 +5,10 @@ use crate::types::*;
 use crate::Pallet as SchemaRegistry;
 use codec::Encode;
 use frame_benchmarking::benchmarks;
-use frame_support::assert_ok;
+use frame_support::{assert_ok, bounded_vec, traits::Currency, BoundedVec};
 use frame_system::RawOrigin;
 use scale_info::prelude::format;
-use scale_info::prelude::vec;
+use sp_runtime::traits::Bounded;
 use sp_application_crypto::RuntimePublic;
 use sp_application_crypto::sr25519::Public;
 benchmarks! {
@@ -22,46 +22,47 @@ benchmarks! {
 		let schema_id: T::SchemaId = Default::default();
 		let public = Public::generate_pair(sp_core::testing::SR25519, None);
 		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
 		let account_id = format!("did:seneca:{:#?}", caller.clone());
-		let schema: VerifiableCredentialSchema<T::Moment> = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.into(),
+		let schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.into_bytes().try_into().unwrap(),
 			public: false,
 			creation_date: Default::default(),
 			expiration_date: Some(Default::default()),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: b"metadata".to_vec().try_into().unwrap(),
 			nonce: 2u64,
 		};
 		// sign the schema in benchmarks
 		let sig = public.sign(sp_core::testing::SR25519, &schema.encode()).unwrap();
 		// Encode and sign the schema message.
 	}:  _(RawOrigin::Signed(caller), 
-			schema_id.clone(),
+			Some(schema_id.clone()),
 			schema.clone().name,
 			schema.clone().creator,
 			false,
@@ -83,82 +84,187 @@ benchmarks! {
 		let schema_id: T::SchemaId = Default::default();
 		let public = Public::generate_pair(sp_core::testing::SR25519, None);
 		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
 		let account_id = format!("did:seneca:{:#?}", caller.clone());
-		let schema: VerifiableCredentialSchema<T::Moment> = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.clone().into(),
+		let schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.clone().into_bytes().try_into().unwrap(),
 			public: false,
 			creation_date: Default::default(),
 			expiration_date: Some(Default::default()),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: b"metadata".to_vec().try_into().unwrap(),
 			nonce: 2u64,
 		};
-		let updated_schema = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.into(),
+		let updated_schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.into_bytes().try_into().unwrap(),
 			public: false,
 			creation_date: Default::default(),
 			expiration_date: Some(Default::default()),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata2".to_vec(),
+			metadata: b"metadata2".to_vec().try_into().unwrap(),
+			nonce: 3u64,
+		};
+
+		// sign the schema in benchmarks
+		let sig = public.sign(sp_core::testing::SR25519, &schema.encode()).unwrap();
+		let updated_sig = public.sign(sp_core::testing::SR25519, &updated_schema.encode()).unwrap();
+
+		assert_ok!(SchemaRegistry::<T>::create_schema(
+			RawOrigin::Signed(caller.clone()).into(), 
+			Some(schema_id.clone()),
+			schema.name,
+			schema.creator,
+			false,
+			schema.mandatory_fields,
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims,
+			schema.subject_claims,
+			schema.credential_claims,
+			schema.metadata,
+			sig.clone().into(),
+			schema.nonce
+		));
+	}:  _(RawOrigin::Signed(caller), schema_id.clone(), (updated_sig.clone().into(), updated_schema.clone()))
+	verify {
+		assert_eq!(SchemaStore::<T>::get(schema_id.clone()), Some((updated_sig.into(), updated_schema)));
+		assert_eq!(SchemaRevisions::<T>::get(schema_id), 1);
+		assert!(SchemaVersions::<T>::contains_key(schema_id, 0));
+	}
+
+	deprecate_schema_version{
+		let s in 0 .. 100;
+		let schema_id: T::SchemaId = Default::default();
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let account_id = format!("did:seneca:{:#?}", caller.clone());
+		let schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.clone().into_bytes().try_into().unwrap(),
+			public: false,
+			creation_date: Default::default(),
+			expiration_date: Some(Default::default()),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
+				attribute_type: AttributeType::Hex,
+			}],
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::IssuerClaim,
+				issuance_requirement: None,
+			}],
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::SubjectClaim,
+				issuance_requirement: None,
+			}],
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::CredentialClaim,
+				issuance_requirement: None,
+			}],
+			metadata: b"metadata".to_vec().try_into().unwrap(),
 			nonce: 2u64,
 		};
+		let updated_schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.into_bytes().try_into().unwrap(),
+			public: false,
+			creation_date: Default::default(),
+			expiration_date: Some(Default::default()),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
+				attribute_type: AttributeType::Hex,
+			}],
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::IssuerClaim,
+				issuance_requirement: None,
+			}],
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::SubjectClaim,
+				issuance_requirement: None,
+			}],
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::CredentialClaim,
+				issuance_requirement: None,
+			}],
+			metadata: b"metadata2".to_vec().try_into().unwrap(),
+			nonce: 3u64,
+		};
 
 		// sign the schema in benchmarks
 		let sig = public.sign(sp_core::testing::SR25519, &schema.encode()).unwrap();
+		let updated_sig = public.sign(sp_core::testing::SR25519, &updated_schema.encode()).unwrap();
 
 		assert_ok!(SchemaRegistry::<T>::create_schema(
 			RawOrigin::Signed(caller.clone()).into(), 
-			schema_id.clone(),
+			Some(schema_id.clone()),
 			schema.name,
 			schema.creator,
 			false,
@@ -172,50 +278,145 @@ benchmarks! {
 			sig.clone().into(),
 			schema.nonce
 		));
-	}:  _(RawOrigin::Signed(caller), schema_id.clone(), (sig.clone().into(), updated_schema.clone()))
+		assert_ok!(SchemaRegistry::<T>::update_schema(
+			RawOrigin::Signed(caller.clone()).into(),
+			schema_id.clone(),
+			(updated_sig.into(), updated_schema)
+		));
+	}:  _(RawOrigin::Signed(caller), schema_id.clone(), 0)
 	verify {
-		assert_eq!(SchemaStore::<T>::get(schema_id.clone()), Some((sig.into(), updated_schema)));
+		assert!(DeprecatedVersions::<T>::contains_key(schema_id, 0));
 	}
 
 	delete_schema{
+		let v in 0 .. 100;
+		let i in 0 .. T::MaxAuthorizedIssuers::get();
+		// Dispatch a signed extrinsic.
+		let schema_id: T::SchemaId = Default::default();
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let account_id = format!("did:seneca:{:#?}", caller.clone());
+		let schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.into_bytes().try_into().unwrap(),
+			public: false,
+			creation_date: Default::default(),
+			expiration_date: Some(Default::default()),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
+				attribute_type: AttributeType::Hex,
+			}],
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::IssuerClaim,
+				issuance_requirement: None,
+			}],
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::SubjectClaim,
+				issuance_requirement: None,
+			}],
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::CredentialClaim,
+				issuance_requirement: None,
+			}],
+			metadata: b"metadata".to_vec().try_into().unwrap(),
+			nonce: 2u64,
+		};
+
+		let sig = public.sign(sp_core::testing::SR25519, &schema.encode()).unwrap();
+		let original = schema.clone();
+
+		assert_ok!(SchemaRegistry::<T>::create_schema(
+			RawOrigin::Signed(caller.clone()).into(), 
+			Some(schema_id.clone()),
+			schema.name,
+			schema.creator,
+			false,
+			schema.mandatory_fields,
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims,
+			schema.subject_claims,
+			schema.credential_claims,
+			schema.metadata,
+			sig.clone().into(),
+			schema.nonce
+		));
+		// `v` superseded versions and `i` authorized issuers are deleted with the schema
+		for version in 0..v {
+			let updated =
+				SchemaOf::<T> { nonce: original.nonce + 1 + version as u64, ..original.clone() };
+			let updated_sig = public.sign(sp_core::testing::SR25519, &updated.encode()).unwrap();
+			assert_ok!(SchemaRegistry::<T>::update_schema(
+				RawOrigin::Signed(caller.clone()).into(),
+				schema_id.clone(),
+				(updated_sig.into(), updated)
+			));
+		}
+		for issuer in 0..i {
+			let issuer: BoundedVec<u8, T::MaxDidLength> =
+				format!("did:seneca:issuer{}", issuer).into_bytes().try_into().unwrap();
+			assert_ok!(SchemaRegistry::<T>::add_issuer(
+				RawOrigin::Signed(caller.clone()).into(),
+				schema_id.clone(),
+				issuer
+			));
+		}
+	}:  _(RawOrigin::Signed(caller), schema_id.clone(), v)
+	verify {
+		assert_eq!(SchemaStore::<T>::get(schema_id), None);
+		assert_eq!(AuthorizedIssuerCount::<T>::get(schema_id), 0);
+	}
+
+	deprecate_schema{
 		let s in 0 .. 100;
 		// Dispatch a signed extrinsic.
 		let schema_id: T::SchemaId = Default::default();
 		let public = Public::generate_pair(sp_core::testing::SR25519, None);
 		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
 		let account_id = format!("did:seneca:{:#?}", caller.clone());
-		let schema: VerifiableCredentialSchema<T::Moment> = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.into(),
+		let schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.into_bytes().try_into().unwrap(),
 			public: false,
 			creation_date: Default::default(),
 			expiration_date: Some(Default::default()),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: b"metadata".to_vec().try_into().unwrap(),
 			nonce: 2u64,
 		};
 
@@ -223,7 +424,7 @@ benchmarks! {
 
 		assert_ok!(SchemaRegistry::<T>::create_schema(
 			RawOrigin::Signed(caller.clone()).into(), 
-			schema_id.clone(),
+			Some(schema_id.clone()),
 			schema.name,
 			schema.creator,
 			false,
@@ -239,7 +440,148 @@ benchmarks! {
 		));
 	}:  _(RawOrigin::Signed(caller), schema_id.clone())
 	verify {
-		assert_eq!(SchemaStore::<T>::get(schema_id), None);
+		assert!(DeprecatedSchemas::<T>::contains_key(schema_id));
+	}
+
+	add_issuer{
+		let s in 0 .. 100;
+		// Dispatch a signed extrinsic.
+		let schema_id: T::SchemaId = Default::default();
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let account_id = format!("did:seneca:{:#?}", caller.clone());
+		let schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.into_bytes().try_into().unwrap(),
+			public: false,
+			creation_date: Default::default(),
+			expiration_date: Some(Default::default()),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
+				attribute_type: AttributeType::Hex,
+			}],
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::IssuerClaim,
+				issuance_requirement: None,
+			}],
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::SubjectClaim,
+				issuance_requirement: None,
+			}],
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::CredentialClaim,
+				issuance_requirement: None,
+			}],
+			metadata: b"metadata".to_vec().try_into().unwrap(),
+			nonce: 2u64,
+		};
+
+		let sig = public.sign(sp_core::testing::SR25519, &schema.encode()).unwrap();
+
+		assert_ok!(SchemaRegistry::<T>::create_schema(
+			RawOrigin::Signed(caller.clone()).into(), 
+			Some(schema_id.clone()),
+			schema.name,
+			schema.creator,
+			false,
+			schema.mandatory_fields,
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims,
+			schema.subject_claims,
+			schema.credential_claims,
+			schema.metadata,
+			sig.clone().into(),
+			schema.nonce
+		));
+		let issuer: BoundedVec<u8, T::MaxDidLength> =
+			b"did:seneca:issuer".to_vec().try_into().unwrap();
+	}:  _(RawOrigin::Signed(caller), schema_id.clone(), issuer.clone())
+	verify {
+		assert!(AuthorizedIssuers::<T>::contains_key(schema_id, issuer));
+	}
+
+	remove_issuer{
+		let s in 0 .. 100;
+		// Dispatch a signed extrinsic.
+		let schema_id: T::SchemaId = Default::default();
+		let public = Public::generate_pair(sp_core::testing::SR25519, None);
+		let caller: T::AccountId = public.into();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let account_id = format!("did:seneca:{:#?}", caller.clone());
+		let schema: SchemaOf<T> = VerifiableCredentialSchema {
+			name: b"Alice Data".to_vec().try_into().unwrap(),
+			creator: account_id.into_bytes().try_into().unwrap(),
+			public: false,
+			creation_date: Default::default(),
+			expiration_date: Some(Default::default()),
+			mandatory_fields: bounded_vec![Attribute {
+				name: b"name".to_vec().try_into().unwrap(),
+				attribute_type: AttributeType::Hex,
+			}],
+			issuer_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::IssuerClaim,
+				issuance_requirement: None,
+			}],
+			subject_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::SubjectClaim,
+				issuance_requirement: None,
+			}],
+			credential_claims: bounded_vec![Claim {
+				property: b"property".to_vec().try_into().unwrap(),
+				value: b"value".to_vec().try_into().unwrap(),
+				schemaid: None,
+				claim_type: ClaimType::CredentialClaim,
+				issuance_requirement: None,
+			}],
+			metadata: b"metadata".to_vec().try_into().unwrap(),
+			nonce: 2u64,
+		};
+
+		let sig = public.sign(sp_core::testing::SR25519, &schema.encode()).unwrap();
+
+		assert_ok!(SchemaRegistry::<T>::create_schema(
+			RawOrigin::Signed(caller.clone()).into(), 
+			Some(schema_id.clone()),
+			schema.name,
+			schema.creator,
+			false,
+			schema.mandatory_fields,
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims,
+			schema.subject_claims,
+			schema.credential_claims,
+			schema.metadata,
+			sig.clone().into(),
+			schema.nonce
+		));
+		let issuer: BoundedVec<u8, T::MaxDidLength> =
+			b"did:seneca:issuer".to_vec().try_into().unwrap();
+		assert_ok!(SchemaRegistry::<T>::add_issuer(
+			RawOrigin::Signed(caller.clone()).into(),
+			schema_id.clone(),
+			issuer.clone()
+		));
+	}:  _(RawOrigin::Signed(caller), schema_id.clone(), issuer.clone())
+	verify {
+		assert!(!AuthorizedIssuers::<T>::contains_key(schema_id, issuer));
 	}
 	impl_benchmark_test_suite!(SchemaRegistry, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -1,5 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod attribute;
 #[cfg(test)]
 mod mock;
 
@@ -11,6 +12,7 @@ pub mod types;
 pub use pallet::*;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
 #[frame_support::pallet]
@@ -22,14 +24,39 @@ pub mod pallet {
 	use frame_support::{
 		ensure,
 		pallet_prelude::*,
-		sp_runtime::traits::{IdentifyAccount, Member, Scale, Verify},
-		traits::{IsType, Time},
+		sp_runtime::traits::{AtLeast32Bit, IdentifyAccount, Member, Saturating, Scale, Verify},
+		traits::{Currency, IsType, ReservableCurrency, Time},
+		CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
 	};
 	use frame_system::pallet_prelude::*;
+	use node_primitives::did::{DidError, DidResolver};
 	use scale_info::{prelude::vec::Vec, StaticTypeInfo};
 
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Bounds of the schema types, taken from the pallet configuration.
+	#[derive(CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound)]
+	pub struct SchemaLimitsOf<T>(PhantomData<T>);
+
+	impl<T: Config> SchemaLimits for SchemaLimitsOf<T> {
+		type MaxNameLength = T::MaxNameLength;
+		type MaxDidLength = T::MaxDidLength;
+		type MaxClaims = T::MaxClaims;
+		type MaxValueLength = T::MaxValueLength;
+		type MaxMetadataLength = T::MaxMetadataLength;
+	}
+
+	pub type SchemaOf<T> = VerifiableCredentialSchema<<T as Config>::Moment, SchemaLimitsOf<T>>;
+
+	/// The first id assigned from the id counter, callers may only supply ids below it.
+	pub const FIRST_ASSIGNED_ID: u32 = 1 << 31;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
-	#[pallet::without_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -48,15 +75,45 @@ pub mod pallet {
 			+ Parameter
 			+ Decode
 			+ Encode
+			+ MaxEncodedLen
 			+ From<sp_core::sr25519::Signature>
 			+ TypeInfo;
 		type Moment: Parameter
 			+ Default
+			+ AtLeast32Bit
 			+ Scale<Self::BlockNumber, Output = Self::Moment>
 			+ Copy
 			+ MaxEncodedLen
 			+ StaticTypeInfo;
 		type Timestamp: Time<Moment = Self::Moment>;
+		/// Resolves the DIDs of schema creators, and of credential issuers, to their signers.
+		type DidResolver: DidResolver<Self::AccountId>;
+		/// The currency storage deposits of schemas, and of credentials, are reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The base deposit reserved for storing a schema.
+		#[pallet::constant]
+		type SchemaDepositBase: Get<BalanceOf<Self>>;
+		/// The deposit reserved per byte of a stored schema and its signature.
+		#[pallet::constant]
+		type SchemaDepositPerByte: Get<BalanceOf<Self>>;
+		/// The maximum length of a schema name, attribute name or claim property.
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+		/// The maximum length of a DID naming a schema creator or a credential party.
+		#[pallet::constant]
+		type MaxDidLength: Get<u32>;
+		/// The maximum number of attributes or claims in one list.
+		#[pallet::constant]
+		type MaxClaims: Get<u32>;
+		/// The maximum length of a claim value.
+		#[pallet::constant]
+		type MaxValueLength: Get<u32>;
+		/// The maximum length of schema metadata.
+		#[pallet::constant]
+		type MaxMetadataLength: Get<u32>;
+		/// The maximum number of issuer DIDs authorized for one schema.
+		#[pallet::constant]
+		type MaxAuthorizedIssuers: Get<u32>;
 		type WeightInfo: WeightInfo;
 		/// Identifier for the schema.
 		type SchemaId: Parameter
@@ -79,14 +136,88 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		T::SchemaId,
-		(T::Signature, VerifiableCredentialSchema<T::Moment>),
+		(T::Signature, SchemaOf<T>),
 		OptionQuery,
 	>;
 
+	/// Counter the ids of schemas created without a caller supplied id are taken from, offset by
+	/// `FIRST_ASSIGNED_ID`.
+	#[pallet::storage]
+	pub type NextSchemaId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Account and amount of the deposit reserved for each schema.
+	#[pallet::storage]
+	pub type SchemaDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::SchemaId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// Next nonce expected in a schema signed by each creator DID.
 	#[pallet::storage]
 	#[pallet::getter(fn get_nonce)]
-	pub(super) type Nonce<T: Config> = StorageValue<_, u64, ValueQuery>;
+	pub(super) type Nonce<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxDidLength>, u64, ValueQuery>;
+
+	/// Latest version of each schema, the one in `SchemaStore`. Versions start at 0 and every
+	/// update publishes the next one.
+	#[pallet::storage]
+	pub type SchemaRevisions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::SchemaId, u32, ValueQuery>;
+
+	/// Superseded versions of each schema. Published versions are never modified.
+	#[pallet::storage]
+	pub type SchemaVersions<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::SchemaId,
+		Twox64Concat,
+		u32,
+		(T::Signature, SchemaOf<T>),
+		OptionQuery,
+	>;
 
+	/// Schema versions deprecated by their creator, no new credentials are issued against them.
+	#[pallet::storage]
+	pub type DeprecatedVersions<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::SchemaId, Twox64Concat, u32, (), OptionQuery>;
+
+	/// Number of stored credentials issued against each schema.
+	#[pallet::storage]
+	pub type SchemaReferences<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::SchemaId, u32, ValueQuery>;
+
+	/// Schemas deprecated by their creator, no new credentials are issued against them.
+	#[pallet::storage]
+	pub type DeprecatedSchemas<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::SchemaId, (), OptionQuery>;
+
+	/// Issuer DIDs the creator authorized to issue credentials against each non-public schema,
+	/// besides the creator itself.
+	#[pallet::storage]
+	pub type AuthorizedIssuers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::SchemaId,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxDidLength>,
+		(),
+		OptionQuery,
+	>;
+
+	/// Number of issuer DIDs authorized for each schema, at most `MaxAuthorizedIssuers`.
+	#[pallet::storage]
+	pub type AuthorizedIssuerCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::SchemaId, u32, ValueQuery>;
+
+	/// Update history of each schema, keyed by the revision the update produced.
+	#[pallet::storage]
+	pub type SchemaHistory<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::SchemaId,
+		Twox64Concat,
+		u32,
+		UpdateRecord<T::BlockNumber, T::Moment>,
+		OptionQuery,
+	>;
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/main-docs/build/events-errors/
@@ -95,21 +226,43 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// Event documentation should end with an array that provides descriptive names for event
 		/// parameters. [something, who]
-		// Event is emitted when a Schema item is created
-		SchemaCreated(T::SchemaId, Vec<u8>),
-		// Event is emitted when an existing Schema item is updated
-		SchemaUpdated(T::SchemaId, Vec<u8>),
+		// Event is emitted when a Schema item is created, `content_hash` is the Blake2-256 hash
+		// of the encoded schema
+		SchemaCreated {
+			schema_id: T::SchemaId,
+			creator: T::AccountId,
+			creator_did: Vec<u8>,
+			content_hash: [u8; 32],
+			moment: T::Moment,
+		},
+		// Event is emitted when a new version of an existing Schema item is published
+		SchemaUpdated {
+			schema_id: T::SchemaId,
+			version: u32,
+			creator: T::AccountId,
+			creator_did: Vec<u8>,
+			content_hash: [u8; 32],
+			moment: T::Moment,
+		},
 		// Event is emitted when an existing Schema item is deleted
 		SchemaDeleted(T::SchemaId),
+		// Event is emitted when a superseded Schema version is deprecated
+		SchemaVersionDeprecated(T::SchemaId, u32),
+		// Event is emitted when an existing Schema item is deprecated
+		SchemaDeprecated(T::SchemaId),
+		// Event is emitted when an issuer DID is authorized for a Schema item
+		IssuerAdded(T::SchemaId, Vec<u8>),
+		// Event is emitted when an issuer DID is no longer authorized for a Schema item
+		IssuerRemoved(T::SchemaId, Vec<u8>),
 	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
 	pub enum Error<T> {
-		/// Error names should be descriptive.
-		NoneValue,
 		/// Errors emitted when schema already exists.
 		SchemaAlreadyExists,
+		/// Error emitted when an update leaves the schema unchanged
+		SchemaUnchanged,
 		///Error emitted when schema is unknown
 		UnknownSchema,
 		///Error emitted when schema id doesn't exist
@@ -118,9 +271,47 @@ pub mod pallet {
 		SignatureVerifyError,
 		/// Error emitted when invalid DID is used
 		InvalidDID,
+		/// Error emitted when the DID does not encode a valid account
+		InvalidDidAccount,
+		/// Error emitted when the DID is deactivated
+		DidDeactivated,
+		/// Error emitted when the DID has no assertion key
+		NoAssertionKey,
 		/// Error emitted when the origin and schema creator don't match
-		NotSchemaOwner
-
+		NotSchemaOwner,
+		/// Error emitted when the schema creation date is ahead of on-chain time
+		CreationDateInFuture,
+		/// Error emitted when the schema expiration date is already in the past
+		ExpirationDateInPast,
+		/// Error emitted when the schema has expired
+		SchemaExpired,
+		/// Error emitted when the nonce was already used by the schema creator
+		StaleNonce,
+		/// Error emitted when an update changes the schema creator
+		CreatorChanged,
+		/// Error emitted when the schema version was never published
+		UnknownSchemaVersion,
+		/// Error emitted when the schema version is deprecated
+		SchemaVersionDeprecated,
+		/// Error emitted when deprecating the latest version of a schema
+		CannotDeprecateLatestVersion,
+		/// Error emitted when deleting a schema that stored credentials were issued against
+		SchemaInUse,
+		/// Error emitted when the schema is deprecated
+		SchemaDeprecated,
+		/// Error emitted when the issuer is already on the schema allow-list
+		IssuerAlreadyAuthorized,
+		/// Error emitted when the issuer is not on the schema allow-list
+		IssuerNotAuthorized,
+		/// Error emitted when the id counter has assigned every id above `FIRST_ASSIGNED_ID`, an id
+		/// has to be supplied instead
+		NoFreeSchemaId,
+		/// Error emitted when the schema already authorizes `MaxAuthorizedIssuers` issuers
+		TooManyIssuers,
+		/// Error emitted when deleting a schema with fewer `versions` than it published
+		InvalidVersionCount,
+		/// Error emitted when a caller supplies an id reserved for the id counter
+		SchemaIdReserved,
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -128,22 +319,23 @@ pub mod pallet {
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Create a new schema item
+		/// Create a new schema item. Without an `id` the next free one is assigned and returned
+		/// in the `SchemaCreated` event, supplying it is meant for migrating existing schemas.
 		#[pallet::call_index(1)]
 		#[pallet::weight(T::WeightInfo::create_schema())]
 		pub fn create_schema(
 			origin: OriginFor<T>,
-			#[pallet::compact] id: T::SchemaId,
-			name: Vec<u8>,
-			creator: Vec<u8>,
+			id: Option<T::SchemaId>,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			creator: BoundedVec<u8, T::MaxDidLength>,
 			public: bool,
-			mandatory_fields: Vec<Attribute>,
+			mandatory_fields: BoundedAttributes<SchemaLimitsOf<T>>,
 			creation_date: T::Moment,
 			expiration_date: Option<T::Moment>,
-			issuer_claims: Vec<Claim>,
-			subject_claims: Vec<Claim>,
-			credential_claims: Vec<Claim>,
-			metadata: Vec<u8>,
+			issuer_claims: BoundedClaims<SchemaLimitsOf<T>>,
+			subject_claims: BoundedClaims<SchemaLimitsOf<T>>,
+			credential_claims: BoundedClaims<SchemaLimitsOf<T>>,
+			metadata: BoundedVec<u8, T::MaxMetadataLength>,
 			signature: T::Signature,
 			nonce: u64,
 		) -> DispatchResult {
@@ -153,8 +345,17 @@ pub mod pallet {
 			let schema_creator = Self::split_publickey_from_did(&creator)?;
 			// ensure schema creator and origin are the same
 			ensure!(schema_creator == origin, Error::<T>::NotSchemaOwner);
+			let id = match id {
+				Some(id) => {
+					ensure!(id < T::SchemaId::from(FIRST_ASSIGNED_ID), Error::<T>::SchemaIdReserved);
+					id
+				},
+				None => Self::next_schema_id()?,
+			};
 			// Ensure that the Schema does not already exist
-			ensure!(!SchemaStore::<T>::contains_key(&id), "Schema already exists");
+			ensure!(!SchemaStore::<T>::contains_key(&id), Error::<T>::SchemaAlreadyExists);
+			Self::ensure_valid_dates(creation_date, expiration_date)?;
+			Self::ensure_fresh_nonce(&creator, nonce)?;
 			// Create a new Schema item
 			Self::create_verifiable_schema(
 				&id,
@@ -173,13 +374,13 @@ pub mod pallet {
 			)
 		}
 
-		// Function to update an existing schema
+		// Function to publish a new version of an existing schema
 		#[pallet::call_index(3)]
 		#[pallet::weight(T::WeightInfo::update_schema())]
 		pub fn update_schema(
 			origin: OriginFor<T>,
 			#[pallet::compact] old_schema_key: T::SchemaId,
-			new_data: (T::Signature, VerifiableCredentialSchema<T::Moment>),
+			new_data: (T::Signature, SchemaOf<T>),
 		) -> DispatchResult {
 
 			let origin = ensure_signed(origin)?;
@@ -189,18 +390,23 @@ pub mod pallet {
 			// ensure schema creator is the one updating the schema
 			let schema_creator = Self::split_publickey_from_did(&schema_data.1.creator)?;
 			ensure!(schema_creator == origin, Error::<T>::NotSchemaOwner);
-			ensure!(schema_data != new_data, Error::<T>::SchemaAlreadyExists);
+			ensure!(schema_data.1.creator == new_data.1.creator, Error::<T>::CreatorChanged);
+			ensure!(schema_data != new_data, Error::<T>::SchemaUnchanged);
+			Self::ensure_valid_dates(new_data.1.creation_date, new_data.1.expiration_date)?;
+			Self::ensure_fresh_nonce(&new_data.1.creator, new_data.1.nonce)?;
 			// Update the schema data
 			Self::update_verifiable_schema(&old_schema_key, &new_data)
 		}
 
-		// Function to delete an existing schema
+		/// Delete an existing schema with its superseded versions, history and issuers. The call
+		/// is weighed by `versions`, which has to be at least the latest version of the schema.
 		#[pallet::call_index(5)]
-		#[pallet::weight(T::WeightInfo::delete_schema())]
+		#[pallet::weight(T::WeightInfo::delete_schema(*versions, T::MaxAuthorizedIssuers::get()))]
 		pub fn delete_schema(
 			origin: OriginFor<T>,
 			#[pallet::compact] key: T::SchemaId,
-		) -> DispatchResult {
+			versions: u32,
+		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 			// fetch schema from schema store
 			let schema_data =
@@ -209,28 +415,127 @@ pub mod pallet {
 			let schema_creator = Self::split_publickey_from_did(&schema_data.1.creator)?;
 			// ensure schema creator is the one updating the schema
 			ensure!(schema_creator == origin, Error::<T>::NotSchemaOwner);
-			Self::delete_verifiable_schema(&key)
+			// credentials issued against the schema must be deleted first
+			ensure!(SchemaReferences::<T>::get(&key) == 0, Error::<T>::SchemaInUse);
+			let latest = SchemaRevisions::<T>::get(&key);
+			ensure!(latest <= versions, Error::<T>::InvalidVersionCount);
+			let issuers = AuthorizedIssuerCount::<T>::get(&key);
+			Self::delete_verifiable_schema(&key)?;
+			Ok(Some(T::WeightInfo::delete_schema(latest, issuers)).into())
+		}
+
+		/// Deprecate a superseded version of a schema
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::deprecate_schema_version())]
+		pub fn deprecate_schema_version(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::SchemaId,
+			version: u32,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let schema_data = SchemaStore::<T>::get(&key).ok_or(Error::<T>::UnknownSchema)?;
+			// ensure schema creator is the one deprecating the version
+			let schema_creator = Self::split_publickey_from_did(&schema_data.1.creator)?;
+			ensure!(schema_creator == origin, Error::<T>::NotSchemaOwner);
+			ensure!(
+				version != SchemaRevisions::<T>::get(&key),
+				Error::<T>::CannotDeprecateLatestVersion
+			);
+			ensure!(
+				SchemaVersions::<T>::contains_key(&key, version),
+				Error::<T>::UnknownSchemaVersion
+			);
+			DeprecatedVersions::<T>::insert(&key, version, ());
+			Self::deposit_event(Event::SchemaVersionDeprecated(key, version));
+			Ok(())
+		}
+
+		/// Deprecate a schema, keeping the credentials already issued against it
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::deprecate_schema())]
+		pub fn deprecate_schema(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::SchemaId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let schema_data = SchemaStore::<T>::get(&key).ok_or(Error::<T>::UnknownSchema)?;
+			// ensure schema creator is the one deprecating the schema
+			let schema_creator = Self::split_publickey_from_did(&schema_data.1.creator)?;
+			ensure!(schema_creator == origin, Error::<T>::NotSchemaOwner);
+			ensure!(!DeprecatedSchemas::<T>::contains_key(&key), Error::<T>::SchemaDeprecated);
+			DeprecatedSchemas::<T>::insert(&key, ());
+			Self::deposit_event(Event::SchemaDeprecated(key));
+			Ok(())
+		}
+
+		/// Authorize an issuer DID to issue credentials against a non-public schema, growing the
+		/// deposit held for the schema by the deposit for the issuer DID
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::add_issuer())]
+		pub fn add_issuer(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::SchemaId,
+			issuer: BoundedVec<u8, T::MaxDidLength>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_schema_creator(&origin, &key)?;
+			ensure!(
+				!AuthorizedIssuers::<T>::contains_key(&key, &issuer),
+				Error::<T>::IssuerAlreadyAuthorized
+			);
+			let count = AuthorizedIssuerCount::<T>::get(&key);
+			ensure!(count < T::MaxAuthorizedIssuers::get(), Error::<T>::TooManyIssuers);
+			let held = Self::held_deposit(&key);
+			Self::hold_deposit(&key, &origin, held.saturating_add(Self::deposit_for(&issuer)))?;
+			AuthorizedIssuerCount::<T>::insert(&key, count + 1);
+			AuthorizedIssuers::<T>::insert(&key, &issuer, ());
+			Self::deposit_event(Event::IssuerAdded(key, issuer.into_inner()));
+			Ok(())
+		}
+
+		/// Withdraw the authorization of an issuer DID for a non-public schema
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::remove_issuer())]
+		pub fn remove_issuer(
+			origin: OriginFor<T>,
+			#[pallet::compact] key: T::SchemaId,
+			issuer: BoundedVec<u8, T::MaxDidLength>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_schema_creator(&origin, &key)?;
+			ensure!(
+				AuthorizedIssuers::<T>::contains_key(&key, &issuer),
+				Error::<T>::IssuerNotAuthorized
+			);
+			let held = Self::held_deposit(&key);
+			Self::hold_deposit(&key, &origin, held.saturating_sub(Self::deposit_for(&issuer)))?;
+			AuthorizedIssuerCount::<T>::mutate(&key, |count| *count = count.saturating_sub(1));
+			AuthorizedIssuers::<T>::remove(&key, &issuer);
+			Self::deposit_event(Event::IssuerRemoved(key, issuer.into_inner()));
+			Ok(())
 		}
 	}
 
-	impl<T: Config> Schema<T::AccountId, T::Moment, T::Signature, T::SchemaId> for Pallet<T> {
+	impl<T: Config> Schema<T::AccountId, T::Moment, T::Signature, T::SchemaId, SchemaLimitsOf<T>>
+		for Pallet<T>
+	{
 		// Function to create a new schema
 		fn create_verifiable_schema(
 			id: &T::SchemaId,
-			name: &Vec<u8>,
-			creator: &Vec<u8>,
+			name: &BoundedVec<u8, T::MaxNameLength>,
+			creator: &BoundedVec<u8, T::MaxDidLength>,
 			public: &bool,
 			creation_date: T::Moment,
 			expiration_date: Option<T::Moment>,
-			mandatory_fields: &Vec<Attribute>,
-			issuer_claims: &Vec<Claim>,
-			subject_claims: &Vec<Claim>,
-			credential_claims: &Vec<Claim>,
-			metadata: &Vec<u8>,
+			mandatory_fields: &BoundedAttributes<SchemaLimitsOf<T>>,
+			issuer_claims: &BoundedClaims<SchemaLimitsOf<T>>,
+			subject_claims: &BoundedClaims<SchemaLimitsOf<T>>,
+			credential_claims: &BoundedClaims<SchemaLimitsOf<T>>,
+			metadata: &BoundedVec<u8, T::MaxMetadataLength>,
 			signature: &T::Signature,
 			nonce: &u64,
 		) -> DispatchResult {
-			let verifiable_credential_schema = VerifiableCredentialSchema {
+			let verifiable_credential_schema: SchemaOf<T> = VerifiableCredentialSchema {
 				name: name.clone(),
 				creator: creator.clone(),
 				public: public.clone(),
@@ -248,30 +553,80 @@ pub mod pallet {
 
 			let signer = Self::split_publickey_from_did(&verifiable_credential_schema.creator)?;
 			Self::is_valid_signer(vc_bytes, signature, &signer)?;
+			let deposit = Self::deposit_for(&(signature, &verifiable_credential_schema));
+			Self::hold_deposit(id, &signer, deposit)?;
 			// Save the Schema data in storage
 			SchemaStore::<T>::insert(id, (&signature, &verifiable_credential_schema));
+			Nonce::<T>::insert(creator, nonce.saturating_add(1));
 			// Emit an event to indicate that the Schema was created
-			Self::deposit_event(Event::SchemaCreated(
-				id.clone(),
-				verifiable_credential_schema.encode(),
-			));
+			Self::deposit_event(Event::SchemaCreated {
+				schema_id: id.clone(),
+				creator: signer,
+				creator_did: creator.to_vec(),
+				content_hash: sp_io::hashing::blake2_256(vc_bytes),
+				moment: T::Timestamp::now(),
+			});
 			Ok(())
 		}
 
 		// update a schema
 		fn update_verifiable_schema(
 			old_schema_key: &T::SchemaId,
-			new_data: &(T::Signature, VerifiableCredentialSchema<T::Moment>),
+			new_data: &(T::Signature, SchemaOf<T>),
 		) -> DispatchResult {
-			// Update the schema data
+			let signer = Self::split_publickey_from_did(&new_data.1.creator)?;
+			Self::is_valid_signer(&new_data.1.encode(), &new_data.0, &signer)?;
+			// Every published version stays stored, so the deposit grows with each of them
+			let held = Self::held_deposit(old_schema_key);
+			Self::hold_deposit(
+				old_schema_key,
+				&signer,
+				held.saturating_add(Self::deposit_for(new_data)),
+			)?;
+			let version = SchemaRevisions::<T>::mutate(old_schema_key, |version| {
+				*version = version.saturating_add(1);
+				*version
+			});
+			// Archive the superseded version before publishing the new one
+			if let Some(old_schema) = SchemaStore::<T>::get(old_schema_key) {
+				SchemaHistory::<T>::insert(
+					old_schema_key,
+					version,
+					UpdateRecord {
+						previous_hash: sp_io::hashing::blake2_256(&old_schema.1.encode()),
+						block: frame_system::Pallet::<T>::block_number(),
+						moment: T::Timestamp::now(),
+					},
+				);
+				SchemaVersions::<T>::insert(old_schema_key, version - 1, old_schema);
+			}
+			// Publish the new version
 			SchemaStore::<T>::insert(old_schema_key, new_data);
-			Self::deposit_event(Event::SchemaUpdated(old_schema_key.clone(), new_data.encode()));
+			Nonce::<T>::insert(&new_data.1.creator, new_data.1.nonce.saturating_add(1));
+			Self::deposit_event(Event::SchemaUpdated {
+				schema_id: old_schema_key.clone(),
+				version,
+				creator: signer,
+				creator_did: new_data.1.creator.to_vec(),
+				content_hash: sp_io::hashing::blake2_256(&new_data.1.encode()),
+				moment: T::Timestamp::now(),
+			});
 			Ok(())
 		}
 
 		// delete schema
 		fn delete_verifiable_schema(key: &T::SchemaId) -> DispatchResult {
 			<SchemaStore<T>>::remove(key);
+			if let Some((depositor, deposit)) = <SchemaDeposits<T>>::take(key) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			<SchemaRevisions<T>>::remove(key);
+			let _ = <SchemaHistory<T>>::clear_prefix(key, u32::MAX, None);
+			let _ = <SchemaVersions<T>>::clear_prefix(key, u32::MAX, None);
+			let _ = <DeprecatedVersions<T>>::clear_prefix(key, u32::MAX, None);
+			<DeprecatedSchemas<T>>::remove(key);
+			let _ = <AuthorizedIssuers<T>>::clear_prefix(key, u32::MAX, None);
+			<AuthorizedIssuerCount<T>>::remove(key);
 			Self::deposit_event(Event::SchemaDeleted(key.clone()));
 			Ok(())
 		}
@@ -282,29 +637,173 @@ pub mod pallet {
 		}
 
 		fn split_publickey_from_did(did: &Vec<u8>) -> Result<T::AccountId, DispatchError> {
-			let did_string = match sp_std::str::from_utf8(did) {
-				Ok(did_str) => did_str,
-				Err(e) => {
-					log::error!("{:?}", e);
-					return Err(<Error<T>>::InvalidDID.into());
+			T::DidResolver::resolve(did).map_err(|e| {
+				log::error!("{:?}", e);
+				match e {
+					DidError::Deactivated => Error::<T>::DidDeactivated,
+					DidError::NoAssertionKey => Error::<T>::NoAssertionKey,
+					DidError::InvalidAccount(_) => Error::<T>::InvalidDidAccount,
+					_ => Error::<T>::InvalidDID,
+				}
+				.into()
+			})
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Update history of a schema, oldest update first
+		pub fn schema_history(
+			id: &T::SchemaId,
+		) -> Vec<(u32, UpdateRecord<T::BlockNumber, T::Moment>)> {
+			let mut history: Vec<_> = SchemaHistory::<T>::iter_prefix(id).collect();
+			history.sort_by_key(|(revision, _)| *revision);
+			history
+		}
+
+		/// A published version of a schema, the latest one included
+		pub fn schema_version(
+			id: &T::SchemaId,
+			version: u32,
+		) -> Option<(T::Signature, SchemaOf<T>)> {
+			if version == SchemaRevisions::<T>::get(id) {
+				SchemaStore::<T>::get(id)
+			} else {
+				SchemaVersions::<T>::get(id, version)
+			}
+		}
+
+		// Take the next id from the counter, counter ids start at `FIRST_ASSIGNED_ID` so callers
+		// cannot take them ahead of it
+		fn next_schema_id() -> Result<T::SchemaId, DispatchError> {
+			let next = NextSchemaId::<T>::get();
+			let id = FIRST_ASSIGNED_ID.checked_add(next).ok_or(Error::<T>::NoFreeSchemaId)?;
+			NextSchemaId::<T>::put(next.saturating_add(1));
+			Ok(T::SchemaId::from(id))
+		}
+
+		// Ensure `who` created schema `key`
+		fn ensure_schema_creator(who: &T::AccountId, key: &T::SchemaId) -> DispatchResult {
+			let (_, schema) = SchemaStore::<T>::get(key).ok_or(Error::<T>::UnknownSchema)?;
+			let schema_creator = Self::split_publickey_from_did(&schema.creator)?;
+			ensure!(schema_creator == *who, Error::<T>::NotSchemaOwner);
+			Ok(())
+		}
+
+		// Ensure `nonce` has not been used by `creator` yet
+		fn ensure_fresh_nonce(
+			creator: &BoundedVec<u8, T::MaxDidLength>,
+			nonce: u64,
+		) -> DispatchResult {
+			ensure!(nonce >= Nonce::<T>::get(creator), Error::<T>::StaleNonce);
+			Ok(())
+		}
+
+		// Deposit required to store `data`
+		fn deposit_for<V: Encode>(data: &V) -> BalanceOf<T> {
+			let bytes: BalanceOf<T> = (data.encoded_size() as u32).into();
+			T::SchemaDepositBase::get()
+				.saturating_add(T::SchemaDepositPerByte::get().saturating_mul(bytes))
+		}
+
+		// Ensure the schema dates are consistent with on-chain time
+		fn ensure_valid_dates(
+			creation_date: T::Moment,
+			expiration_date: Option<T::Moment>,
+		) -> DispatchResult {
+			let now = T::Timestamp::now();
+			ensure!(creation_date <= now, Error::<T>::CreationDateInFuture);
+			if let Some(expiration_date) = expiration_date {
+				ensure!(expiration_date >= now, Error::<T>::ExpirationDateInPast);
+			}
+			Ok(())
+		}
+
+		// Deposit currently held for schema `id`
+		fn held_deposit(id: &T::SchemaId) -> BalanceOf<T> {
+			SchemaDeposits::<T>::get(id).map(|(_, held)| held).unwrap_or_default()
+		}
+
+		// Hold `deposit` from `who` for schema `id`, adjusting or moving any deposit already held
+		fn hold_deposit(
+			id: &T::SchemaId,
+			who: &T::AccountId,
+			deposit: BalanceOf<T>,
+		) -> DispatchResult {
+			match SchemaDeposits::<T>::get(id) {
+				Some((depositor, held)) if depositor == *who =>
+					if deposit > held {
+						T::Currency::reserve(who, deposit - held)?;
+					} else {
+						T::Currency::unreserve(who, held - deposit);
+					},
+				previous => {
+					T::Currency::reserve(who, deposit)?;
+					if let Some((depositor, held)) = previous {
+						T::Currency::unreserve(&depositor, held);
+					}
 				},
-			};
-			let did_vec: Vec<&str> = did_string.split(":").collect();
-			let public_key_str = did_vec[2].trim();
-			match node_primitives::convert2accountid::convert_string_to_accountid(public_key_str) {
-				Ok(account_id) => Ok(account_id),
-				Err(e) => Err(e),
 			}
+			SchemaDeposits::<T>::insert(id, (who, deposit));
+			Ok(())
 		}
 	}
 
 	impl<T: Config> SchemaInterface for Pallet<T> {
 		type SchemaId = T::SchemaId;
+		type Moment = T::Moment;
+		type Limits = SchemaLimitsOf<T>;
 		fn check_schema_id_exists(schema: Self::SchemaId) -> DispatchResult {
 			ensure!(<SchemaStore<T>>::contains_key(&schema), Error::<T>::SchemaIdDoesNotExist);
 			Ok(())
 		}
 
+		fn check_schema_version(schema: Self::SchemaId, version: u32) -> DispatchResult {
+			ensure!(!<DeprecatedSchemas<T>>::contains_key(&schema), Error::<T>::SchemaDeprecated);
+			let (_, schema_data) =
+				Self::schema_version(&schema, version).ok_or(Error::<T>::UnknownSchemaVersion)?;
+			ensure!(
+				!<DeprecatedVersions<T>>::contains_key(&schema, version),
+				Error::<T>::SchemaVersionDeprecated
+			);
+			if let Some(expiration_date) = schema_data.expiration_date {
+				ensure!(expiration_date >= T::Timestamp::now(), Error::<T>::SchemaExpired);
+			}
+			Ok(())
+		}
+
+		fn get_schema(schema: Self::SchemaId, version: u32) -> Option<SchemaOf<T>> {
+			Self::schema_version(&schema, version).map(|(_, schema)| schema)
+		}
+
+		fn latest_version(schema: Self::SchemaId) -> u32 {
+			<SchemaRevisions<T>>::get(&schema)
+		}
+
+		fn check_issuer_authorized(schema: Self::SchemaId, issuer: &[u8]) -> DispatchResult {
+			let (_, schema_data) =
+				<SchemaStore<T>>::get(&schema).ok_or(Error::<T>::SchemaIdDoesNotExist)?;
+			if schema_data.public || schema_data.creator[..] == issuer[..] {
+				return Ok(())
+			}
+			let authorized = BoundedVec::<u8, T::MaxDidLength>::try_from(issuer.to_vec())
+				.map_or(false, |issuer| <AuthorizedIssuers<T>>::contains_key(&schema, issuer));
+			ensure!(authorized, Error::<T>::IssuerNotAuthorized);
+			Ok(())
+		}
+
+		fn increment_references(schema: Self::SchemaId) {
+			<SchemaReferences<T>>::mutate(&schema, |references| {
+				*references = references.saturating_add(1)
+			});
+		}
+
+		fn decrement_references(schema: Self::SchemaId) {
+			<SchemaReferences<T>>::mutate_exists(&schema, |references| {
+				let remaining = references.unwrap_or_default().saturating_sub(1);
+				*references = (remaining > 0).then_some(remaining);
+			});
+		}
+
 		fn to_schema_id(schema_id: &u32) -> T::SchemaId {
 			let returned_schema_id: T::SchemaId = T::SchemaId::from(*schema_id);
 			returned_schema_id
@@ -0,0 +1,193 @@
+//! Storage migrations of the schemas pallet.
+
+/// Moves schemas from unbounded vectors to the bounded types of storage version 1.
+pub mod v1 {
+	use crate::{
+		types::*, Config, Pallet, SchemaDeposits, SchemaHistory, SchemaOf, SchemaRevisions,
+		SchemaStore,
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		pallet_prelude::*,
+		storage_alias,
+		traits::{OnRuntimeUpgrade, ReservableCurrency},
+	};
+	use scale_info::prelude::vec::Vec;
+
+	/// Schema layout before storage version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OldVerifiableCredentialSchema<Moment> {
+		pub name: Vec<u8>,
+		pub creator: Vec<u8>,
+		pub public: bool,
+		pub creation_date: Moment,
+		pub expiration_date: Option<Moment>,
+		pub mandatory_fields: Vec<OldAttribute>,
+		pub issuer_claims: Vec<OldClaim>,
+		pub subject_claims: Vec<OldClaim>,
+		pub credential_claims: Vec<OldClaim>,
+		pub metadata: Vec<u8>,
+		pub nonce: u64,
+	}
+
+	/// Claim layout before storage version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OldClaim {
+		pub schemaid: Option<u32>,
+		pub property: Vec<u8>,
+		pub value: Vec<u8>,
+		pub claim_type: ClaimType,
+		pub issuance_requirement: Option<Vec<OldIssuanceRequirement>>,
+	}
+
+	/// Attribute layout before storage version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OldAttribute {
+		pub name: Vec<u8>,
+		pub attribute_type: AttributeType,
+	}
+
+	/// Issuance requirement layout before storage version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OldIssuanceRequirement {
+		pub name: Vec<u8>,
+		pub insuance_type: IssuanceType,
+	}
+
+	/// Bound every item of `items`, or `None` if any item or the list itself is too long.
+	pub fn bound_all<I, O, S: Get<u32>>(
+		items: Vec<I>,
+		bound: impl Fn(I) -> Option<O>,
+	) -> Option<BoundedVec<O, S>> {
+		items.into_iter().map(bound).collect::<Option<Vec<_>>>()?.try_into().ok()
+	}
+
+	impl OldClaim {
+		pub fn bounded<L: SchemaLimits>(self) -> Option<Claim<L>> {
+			let issuance_requirement = match self.issuance_requirement {
+				Some(requirements) => Some(bound_all(requirements, |requirement| {
+					Some(IssuanceRequirement {
+						name: requirement.name.try_into().ok()?,
+						insuance_type: requirement.insuance_type,
+					})
+				})?),
+				None => None,
+			};
+			Some(Claim {
+				schemaid: self.schemaid,
+				property: self.property.try_into().ok()?,
+				value: self.value.try_into().ok()?,
+				claim_type: self.claim_type,
+				issuance_requirement,
+			})
+		}
+	}
+
+	impl<Moment> OldVerifiableCredentialSchema<Moment> {
+		pub fn bounded<L: SchemaLimits>(self) -> Option<VerifiableCredentialSchema<Moment, L>> {
+			Some(VerifiableCredentialSchema {
+				name: self.name.try_into().ok()?,
+				creator: self.creator.try_into().ok()?,
+				public: self.public,
+				creation_date: self.creation_date,
+				expiration_date: self.expiration_date,
+				mandatory_fields: bound_all(self.mandatory_fields, |attribute| {
+					Some(Attribute {
+						name: attribute.name.try_into().ok()?,
+						attribute_type: attribute.attribute_type,
+					})
+				})?,
+				issuer_claims: bound_all(self.issuer_claims, OldClaim::bounded)?,
+				subject_claims: bound_all(self.subject_claims, OldClaim::bounded)?,
+				credential_claims: bound_all(self.credential_claims, OldClaim::bounded)?,
+				metadata: self.metadata.try_into().ok()?,
+				nonce: self.nonce,
+			})
+		}
+	}
+
+	type OldSchema<T> = OldVerifiableCredentialSchema<<T as Config>::Moment>;
+
+	// Keyed by the creator DID, left unbounded here so over-long keys decode
+	#[storage_alias]
+	type Nonce<T: Config> = StorageMap<Pallet<T>, Blake2_128Concat, Vec<u8>, u64, ValueQuery>;
+
+	/// The nonce shared by every creator before nonces were kept per DID.
+	pub mod shared {
+		use crate::{Config, Pallet};
+		use frame_support::{pallet_prelude::*, storage_alias};
+
+		#[storage_alias]
+		pub type Nonce<T: Config> = StorageValue<Pallet<T>, u64, ValueQuery>;
+	}
+
+	/// Translates stored schemas to their bounded form. Schemas exceeding the configured bounds
+	/// are removed and their deposits released, as are nonces of over-long creator DIDs. The
+	/// shared nonce the DID keyed ones replaced is removed.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				log::info!("schemas storage is already at version 1, skipping migration");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			let mut dropped = Vec::new();
+			SchemaStore::<T>::translate::<(T::Signature, OldSchema<T>), _>(
+				|id, (signature, schema)| {
+					reads += 1;
+					writes += 1;
+					let bounded: Option<SchemaOf<T>> = schema.bounded();
+					if bounded.is_none() {
+						log::warn!("removing schema {:?} exceeding the storage bounds", id);
+						dropped.push(id);
+					}
+					bounded.map(|schema| (signature, schema))
+				},
+			);
+			for id in dropped {
+				if let Some((depositor, deposit)) = SchemaDeposits::<T>::take(&id) {
+					T::Currency::unreserve(&depositor, deposit);
+				}
+				SchemaRevisions::<T>::remove(&id);
+				let _ = SchemaHistory::<T>::clear_prefix(&id, u32::MAX, None);
+				reads += 2;
+				writes += 5;
+			}
+
+			shared::Nonce::<T>::kill();
+			writes += 1;
+			let stale: Vec<Vec<u8>> = Nonce::<T>::iter_keys()
+				.filter(|creator| creator.len() > T::MaxDidLength::get() as usize)
+				.collect();
+			reads += stale.len() as u64;
+			writes += stale.len() as u64;
+			for creator in stale {
+				Nonce::<T>::remove(creator);
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok((SchemaStore::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let before = u32::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+			ensure!(StorageVersion::get::<Pallet<T>>() == 1, "schemas storage version not set");
+			ensure!(
+				SchemaStore::<T>::iter_keys().count() as u32 <= before,
+				"schemas added by the migration"
+			);
+			ensure!(!shared::Nonce::<T>::exists(), "shared nonce left in storage");
+			Ok(())
+		}
+	}
+}
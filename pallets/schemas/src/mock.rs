@@ -1,7 +1,9 @@
 use crate as pallet_schema;
-use frame_support::traits::OnTimestampSet;
-use frame_support::traits::{ConstU16, ConstU64};
+use frame_support::traits::{Get, OnTimestampSet};
+use frame_support::BoundedVec;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64};
 use frame_system as system;
+use node_primitives::did::{DidError, DidResolver, ImplicitResolver};
 use sp_core::sr25519;
 use sp_core::Pair;
 use sp_core::H256;
@@ -15,6 +17,8 @@ type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 type Moment = u64;
 
+pub const INITIAL_BALANCE: u64 = 1_000_000;
+
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
 	pub enum Test where
@@ -23,6 +27,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system,
+		Balances: pallet_balances,
 		SchemaRegistry: pallet_schema,
 		Timestamp: pallet_timestamp,
 	}
@@ -46,7 +51,7 @@ impl system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -66,6 +71,46 @@ impl OnTimestampSet<Moment> for MockOnTimestampSet {
 	}
 }
 
+/// DID the mock resolver reports as deactivated.
+pub const DEACTIVATED_DID: &[u8] = b"did:seneca:deactivated";
+/// DID the mock resolver reports as lacking an assertion key.
+pub const KEYLESS_DID: &[u8] = b"did:seneca:keyless";
+
+/// Implicit resolution, except for the deactivated and keyless DIDs above.
+pub struct MockDidResolver;
+impl DidResolver<sr25519::Public> for MockDidResolver {
+	fn resolve(did: &[u8]) -> Result<sr25519::Public, DidError> {
+		match did {
+			DEACTIVATED_DID => Err(DidError::Deactivated),
+			KEYLESS_DID => Err(DidError::NoAssertionKey),
+			_ => <ImplicitResolver<ConstU16<42>> as DidResolver<_>>::resolve(did),
+		}
+	}
+
+	fn authentication_keys(did: &[u8]) -> Result<Vec<sr25519::Public>, DidError> {
+		match did {
+			DEACTIVATED_DID => Err(DidError::Deactivated),
+			_ => <ImplicitResolver<ConstU16<42>> as DidResolver<_>>::authentication_keys(did),
+		}
+	}
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type HoldIdentifier = ();
+	type FreezeIdentifier = ();
+	type MaxHolds = ();
+	type MaxFreezes = ();
+	type WeightInfo = ();
+}
+
 impl pallet_timestamp::Config for Test {
 	type Moment = Moment;
 	type OnTimestampSet = MockOnTimestampSet;
@@ -80,12 +125,36 @@ impl pallet_schema::Config for Test {
 	type Signature = sp_core::sr25519::Signature;
 	type Moment = Moment;
 	type Timestamp = Timestamp;
+	type DidResolver = MockDidResolver;
+	type Currency = Balances;
+	type SchemaDepositBase = ConstU64<100>;
+	type SchemaDepositPerByte = ConstU64<1>;
+	type MaxNameLength = ConstU32<64>;
+	type MaxDidLength = ConstU32<128>;
+	type MaxClaims = ConstU32<16>;
+	type MaxValueLength = ConstU32<256>;
+	type MaxMetadataLength = ConstU32<512>;
+	type MaxAuthorizedIssuers = ConstU32<4>;
 	type SchemaId = u32;
 }
 // Build genesis storage according to the mock runtime.
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: ["Alice", "Bob", "Charlie"]
+			.iter()
+			.map(|name| (account_pair(name).public(), INITIAL_BALANCE))
+			.collect(),
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
+}
+
+/// Bounded copy of `data`, for building schema fixtures.
+pub fn bounded<S: Get<u32>>(data: &[u8]) -> BoundedVec<u8, S> {
+	data.to_vec().try_into().expect("fixtures fit the mock bounds; qed")
 }
 
 pub fn account_pair(s: &str) -> sr25519::Pair {
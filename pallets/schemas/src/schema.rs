@@ -1,27 +1,28 @@
 use crate::types::*;
 use frame_support::dispatch::DispatchResult;
 use frame_support::pallet_prelude::DispatchError;
+use frame_support::BoundedVec;
 use scale_info::prelude::vec::Vec;
 
-pub trait Schema<AccountId, Moment, Signature, SchemaId> {
+pub trait Schema<AccountId, Moment, Signature, SchemaId, Limits: SchemaLimits> {
 	fn create_verifiable_schema(
 		id: &SchemaId,
-		name: &Vec<u8>,
-		creator: &Vec<u8>,
+		name: &BoundedName<Limits>,
+		creator: &BoundedDid<Limits>,
 		public: &bool,
 		creation_date: Moment,
 		expiration_date: Option<Moment>,
-		mandatory_fields: &Vec<Attribute>,
-		issuer_claims: &Vec<Claim>,
-		subject_claims: &Vec<Claim>,
-		credential_claims: &Vec<Claim>,
-		metadata: &Vec<u8>,
+		mandatory_fields: &BoundedAttributes<Limits>,
+		issuer_claims: &BoundedClaims<Limits>,
+		subject_claims: &BoundedClaims<Limits>,
+		credential_claims: &BoundedClaims<Limits>,
+		metadata: &BoundedVec<u8, Limits::MaxMetadataLength>,
 		signature: &Signature,
 		nonce: &u64,
 	) -> DispatchResult;
 	fn update_verifiable_schema(
 		old_schema_key: &SchemaId,
-		new_data: &(Signature, VerifiableCredentialSchema<Moment>),
+		new_data: &(Signature, VerifiableCredentialSchema<Moment, Limits>),
 	) -> DispatchResult;
 	fn delete_verifiable_schema(key: &SchemaId) -> DispatchResult;
 	fn is_valid_signer(data: &[u8], sig: &Signature, from: &AccountId) -> DispatchResult;
@@ -30,6 +31,21 @@ pub trait Schema<AccountId, Moment, Signature, SchemaId> {
 
 pub trait SchemaInterface {
 	type SchemaId;
+	type Moment;
+	type Limits: SchemaLimits;
 	fn check_schema_id_exists(schema: Self::SchemaId) -> DispatchResult;
+	fn check_schema_version(schema: Self::SchemaId, version: u32) -> DispatchResult;
+	fn get_schema(
+		schema: Self::SchemaId,
+		version: u32,
+	) -> Option<VerifiableCredentialSchema<Self::Moment, Self::Limits>>;
+	fn latest_version(schema: Self::SchemaId) -> u32;
+	/// Ensure `issuer` may issue credentials against `schema`: the schema is public, `issuer`
+	/// created it or the creator authorized `issuer`.
+	fn check_issuer_authorized(schema: Self::SchemaId, issuer: &[u8]) -> DispatchResult;
+	/// Count one more stored credential issued against `schema`.
+	fn increment_references(schema: Self::SchemaId);
+	/// Count one stored credential issued against `schema` less.
+	fn decrement_references(schema: Self::SchemaId);
 	fn to_schema_id(schema: &u32) -> Self::SchemaId;
 }
@@ -1,7 +1,15 @@
 use crate::mock::*;
 use crate::types::*;
 use codec::Encode;
-use frame_support::assert_ok;
+use crate::{
+	migrations::v1, schema::SchemaInterface, AuthorizedIssuerCount, DeprecatedVersions, Error,
+	NextSchemaId, SchemaDeposits, SchemaOf, SchemaReferences, SchemaStore, FIRST_ASSIGNED_ID,
+};
+use frame_support::{
+	assert_noop, assert_ok, bounded_vec,
+	storage::unhashed,
+	traits::{OnRuntimeUpgrade, ReservableCurrency, StorageVersion},
+};
 use frame_system::RawOrigin;
 use sp_core::Pair;
 use sp_runtime::traits::IdentifyAccount;
@@ -14,38 +22,38 @@ fn it_works_for_create_schema() {
 		let signer = account_pair.public();
 		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
 		// Encode and sign the schema struct.
-		let schema = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.clone().into(),
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
 			public: false,
 			creation_date: Timestamp::now(),
 			expiration_date: Some(1702379816u64),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: bounded(b"metadata"),
 			nonce: 2u64,
 		};
 		let schema_id = 0u32;
@@ -56,7 +64,7 @@ fn it_works_for_create_schema() {
 		// Dispatch a signed create schema extrinsic.
 		assert_ok!(SchemaRegistry::create_schema(
 			RawOrigin::Signed(signer).into(),
-			schema_id,
+			Some(schema_id),
 			schema.name,
 			schema.creator,
 			false,
@@ -66,7 +74,7 @@ fn it_works_for_create_schema() {
 			schema.issuer_claims,
 			schema.subject_claims,
 			schema.credential_claims,
-			b"metadata".to_vec(),
+			bounded(b"metadata"),
 			data_sig,
 			schema.nonce
 		));
@@ -80,73 +88,73 @@ fn it_works_for_update_schema() {
 		let signer = account_pair.public();
 		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
 
-		let schema = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.clone().into(),
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
 			public: false,
 			creation_date: Timestamp::now(),
 			expiration_date: Some(1702379816u64),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: bounded(b"metadata"),
 			nonce: 2u64,
 		};
-		let updated_schema = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.clone().into(),
+		let updated_schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
 			public: false,
 			creation_date: Timestamp::now(),
 			expiration_date: Some(1702379816u64),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata2".to_vec(),
-			nonce: 2u64,
+			metadata: bounded(b"metadata2"),
+			nonce: 3u64,
 		};
 		let data_sig = account_pair.sign(&schema.encode());
 		let updated_sig = account_pair.sign(&updated_schema.encode());
@@ -154,7 +162,7 @@ fn it_works_for_update_schema() {
 		// Dispatch a signed extrinsic.
 		assert_ok!(SchemaRegistry::create_schema(
 			RawOrigin::Signed(signer).into(),
-			schema_id,
+			Some(schema_id),
 			schema.name,
 			schema.creator,
 			false,
@@ -164,7 +172,7 @@ fn it_works_for_update_schema() {
 			schema.issuer_claims,
 			schema.subject_claims,
 			schema.credential_claims,
-			b"metadata".to_vec(),
+			bounded(b"metadata"),
 			data_sig,
 			schema.nonce
 		));
@@ -187,38 +195,38 @@ fn it_works_for_delete_schema() {
 		let account_pair = account_pair("Alice");
 		let signer = account_pair.public();
 		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
-		let schema = VerifiableCredentialSchema {
-			name: b"Alice Data".to_vec(),
-			creator: account_id.clone().into(),
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
 			public: false,
 			creation_date: Timestamp::now(),
 			expiration_date: Some(1702379816u64),
-			mandatory_fields: vec![Attribute {
-				name: b"name".to_vec(),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
 				attribute_type: AttributeType::Hex,
 			}],
-			issuer_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			issuer_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::IssuerClaim,
 				issuance_requirement: None,
 			}],
-			subject_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			subject_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::SubjectClaim,
 				issuance_requirement: None,
 			}],
-			credential_claims: vec![Claim {
-				property: b"property".to_vec(),
-				value: b"value".to_vec(),
+			credential_claims: bounded_vec![Claim {
+				property: bounded(b"property"),
+				value: bounded(b"value"),
 				schemaid: None,
 				claim_type: ClaimType::CredentialClaim,
 				issuance_requirement: None,
 			}],
-			metadata: b"metadata".to_vec(),
+			metadata: bounded(b"metadata"),
 			nonce: 2u64,
 		};
 		let data_sig = account_pair.sign(&schema.encode());
@@ -226,7 +234,7 @@ fn it_works_for_delete_schema() {
 		// Dispatch a signed create schema extrinsic.
 		assert_ok!(SchemaRegistry::create_schema(
 			RawOrigin::Signed(signer).into(),
-			schema_id,
+			Some(schema_id),
 			schema.name,
 			schema.creator,
 			false,
@@ -236,16 +244,961 @@ fn it_works_for_delete_schema() {
 			schema.issuer_claims,
 			schema.subject_claims,
 			schema.credential_claims,
-			b"metadata".to_vec(),
+			bounded(b"metadata"),
 			data_sig,
 			schema.nonce
 		));
 		// Dispatch a signed extrinsic.
 		assert_ok!(SchemaRegistry::delete_schema(
 			RawOrigin::Signed(signer).into(),
-			schema_id.clone()
+			schema_id.clone(),
+			0
 		));
 		// Read pallet storage and assert an expected result.
 		assert_eq!(SchemaRegistry::schema_registry(schema_id.clone()), None);
 	});
 }
+
+#[test]
+fn referenced_schemas_are_deprecated_instead_of_deleted() {
+	new_test_ext().execute_with(|| {
+		let bob = account_pair("Bob");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name,
+			schema.creator,
+			false,
+			schema.mandatory_fields,
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims,
+			schema.subject_claims,
+			schema.credential_claims,
+			schema.metadata,
+			data_sig,
+			schema.nonce
+		));
+
+		// two credentials issued against the schema
+		SchemaRegistry::increment_references(0u32);
+		SchemaRegistry::increment_references(0u32);
+		assert_noop!(
+			SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), 0u32, 0),
+			Error::<Test>::SchemaInUse
+		);
+
+		// deprecation stops issuance but keeps the schema
+		assert_noop!(
+			SchemaRegistry::deprecate_schema(RawOrigin::Signed(bob.public()).into(), 0u32),
+			Error::<Test>::NotSchemaOwner
+		);
+		assert_ok!(SchemaRegistry::deprecate_schema(RawOrigin::Signed(signer).into(), 0u32));
+		assert_noop!(
+			SchemaRegistry::deprecate_schema(RawOrigin::Signed(signer).into(), 0u32),
+			Error::<Test>::SchemaDeprecated
+		);
+		assert_noop!(
+			SchemaRegistry::check_schema_version(0u32, 0),
+			Error::<Test>::SchemaDeprecated
+		);
+		assert!(SchemaRegistry::get_schema(0u32, 0).is_some());
+
+		// once the credentials are gone the schema can be deleted
+		SchemaRegistry::decrement_references(0u32);
+		assert_eq!(SchemaReferences::<Test>::get(0u32), 1);
+		SchemaRegistry::decrement_references(0u32);
+		assert!(!SchemaReferences::<Test>::contains_key(0u32));
+		assert_ok!(SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), 0u32, 0));
+	});
+}
+
+#[test]
+fn issuer_allow_list_is_managed_by_the_creator() {
+	new_test_ext().execute_with(|| {
+		let bob = account_pair("Bob");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let issuer = format!("did:seneca:{}", bob.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name.clone(),
+			schema.creator.clone(),
+			false,
+			schema.mandatory_fields.clone(),
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims.clone(),
+			schema.subject_claims.clone(),
+			schema.credential_claims.clone(),
+			schema.metadata.clone(),
+			data_sig,
+			schema.nonce
+		));
+
+		// the creator may always issue, others only once authorized
+		assert_ok!(SchemaRegistry::check_issuer_authorized(0u32, account_id.as_bytes()));
+		assert_noop!(
+			SchemaRegistry::check_issuer_authorized(0u32, issuer.as_bytes()),
+			Error::<Test>::IssuerNotAuthorized
+		);
+		assert_noop!(
+			SchemaRegistry::add_issuer(
+				RawOrigin::Signed(bob.public()).into(),
+				0u32,
+				bounded(issuer.as_bytes())
+			),
+			Error::<Test>::NotSchemaOwner
+		);
+		assert_ok!(SchemaRegistry::add_issuer(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			bounded(issuer.as_bytes())
+		));
+		assert_noop!(
+			SchemaRegistry::add_issuer(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				bounded(issuer.as_bytes())
+			),
+			Error::<Test>::IssuerAlreadyAuthorized
+		);
+		assert_ok!(SchemaRegistry::check_issuer_authorized(0u32, issuer.as_bytes()));
+
+		assert_ok!(SchemaRegistry::remove_issuer(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			bounded(issuer.as_bytes())
+		));
+		assert_noop!(
+			SchemaRegistry::remove_issuer(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				bounded(issuer.as_bytes())
+			),
+			Error::<Test>::IssuerNotAuthorized
+		);
+		assert_noop!(
+			SchemaRegistry::check_issuer_authorized(0u32, issuer.as_bytes()),
+			Error::<Test>::IssuerNotAuthorized
+		);
+
+		// public schemas accept any issuer
+		let public = SchemaOf::<Test> { public: true, nonce: 3u64, ..schema };
+		assert_ok!(SchemaRegistry::update_schema(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			(account_pair.sign(&public.encode()), public)
+		));
+		assert_ok!(SchemaRegistry::check_issuer_authorized(0u32, issuer.as_bytes()));
+	});
+}
+
+#[test]
+fn authorized_issuers_are_bounded_and_hold_a_deposit() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name.clone(),
+			schema.creator.clone(),
+			false,
+			schema.mandatory_fields.clone(),
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims.clone(),
+			schema.subject_claims.clone(),
+			schema.credential_claims.clone(),
+			schema.metadata.clone(),
+			data_sig,
+			schema.nonce
+		));
+		let schema_deposit = Balances::reserved_balance(&signer);
+
+		// every authorized issuer adds its own deposit, up to MaxAuthorizedIssuers of them
+		let issuers: Vec<String> = (0..4).map(|i| format!("did:seneca:issuer{}", i)).collect();
+		let issuer_deposit = 100 + issuers[0].as_bytes().to_vec().encoded_size() as u64;
+		for issuer in &issuers {
+			assert_ok!(SchemaRegistry::add_issuer(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				bounded(issuer.as_bytes())
+			));
+		}
+		assert_eq!(AuthorizedIssuerCount::<Test>::get(0u32), 4);
+		assert_eq!(Balances::reserved_balance(&signer), schema_deposit + 4 * issuer_deposit);
+		assert_noop!(
+			SchemaRegistry::add_issuer(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				bounded(b"did:seneca:issuer4")
+			),
+			Error::<Test>::TooManyIssuers
+		);
+
+		// removing an issuer releases its deposit and frees a slot
+		assert_ok!(SchemaRegistry::remove_issuer(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			bounded(issuers[0].as_bytes())
+		));
+		assert_eq!(AuthorizedIssuerCount::<Test>::get(0u32), 3);
+		assert_eq!(Balances::reserved_balance(&signer), schema_deposit + 3 * issuer_deposit);
+		assert_ok!(SchemaRegistry::add_issuer(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			bounded(b"did:seneca:issuer4")
+		));
+
+		// deleting the schema releases everything
+		assert_ok!(SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), 0u32, 0));
+		assert!(!AuthorizedIssuerCount::<Test>::contains_key(0u32));
+		assert_eq!(Balances::reserved_balance(&signer), 0);
+		assert_eq!(Balances::free_balance(&signer), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn create_schema_assigns_free_ids() {
+	new_test_ext().execute_with(|| {
+		// events are only recorded from the first block on
+		System::set_block_number(1);
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema_with = |nonce: u64| SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce,
+		};
+		let create = |id: Option<u32>, nonce: u64| {
+			let schema = schema_with(nonce);
+			SchemaRegistry::create_schema(
+				RawOrigin::Signed(signer).into(),
+				id,
+				schema.name.clone(),
+				schema.creator.clone(),
+				false,
+				schema.mandatory_fields.clone(),
+				schema.creation_date,
+				schema.expiration_date,
+				schema.issuer_claims.clone(),
+				schema.subject_claims.clone(),
+				schema.credential_claims.clone(),
+				schema.metadata.clone(),
+				account_pair.sign(&schema.encode()),
+				schema.nonce,
+			)
+		};
+
+		// callers cannot claim the ids the counter assigns next
+		for id in FIRST_ASSIGNED_ID..FIRST_ASSIGNED_ID + 16 {
+			assert_noop!(create(Some(id), 0), Error::<Test>::SchemaIdReserved);
+		}
+		assert_ok!(create(Some(0u32), 0));
+		assert_ok!(create(Some(1u32), 1));
+		let created = |schema_id: u32, schema: &SchemaOf<Test>| {
+			crate::Event::SchemaCreated {
+				schema_id,
+				creator: signer,
+				creator_did: schema.creator.to_vec(),
+				content_hash: sp_io::hashing::blake2_256(&schema.encode()),
+				moment: Timestamp::now(),
+			}
+		};
+		assert_ok!(create(None, 2));
+		System::assert_last_event(created(FIRST_ASSIGNED_ID, &schema_with(2)).into());
+		assert_ok!(create(None, 3));
+		System::assert_last_event(created(FIRST_ASSIGNED_ID + 1, &schema_with(3)).into());
+		assert_eq!(
+			SchemaRegistry::schema_registry(FIRST_ASSIGNED_ID + 1).map(|(_, schema)| schema),
+			Some(schema_with(3))
+		);
+
+		// once the counter ran through its range an id has to be supplied
+		NextSchemaId::<Test>::put(u32::MAX - FIRST_ASSIGNED_ID);
+		assert_ok!(create(None, 4));
+		assert!(SchemaStore::<Test>::contains_key(u32::MAX));
+		assert_noop!(create(None, 5), Error::<Test>::NoFreeSchemaId);
+		assert_ok!(create(Some(2u32), 5));
+	});
+}
+
+#[test]
+fn create_schema_enforces_dates() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		Timestamp::set_timestamp(1_000);
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: Some(500u64),
+			mandatory_fields: bounded_vec![Attribute {
+				name: bounded(b"name"),
+				attribute_type: AttributeType::Hex,
+			}],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_noop!(
+			SchemaRegistry::create_schema(
+				RawOrigin::Signed(signer).into(),
+				Some(0u32),
+				schema.name.clone(),
+				schema.creator.clone(),
+				false,
+				schema.mandatory_fields.clone(),
+				schema.creation_date,
+				schema.expiration_date,
+				schema.issuer_claims.clone(),
+				schema.subject_claims.clone(),
+				schema.credential_claims.clone(),
+				schema.metadata.clone(),
+				data_sig.clone(),
+				schema.nonce
+			),
+			Error::<Test>::ExpirationDateInPast
+		);
+		assert_noop!(
+			SchemaRegistry::create_schema(
+				RawOrigin::Signed(signer).into(),
+				Some(0u32),
+				schema.name,
+				schema.creator,
+				false,
+				schema.mandatory_fields,
+				2_000u64,
+				None,
+				schema.issuer_claims,
+				schema.subject_claims,
+				schema.credential_claims,
+				schema.metadata,
+				data_sig,
+				schema.nonce
+			),
+			Error::<Test>::CreationDateInFuture
+		);
+	});
+}
+
+#[test]
+fn update_schema_enforces_dates() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		Timestamp::set_timestamp(1_000);
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name.clone(),
+			schema.creator.clone(),
+			false,
+			schema.mandatory_fields.clone(),
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims.clone(),
+			schema.subject_claims.clone(),
+			schema.credential_claims.clone(),
+			schema.metadata.clone(),
+			account_pair.sign(&schema.encode()),
+			schema.nonce
+		));
+		let update = |version: SchemaOf<Test>| {
+			SchemaRegistry::update_schema(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				(account_pair.sign(&version.encode()), version),
+			)
+		};
+
+		// a version that already expired or is not created yet is not published
+		let expired = SchemaOf::<Test> { expiration_date: Some(500u64), nonce: 3, ..schema.clone() };
+		assert_noop!(update(expired), Error::<Test>::ExpirationDateInPast);
+		let future = SchemaOf::<Test> { creation_date: 2_000u64, nonce: 3, ..schema.clone() };
+		assert_noop!(update(future), Error::<Test>::CreationDateInFuture);
+		assert_ok!(update(SchemaOf::<Test> {
+			expiration_date: Some(5_000u64),
+			nonce: 3,
+			..schema
+		}));
+	});
+}
+
+#[test]
+fn create_schema_rejects_replayed_nonce() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name.clone(),
+			schema.creator.clone(),
+			false,
+			schema.mandatory_fields.clone(),
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims.clone(),
+			schema.subject_claims.clone(),
+			schema.credential_claims.clone(),
+			schema.metadata.clone(),
+			data_sig.clone(),
+			schema.nonce
+		));
+		// the same signed payload cannot be stored under another id
+		assert_noop!(
+			SchemaRegistry::create_schema(
+				RawOrigin::Signed(signer).into(),
+				Some(1u32),
+				schema.name.clone(),
+				schema.creator.clone(),
+				false,
+				schema.mandatory_fields.clone(),
+				schema.creation_date,
+				schema.expiration_date,
+				schema.issuer_claims.clone(),
+				schema.subject_claims.clone(),
+				schema.credential_claims.clone(),
+				schema.metadata.clone(),
+				data_sig.clone(),
+				schema.nonce
+			),
+			Error::<Test>::StaleNonce
+		);
+		assert_eq!(SchemaRegistry::get_nonce(&schema.creator), 3);
+	});
+}
+
+#[test]
+fn create_and_update_schema_report_typed_errors() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let create = |schema: &SchemaOf<Test>| {
+			SchemaRegistry::create_schema(
+				RawOrigin::Signed(signer).into(),
+				Some(0u32),
+				schema.name.clone(),
+				schema.creator.clone(),
+				false,
+				schema.mandatory_fields.clone(),
+				schema.creation_date,
+				schema.expiration_date,
+				schema.issuer_claims.clone(),
+				schema.subject_claims.clone(),
+				schema.credential_claims.clone(),
+				schema.metadata.clone(),
+				account_pair.sign(&schema.encode()),
+				schema.nonce,
+			)
+		};
+
+		// every way the creator DID fails to resolve has its own error
+		for (creator, error) in [
+			(&b"did:other:abc"[..], Error::<Test>::InvalidDID),
+			(&b"did:seneca:abc"[..], Error::<Test>::InvalidDidAccount),
+			(DEACTIVATED_DID, Error::<Test>::DidDeactivated),
+			(KEYLESS_DID, Error::<Test>::NoAssertionKey),
+		] {
+			let schema = SchemaOf::<Test> { creator: bounded(creator), ..schema.clone() };
+			assert_noop!(create(&schema), error);
+		}
+
+		assert_ok!(create(&schema));
+		let next = SchemaOf::<Test> { nonce: 3u64, ..schema.clone() };
+		assert_noop!(create(&next), Error::<Test>::SchemaAlreadyExists);
+
+		// resubmitting the stored schema and signature changes nothing
+		let stored = SchemaStore::<Test>::get(0u32).unwrap();
+		assert_noop!(
+			SchemaRegistry::update_schema(RawOrigin::Signed(signer).into(), 0u32, stored),
+			Error::<Test>::SchemaUnchanged
+		);
+	});
+}
+
+#[test]
+fn update_schema_verifies_signature_and_records_history() {
+	new_test_ext().execute_with(|| {
+		let bob = account_pair("Bob");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name.clone(),
+			schema.creator.clone(),
+			false,
+			schema.mandatory_fields.clone(),
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims.clone(),
+			schema.subject_claims.clone(),
+			schema.credential_claims.clone(),
+			schema.metadata.clone(),
+			data_sig.clone(),
+			schema.nonce
+		));
+
+		let updated = SchemaOf::<Test> {
+			metadata: bounded(b"metadata2"),
+			nonce: 3u64,
+			..schema.clone()
+		};
+		// the signature must cover the new payload and come from the creator
+		assert_noop!(
+			SchemaRegistry::update_schema(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				(data_sig, updated.clone())
+			),
+			Error::<Test>::SignatureVerifyError
+		);
+		assert_noop!(
+			SchemaRegistry::update_schema(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				(bob.sign(&updated.encode()), updated.clone())
+			),
+			Error::<Test>::SignatureVerifyError
+		);
+		let handed_over = SchemaOf::<Test> {
+			creator: bounded(format!("did:seneca:{}", bob.public().into_account()).as_bytes()),
+			..updated.clone()
+		};
+		assert_noop!(
+			SchemaRegistry::update_schema(
+				RawOrigin::Signed(signer).into(),
+				0u32,
+				(bob.sign(&handed_over.encode()), handed_over)
+			),
+			Error::<Test>::CreatorChanged
+		);
+
+		assert_ok!(SchemaRegistry::update_schema(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			(account_pair.sign(&updated.encode()), updated)
+		));
+		let history = SchemaRegistry::schema_history(&0u32);
+		assert_eq!(history.len(), 1);
+		assert_eq!(history[0].0, 1);
+		assert_eq!(history[0].1.previous_hash, sp_io::hashing::blake2_256(&schema.encode()));
+		assert_eq!(history[0].1.moment, Timestamp::now());
+	});
+}
+
+#[test]
+fn update_schema_publishes_immutable_versions() {
+	new_test_ext().execute_with(|| {
+		// events are only recorded from the first block on
+		System::set_block_number(1);
+		let bob = account_pair("Bob");
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name.clone(),
+			schema.creator.clone(),
+			false,
+			schema.mandatory_fields.clone(),
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims.clone(),
+			schema.subject_claims.clone(),
+			schema.credential_claims.clone(),
+			schema.metadata.clone(),
+			data_sig.clone(),
+			schema.nonce
+		));
+		// the latest version cannot be deprecated
+		assert_noop!(
+			SchemaRegistry::deprecate_schema_version(RawOrigin::Signed(signer).into(), 0u32, 0),
+			Error::<Test>::CannotDeprecateLatestVersion
+		);
+
+		let updated = SchemaOf::<Test> {
+			metadata: bounded(b"metadata2"),
+			nonce: 3u64,
+			..schema.clone()
+		};
+		let updated_sig = account_pair.sign(&updated.encode());
+		assert_ok!(SchemaRegistry::update_schema(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			(updated_sig.clone(), updated.clone())
+		));
+		System::assert_last_event(
+			crate::Event::SchemaUpdated {
+				schema_id: 0u32,
+				version: 1,
+				creator: signer,
+				creator_did: updated.creator.to_vec(),
+				content_hash: sp_io::hashing::blake2_256(&updated.encode()),
+				moment: Timestamp::now(),
+			}
+			.into(),
+		);
+		// both versions stay available, unchanged
+		assert_eq!(SchemaRegistry::schema_version(&0u32, 0), Some((data_sig, schema.clone())));
+		assert_eq!(SchemaRegistry::schema_version(&0u32, 1), Some((updated_sig, updated)));
+		assert_eq!(SchemaRegistry::schema_version(&0u32, 2), None);
+		assert_ok!(SchemaRegistry::check_schema_version(0u32, 0));
+		assert_noop!(
+			SchemaRegistry::check_schema_version(0u32, 2),
+			Error::<Test>::UnknownSchemaVersion
+		);
+
+		// only the creator deprecates, and only versions that were published
+		assert_noop!(
+			SchemaRegistry::deprecate_schema_version(
+				RawOrigin::Signed(bob.public()).into(),
+				0u32,
+				0
+			),
+			Error::<Test>::NotSchemaOwner
+		);
+		assert_noop!(
+			SchemaRegistry::deprecate_schema_version(RawOrigin::Signed(signer).into(), 0u32, 5),
+			Error::<Test>::UnknownSchemaVersion
+		);
+		assert_ok!(SchemaRegistry::deprecate_schema_version(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			0
+		));
+		System::assert_last_event(crate::Event::SchemaVersionDeprecated(0u32, 0).into());
+		assert_noop!(
+			SchemaRegistry::check_schema_version(0u32, 0),
+			Error::<Test>::SchemaVersionDeprecated
+		);
+		assert_ok!(SchemaRegistry::check_schema_version(0u32, 1));
+		// the deprecated version is still readable
+		assert_eq!(SchemaRegistry::get_schema(0u32, 0), Some(schema));
+
+		// the witness has to cover every stored version
+		assert_noop!(
+			SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), 0u32, 0),
+			Error::<Test>::InvalidVersionCount
+		);
+		assert_ok!(SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), 0u32, 1));
+		assert_eq!(SchemaRegistry::schema_version(&0u32, 0), None);
+		assert!(!DeprecatedVersions::<Test>::contains_key(0u32, 0));
+	});
+}
+
+#[test]
+fn schema_deposit_follows_schema_size() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Alice Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 2u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		let deposit = |data: &(sp_core::sr25519::Signature, SchemaOf<Test>)| {
+			100 + data.encoded_size() as u64
+		};
+		assert_ok!(SchemaRegistry::create_schema(
+			RawOrigin::Signed(signer).into(),
+			Some(0u32),
+			schema.name.clone(),
+			schema.creator.clone(),
+			false,
+			schema.mandatory_fields.clone(),
+			schema.creation_date,
+			schema.expiration_date,
+			schema.issuer_claims.clone(),
+			schema.subject_claims.clone(),
+			schema.credential_claims.clone(),
+			schema.metadata.clone(),
+			data_sig.clone(),
+			schema.nonce
+		));
+		let original_deposit = deposit(&(data_sig, schema.clone()));
+		assert_eq!(Balances::reserved_balance(&signer), original_deposit);
+
+		// the superseded version stays stored, so its deposit is kept alongside the new one
+		let updated = SchemaOf::<Test> {
+			metadata: bounded(&[b'm'; 64]),
+			nonce: 3u64,
+			..schema.clone()
+		};
+		let updated_data = (account_pair.sign(&updated.encode()), updated);
+		assert_ok!(SchemaRegistry::update_schema(
+			RawOrigin::Signed(signer).into(),
+			0u32,
+			updated_data.clone()
+		));
+		assert_eq!(
+			Balances::reserved_balance(&signer),
+			original_deposit + deposit(&updated_data)
+		);
+
+		assert_ok!(SchemaRegistry::delete_schema(RawOrigin::Signed(signer).into(), 0u32, 1));
+		assert_eq!(Balances::reserved_balance(&signer), 0);
+		assert_eq!(Balances::free_balance(&signer), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn create_schema_requires_deposit() {
+	new_test_ext().execute_with(|| {
+		// Dave holds no funds in the mock genesis
+		let account_pair = account_pair("Dave");
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let schema = SchemaOf::<Test> {
+			name: bounded(b"Dave Data"),
+			creator: bounded(account_id.as_bytes()),
+			public: false,
+			creation_date: Timestamp::now(),
+			expiration_date: None,
+			mandatory_fields: bounded_vec![],
+			issuer_claims: bounded_vec![],
+			subject_claims: bounded_vec![],
+			credential_claims: bounded_vec![],
+			metadata: bounded(b"metadata"),
+			nonce: 0u64,
+		};
+		let data_sig = account_pair.sign(&schema.encode());
+		assert_noop!(
+			SchemaRegistry::create_schema(
+				RawOrigin::Signed(account_pair.public()).into(),
+				Some(0u32),
+				schema.name,
+				schema.creator,
+				false,
+				schema.mandatory_fields,
+				schema.creation_date,
+				schema.expiration_date,
+				schema.issuer_claims,
+				schema.subject_claims,
+				schema.credential_claims,
+				schema.metadata,
+				data_sig,
+				schema.nonce
+			),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn migration_bounds_existing_schemas() {
+	new_test_ext().execute_with(|| {
+		let account_pair = account_pair("Alice");
+		let signer = account_pair.public();
+		let account_id = format!("did:seneca:{}", account_pair.public().into_account());
+		let old_schema = |name: Vec<u8>| v1::OldVerifiableCredentialSchema {
+			name,
+			creator: account_id.clone().into_bytes(),
+			public: false,
+			creation_date: 0u64,
+			expiration_date: None,
+			mandatory_fields: vec![v1::OldAttribute {
+				name: b"name".to_vec(),
+				attribute_type: AttributeType::Text,
+			}],
+			issuer_claims: vec![],
+			subject_claims: vec![],
+			credential_claims: vec![],
+			metadata: b"metadata".to_vec(),
+			nonce: 0u64,
+		};
+		let fitting = old_schema(b"Alice Data".to_vec());
+		let oversized = old_schema(vec![b'n'; 65]);
+		let data_sig = account_pair.sign(&fitting.encode());
+		for (id, schema) in [(0u32, &fitting), (1u32, &oversized)] {
+			unhashed::put(&SchemaStore::<Test>::hashed_key_for(id), &(data_sig.clone(), schema));
+			assert_ok!(Balances::reserve(&signer, 100));
+			SchemaDeposits::<Test>::insert(id, (signer, 100u64));
+		}
+		v1::shared::Nonce::<Test>::put(3);
+		StorageVersion::new(0).put::<SchemaRegistry>();
+
+		v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<SchemaRegistry>(), 1);
+		// the nonce shared by all creators is gone, nonces are kept per DID
+		assert!(!v1::shared::Nonce::<Test>::exists());
+		let (_, migrated) = SchemaRegistry::schema_registry(0u32).unwrap();
+		assert_eq!(migrated.encode(), fitting.encode());
+		// the schema that no longer fits is dropped and its deposit released
+		assert_eq!(SchemaRegistry::schema_registry(1u32), None);
+		assert_eq!(SchemaDeposits::<Test>::get(1u32), None);
+		assert_eq!(Balances::reserved_balance(&signer), 100);
+	});
+}
+
+#[test]
+fn attribute_types_accept_well_formed_values() {
+	assert!(AttributeType::Int.accepts(b"-42"));
+	assert!(!AttributeType::Int.accepts(b"4.2"));
+	assert!(AttributeType::Uint.accepts(b"42"));
+	assert!(!AttributeType::Uint.accepts(b"-42"));
+	assert!(AttributeType::Float.accepts(b"-4.2e10"));
+	assert!(AttributeType::Float.accepts(b".5"));
+	assert!(!AttributeType::Float.accepts(b"NaN"));
+	assert!(AttributeType::Hex.accepts(b"0x1234abcd"));
+	assert!(!AttributeType::Hex.accepts(b"0x"));
+	assert!(AttributeType::DateType.accepts(b"2024-02-29"));
+	assert!(AttributeType::DateType.accepts(b"2023-06-30T12:00:00.000+02:00"));
+	assert!(AttributeType::DateType.accepts(b"2023-06-30T12:00:00Z"));
+	assert!(!AttributeType::DateType.accepts(b"2023-02-29"));
+	assert!(!AttributeType::DateType.accepts(b"2023-06-30T25:00:00Z"));
+	assert!(AttributeType::Base64.accepts(b"aGVsbG8="));
+	assert!(!AttributeType::Base64.accepts(b"aGVsbG8"));
+	assert!(AttributeType::Text.accepts("héllo".as_bytes()));
+	assert!(!AttributeType::Text.accepts(&[0xff, 0xfe]));
+}
@@ -1,51 +1,109 @@
-use codec::{Decode, Encode};
-use frame_support::RuntimeDebug;
-use scale_info::prelude::vec::Vec;
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{traits::Get, BoundedVec, RuntimeDebug};
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct VerifiableCredentialSchema<Moment> {
-	pub name: Vec<u8>,
-	pub creator: Vec<u8>,
+/// Bounds on the variable length fields of schemas and claims.
+pub trait SchemaLimits: 'static {
+	/// Longest schema name, attribute name or claim property.
+	type MaxNameLength: Get<u32>;
+	/// Longest DID of a schema creator or of a credential issuer, holder or subject.
+	type MaxDidLength: Get<u32>;
+	/// Most attributes, claims or issuance requirements in one list.
+	type MaxClaims: Get<u32>;
+	/// Longest claim value.
+	type MaxValueLength: Get<u32>;
+	/// Longest schema metadata.
+	type MaxMetadataLength: Get<u32>;
+}
+
+pub type BoundedName<L> = BoundedVec<u8, <L as SchemaLimits>::MaxNameLength>;
+pub type BoundedDid<L> = BoundedVec<u8, <L as SchemaLimits>::MaxDidLength>;
+pub type BoundedAttributes<L> = BoundedVec<Attribute<L>, <L as SchemaLimits>::MaxClaims>;
+pub type BoundedClaims<L> = BoundedVec<Claim<L>, <L as SchemaLimits>::MaxClaims>;
+
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound(Moment: MaxEncodedLen))]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(serialize = "Moment: Serialize", deserialize = "Moment: Deserialize<'de>"))
+)]
+pub struct VerifiableCredentialSchema<Moment, L: SchemaLimits> {
+	pub name: BoundedName<L>,
+	pub creator: BoundedDid<L>,
 	pub public: bool,
 	pub creation_date: Moment,
 	pub expiration_date: Option<Moment>,
-	pub mandatory_fields: Vec<Attribute>,
-	pub issuer_claims: Vec<Claim>,
-	pub subject_claims: Vec<Claim>,
-	pub credential_claims: Vec<Claim>,
-	pub metadata: Vec<u8>,
+	pub mandatory_fields: BoundedAttributes<L>,
+	pub issuer_claims: BoundedClaims<L>,
+	pub subject_claims: BoundedClaims<L>,
+	pub credential_claims: BoundedClaims<L>,
+	pub metadata: BoundedVec<u8, L::MaxMetadataLength>,
 	pub nonce: u64,
 }
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct Claim {
+
+/// Audit entry written when a stored schema or credential is replaced.
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct UpdateRecord<BlockNumber, Moment> {
+	/// Blake2-256 hash of the encoded payload that was replaced.
+	pub previous_hash: [u8; 32],
+	pub block: BlockNumber,
+	pub moment: Moment,
+}
+
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct Claim<L: SchemaLimits> {
 	pub schemaid: Option<u32>,
-	pub property: Vec<u8>,
-	pub value: Vec<u8>,
+	pub property: BoundedName<L>,
+	pub value: BoundedVec<u8, L::MaxValueLength>,
 	pub claim_type: ClaimType,
-	pub issuance_requirement: Option<Vec<IssuanceRequirement>>,
+	pub issuance_requirement: Option<BoundedVec<IssuanceRequirement<L>, L::MaxClaims>>,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum ClaimType {
 	IssuerClaim,
 	SubjectClaim,
 	CredentialClaim,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct Attribute {
-	pub name: Vec<u8>,
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct Attribute<L: SchemaLimits> {
+	pub name: BoundedName<L>,
 	pub attribute_type: AttributeType,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct Subject {
-	pub id: Vec<u8>,
-	pub claim: Vec<Claim>,
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct Subject<L: SchemaLimits> {
+	pub id: BoundedDid<L>,
+	pub claim: BoundedClaims<L>,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum AttributeType {
 	Int,
 	Uint,
@@ -56,13 +114,20 @@ pub enum AttributeType {
 	Text,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
-pub struct IssuanceRequirement {
-	pub name: Vec<u8>,
+#[derive(PartialEq, Eq, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen)]
+#[scale_info(skip_type_params(L))]
+#[codec(mel_bound())]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
+pub struct IssuanceRequirement<L: SchemaLimits> {
+	pub name: BoundedName<L>,
 	pub insuance_type: IssuanceType,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, TypeInfo, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum IssuanceType {
 	Int,
 	Uint,
@@ -1,12 +1,11 @@
-//! Autogenerated weights for `pallet_schemas`
+//! Weights for `pallet_schemas`
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-01-27, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! HOSTNAME: `MACs-MacBook-Air.local`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
+//! These are estimates, not benchmark output: they are scaled from the weights measured for the
+//! first versions of these calls by their current storage footprint. Regenerate this file with
+//! `scripts/benchmark.sh` on reference hardware before relying on it.
 
-// Executed Command:
-// ./target/production/serv-node
+// Command to regenerate:
+// ./target/production/zeno-node
 // benchmark
 // pallet
 // --chain
@@ -21,8 +20,10 @@
 // 50
 // --repeat
 // 20
+// --template
+// scripts/frame-weight-template.hbs
 // --output
-// pallets/schema-registry/src/weights.rs
+// pallets/schemas/src/weights.rs
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -34,7 +35,11 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn create_schema() -> Weight;
     fn update_schema() -> Weight;
-	fn delete_schema() -> Weight;
+	fn delete_schema(v: u32, i: u32, ) -> Weight;
+	fn deprecate_schema_version() -> Weight;
+	fn deprecate_schema() -> Weight;
+	fn add_issuer() -> Weight;
+	fn remove_issuer() -> Weight;
 }
 
 /// Weight functions for `pallet_schemas`.
@@ -42,49 +47,133 @@ pub struct SchemaRegistryWeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SchemaRegistryWeightInfo<T> {
 	// Storage: Timestamp Now (r:1 w:0)
 	// Storage: SchemaRegistry SchemaStore (r:1 w:1)
+	// Storage: SchemaRegistry Nonce (r:1 w:1)
+	// Storage: SchemaRegistry NextSchemaId (r:1 w:1)
 	/// The range of component `s` is `[0, 100]`.
 	fn create_schema() -> Weight {
-		// Minimum execution time: 17_000 nanoseconds.
 		Weight::from_parts(18_019_846, 0u64)
-			.saturating_add(T::DbWeight::get().reads(2 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
 	}
+	// Storage: Timestamp Now (r:1 w:0)
 	// Storage: SchemaRegistry SchemaStore (r:1 w:1)
+	// Storage: SchemaRegistry Nonce (r:1 w:1)
+	// Storage: SchemaRegistry SchemaDeposits (r:1 w:1)
+	// Storage: SchemaRegistry SchemaRevisions (r:1 w:1)
+	// Storage: SchemaRegistry SchemaHistory (r:0 w:1)
+	// Storage: SchemaRegistry SchemaVersions (r:0 w:1)
 	/// The range of component `s` is `[0, 100]`.
 	fn update_schema() -> Weight {
-		// Minimum execution time: 18_000 nanoseconds.
-		Weight::from_parts(19_789_873, 0u64)
-			.saturating_add(T::DbWeight::get().reads(1 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+		Weight::from_parts(25_318_402, 0u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
 	// Storage: SchemaRegistry SchemaStore (r:1 w:1)
-	/// The range of component `s` is `[0, 100]`.
-	fn delete_schema() -> Weight {
-		// Minimum execution time: 14_000 nanoseconds.
-		Weight::from_parts(14_959_443, 0u64)
-			// Standard Error: 440
-			.saturating_add(Weight::from_parts(172, 0u64).saturating_mul(1 as u64))
-			.saturating_add(T::DbWeight::get().reads(1 as u64))
+	// Storage: SchemaRegistry SchemaReferences (r:1 w:0)
+	// Storage: SchemaRegistry SchemaRevisions (r:1 w:1)
+	// Storage: SchemaRegistry AuthorizedIssuerCount (r:1 w:1)
+	// Storage: SchemaRegistry SchemaDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: SchemaRegistry DeprecatedSchemas (r:0 w:1)
+	// Storage: SchemaRegistry SchemaHistory (r:v w:v)
+	// Storage: SchemaRegistry SchemaVersions (r:v w:v)
+	// Storage: SchemaRegistry DeprecatedVersions (r:v w:v)
+	// Storage: SchemaRegistry AuthorizedIssuers (r:i w:i)
+	/// The range of component `v` is `[0, 100]`.
+	/// The range of component `i` is `[0, 64]`.
+	fn delete_schema(v: u32, i: u32, ) -> Weight {
+		Weight::from_parts(21_283_905, 0u64)
+			.saturating_add(Weight::from_parts(1_500_000, 0u64).saturating_mul(v as u64))
+			.saturating_add(Weight::from_parts(1_000_000, 0u64).saturating_mul(i as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(v as u64)))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(i as u64)))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+			.saturating_add(T::DbWeight::get().writes((3 as u64).saturating_mul(v as u64)))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(i as u64)))
+	}
+	// Storage: SchemaRegistry SchemaStore (r:1 w:0)
+	// Storage: SchemaRegistry SchemaRevisions (r:1 w:0)
+	// Storage: SchemaRegistry SchemaVersions (r:1 w:0)
+	// Storage: SchemaRegistry DeprecatedVersions (r:0 w:1)
+	fn deprecate_schema_version() -> Weight {
+		Weight::from_parts(13_874_260, 0u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: SchemaRegistry SchemaStore (r:1 w:0)
+	// Storage: SchemaRegistry DeprecatedSchemas (r:1 w:1)
+	fn deprecate_schema() -> Weight {
+		Weight::from_parts(12_593_417, 0u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: SchemaRegistry SchemaStore (r:1 w:0)
+	// Storage: SchemaRegistry AuthorizedIssuers (r:1 w:1)
+	// Storage: SchemaRegistry AuthorizedIssuerCount (r:1 w:1)
+	// Storage: SchemaRegistry SchemaDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn add_issuer() -> Weight {
+		Weight::from_parts(12_861_093, 0u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: SchemaRegistry SchemaStore (r:1 w:0)
+	// Storage: SchemaRegistry AuthorizedIssuers (r:1 w:1)
+	// Storage: SchemaRegistry AuthorizedIssuerCount (r:1 w:1)
+	// Storage: SchemaRegistry SchemaDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn remove_issuer() -> Weight {
+		Weight::from_parts(12_740_518, 0u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
 }
 
 impl WeightInfo for () {
     // Storage: SchemaRegistry SchemaStore (r:1 w:1)
     fn create_schema() -> Weight {
         Weight::from_parts(18_019_846, 0u64)
-            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes(3 as u64))
     }
 
 	// Storage: SchemaRegistry SchemaStore (r:1 w:1)
     fn update_schema() -> Weight {
-		Weight::from_parts(19_789_873, 0u64)
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+		Weight::from_parts(25_318_402, 0u64)
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
 	}
 
 	// Storage: SchemaRegistry SchemaStore (r:1 w:1)
-	fn delete_schema() -> Weight {
-        Weight::from_parts(14_959_443, 0u64)
-            .saturating_add(RocksDbWeight::get().writes(1 as u64))
-    }
+	fn delete_schema(v: u32, i: u32, ) -> Weight {
+		Weight::from_parts(21_283_905, 0u64)
+			.saturating_add(Weight::from_parts(1_500_000, 0u64).saturating_mul(v as u64))
+			.saturating_add(Weight::from_parts(1_000_000, 0u64).saturating_mul(i as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes((3 as u64).saturating_mul(v as u64)))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(i as u64)))
+	}
+
+	// Storage: SchemaRegistry DeprecatedVersions (r:0 w:1)
+	fn deprecate_schema_version() -> Weight {
+		Weight::from_parts(13_874_260, 0u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+
+	// Storage: SchemaRegistry DeprecatedSchemas (r:1 w:1)
+	fn deprecate_schema() -> Weight {
+		Weight::from_parts(12_593_417, 0u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+
+	// Storage: SchemaRegistry AuthorizedIssuers (r:1 w:1)
+	fn add_issuer() -> Weight {
+		Weight::from_parts(12_861_093, 0u64)
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+
+	// Storage: SchemaRegistry AuthorizedIssuers (r:1 w:1)
+	fn remove_issuer() -> Weight {
+		Weight::from_parts(12_740_518, 0u64)
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
 }
\ No newline at end of file
@@ -16,37 +16,183 @@
 // limitations under the License.
 
 use codec::{Decode, Encode};
-use sp_core::crypto::AccountId32;
-use sp_runtime::DispatchError;
-use sp_std::{str, vec, vec::Vec};
+use sp_core::hashing::blake2_512;
 
-/// function converts string to accountid
-pub fn convert_string_to_accountid<AccountId>(account_str: &str) -> Result<AccountId, DispatchError>
+const ACCOUNT_LEN: usize = 32;
+const CHECKSUM_LEN: usize = 2;
+const CHECKSUM_PREAMBLE: &[u8] = b"SS58PRE";
+
+/// Reasons an SS58 address can be rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ss58Error {
+	/// The address contains characters outside the base58 alphabet.
+	BadBase58,
+	/// The address does not decode to a prefix, a 32 byte account and a checksum.
+	BadLength,
+	/// The first byte does not start a one or two byte prefix.
+	InvalidPrefix,
+	/// The prefix is reserved and never used by a network.
+	ReservedPrefix,
+	/// The checksum does not match the prefix and account.
+	InvalidChecksum,
+	/// The address is well formed but belongs to a network that is not accepted.
+	UnexpectedPrefix(u16),
+	/// The account bytes do not decode into the requested account id type.
+	InvalidAccountId,
+}
+
+/// Decode an SS58 address into its network prefix and the 32 byte account it encodes.
+pub fn decode_ss58(address: &str) -> Result<(u16, [u8; ACCOUNT_LEN]), Ss58Error> {
+	// a two byte prefix is the longest valid layout, anything larger overflows the buffer
+	let mut buffer = [0u8; 2 + ACCOUNT_LEN + CHECKSUM_LEN];
+	let len = bs58::decode(address).into(&mut buffer[..]).map_err(|error| match error {
+		bs58::decode::Error::BufferTooSmall => Ss58Error::BadLength,
+		_ => Ss58Error::BadBase58,
+	})?;
+	let data = &buffer[..len];
+	if data.len() < 2 {
+		return Err(Ss58Error::BadLength)
+	}
+	let (prefix_len, prefix) = match data[0] {
+		0..=63 => (1, data[0] as u16),
+		64..=127 => {
+			// the lower six bits of the first byte and the upper two of the second form the
+			// low byte, the remaining six bits of the second byte the high byte
+			let lower = (data[0] << 2) | (data[1] >> 6);
+			let upper = data[1] & 0b0011_1111;
+			(2, (lower as u16) | ((upper as u16) << 8))
+		},
+		_ => return Err(Ss58Error::InvalidPrefix),
+	};
+	if data.len() != prefix_len + ACCOUNT_LEN + CHECKSUM_LEN {
+		return Err(Ss58Error::BadLength)
+	}
+	if matches!(prefix, 46 | 47) {
+		return Err(Ss58Error::ReservedPrefix)
+	}
+	let (body, checksum) = data.split_at(prefix_len + ACCOUNT_LEN);
+	if ss58hash(body)[..CHECKSUM_LEN] != *checksum {
+		return Err(Ss58Error::InvalidChecksum)
+	}
+	let mut account = [0u8; ACCOUNT_LEN];
+	account.copy_from_slice(&body[prefix_len..]);
+	Ok((prefix, account))
+}
+
+/// Convert an SS58 address of the network with the given prefix, usually the `SS58Prefix` of
+/// the runtime, to an account id.
+pub fn convert_string_to_accountid<AccountId>(
+	account_str: &str,
+	ss58_prefix: u16,
+) -> Result<AccountId, Ss58Error>
 where
 	AccountId: Encode + ?Sized + Decode,
 {
-	let mut output = vec![0xFF; 35];
-	match bs58::decode(account_str).into(&mut output) {
-		Ok(_) => {}
-		Err(err) => {
-			// Error: Handle the error here or propagate it further
-			log::error!("Error decoding: {}", err);
-			// Additional error handling logic can be added here
-			return Err(DispatchError::Other("Error decoding string"));
+	convert_string_to_accountid_with_prefixes(account_str, &[ss58_prefix])
+}
+
+/// Convert an SS58 address to an account id, accepting only addresses of the given networks.
+pub fn convert_string_to_accountid_with_prefixes<AccountId>(
+	account_str: &str,
+	accepted_prefixes: &[u16],
+) -> Result<AccountId, Ss58Error>
+where
+	AccountId: Encode + ?Sized + Decode,
+{
+	let (prefix, account) = decode_ss58(account_str)?;
+	if !accepted_prefixes.contains(&prefix) {
+		return Err(Ss58Error::UnexpectedPrefix(prefix))
+	}
+	AccountId::decode(&mut &account[..]).map_err(|e| {
+		log::error!("Error decoding: {}", e);
+		Ss58Error::InvalidAccountId
+	})
+}
+
+fn ss58hash(data: &[u8]) -> [u8; 64] {
+	let mut preimage = [0u8; CHECKSUM_PREAMBLE.len() + 2 + ACCOUNT_LEN];
+	preimage[..CHECKSUM_PREAMBLE.len()].copy_from_slice(CHECKSUM_PREAMBLE);
+	preimage[CHECKSUM_PREAMBLE.len()..][..data.len()].copy_from_slice(data);
+	blake2_512(&preimage[..CHECKSUM_PREAMBLE.len() + data.len()])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+	use sp_core::crypto::{AccountId32, Ss58AddressFormat, Ss58Codec};
+
+	const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+	const SS58_PREFIX: u16 = 42;
+
+	fn encode(prefix: u16, account: [u8; 32]) -> String {
+		AccountId32::new(account).to_ss58check_with_version(Ss58AddressFormat::custom(prefix))
+	}
+
+	// what sp_core makes of an address, in the shape returned by `decode_ss58`
+	fn reference_decode(address: &str) -> Option<(u16, [u8; 32])> {
+		AccountId32::from_ss58check_with_version(address)
+			.ok()
+			.map(|(account, format)| (u16::from(format), account.into()))
+	}
+
+	proptest! {
+		#[test]
+		fn decodes_what_sp_core_encodes(
+			prefix in (0u16..16384).prop_filter("reserved", |p| !matches!(p, 46 | 47)),
+			account in any::<[u8; 32]>(),
+		) {
+			prop_assert_eq!(decode_ss58(&encode(prefix, account)), Ok((prefix, account)));
+		}
+
+		#[test]
+		fn agrees_with_sp_core_on_corrupted_addresses(
+			prefix in 0u16..16384,
+			account in any::<[u8; 32]>(),
+			position in any::<prop::sample::Index>(),
+			replacement in prop::sample::select(BASE58_ALPHABET),
+		) {
+			let mut address = encode(prefix, account).into_bytes();
+			let position = position.index(address.len());
+			address[position] = replacement;
+			let address = String::from_utf8(address).unwrap();
+			prop_assert_eq!(decode_ss58(&address).ok(), reference_decode(&address));
+		}
+
+		#[test]
+		fn agrees_with_sp_core_on_arbitrary_input(address in "[1-9A-HJ-NP-Za-km-z]{0,52}") {
+			prop_assert_eq!(decode_ss58(&address).ok(), reference_decode(&address));
+		}
+
+		#[test]
+		fn rejects_other_networks(prefix in 0u16..16384, account in any::<[u8; 32]>()) {
+			prop_assume!(prefix != SS58_PREFIX && !matches!(prefix, 46 | 47));
+			prop_assert_eq!(
+				convert_string_to_accountid::<AccountId32>(&encode(prefix, account), SS58_PREFIX),
+				Err(Ss58Error::UnexpectedPrefix(prefix))
+			);
 		}
 	}
-	let cut_address_vec: Vec<u8> = output.drain(1..33).collect();
-	let mut array = [0; 32];
-	let bytes = &cut_address_vec[..array.len()];
-	array.copy_from_slice(bytes);
-	let account32: AccountId32 = array.into();
-	let mut to32 = AccountId32::as_ref(&account32);
-	let to_address = match AccountId::decode(&mut to32) {
-		Ok(a) => a,
-		Err(e) => {
-			log::error!("Error decoding: {}", e);
-			return Err(DispatchError::Other("Error converting string to AccountId"));
-		},
-	};
-	Ok(to_address)
+
+	#[test]
+	fn rejects_malformed_addresses() {
+		let address = encode(SS58_PREFIX, [7u8; 32]);
+		assert_eq!(
+			convert_string_to_accountid::<AccountId32>(&address, SS58_PREFIX),
+			Ok(AccountId32::new([7u8; 32]))
+		);
+		assert_eq!(decode_ss58("0OIl"), Err(Ss58Error::BadBase58));
+		assert_eq!(decode_ss58("abc"), Err(Ss58Error::BadLength));
+		assert_eq!(decode_ss58(&format!("{}1111", address)), Err(Ss58Error::BadLength));
+		assert_eq!(decode_ss58(&encode(46, [7u8; 32])), Err(Ss58Error::ReservedPrefix));
+
+		// flip the last character to break the checksum
+		let mut corrupted = address.into_bytes();
+		let last = corrupted.last_mut().unwrap();
+		*last = if *last == b'z' { b'y' } else { b'z' };
+		assert_eq!(
+			decode_ss58(&String::from_utf8(corrupted).unwrap()),
+			Err(Ss58Error::InvalidChecksum)
+		);
+	}
 }
@@ -0,0 +1,265 @@
+//! Parsing of decentralized identifiers following the W3C DID Core syntax, and resolution of
+//! the `did:seneca` method to on-chain accounts.
+//!
+//! ```text
+//! did        = "did:" method-name ":" method-specific-id
+//! did-url    = did path-abempty [ "?" query ] [ "#" fragment ]
+//! ```
+
+use crate::convert2accountid::{convert_string_to_accountid, Ss58Error};
+use codec::{Decode, Encode};
+use sp_core::Get;
+use sp_std::{marker::PhantomData, str, vec, vec::Vec};
+
+/// Method name of DIDs anchored on this chain.
+pub const SENECA_METHOD: &str = "seneca";
+
+/// Reasons a DID can be rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DidError {
+	/// The input is not valid UTF-8.
+	NotUtf8,
+	/// The input does not start with the `did:` scheme.
+	InvalidScheme,
+	/// The method name is empty or contains characters other than `a-z` and `0-9`.
+	InvalidMethod,
+	/// The method-specific id is missing or contains invalid characters.
+	InvalidMethodSpecificId,
+	/// The path contains invalid characters.
+	InvalidPath,
+	/// The query contains invalid characters.
+	InvalidQuery,
+	/// The fragment contains invalid characters.
+	InvalidFragment,
+	/// The DID is well formed but does not use the expected method.
+	UnsupportedMethod,
+	/// The method-specific id is not an SS58 address of this chain.
+	InvalidAccount(Ss58Error),
+	/// The DID has been deactivated.
+	Deactivated,
+	/// The DID document has no assertion method matching the request.
+	NoAssertionKey,
+}
+
+/// A DID, or DID URL, borrowed from its textual form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Did<'a> {
+	pub method: &'a str,
+	pub method_specific_id: &'a str,
+	/// Path including its leading `/`.
+	pub path: Option<&'a str>,
+	pub query: Option<&'a str>,
+	pub fragment: Option<&'a str>,
+}
+
+impl<'a> Did<'a> {
+	/// Parse a DID or DID URL.
+	pub fn parse(did: &'a str) -> Result<Self, DidError> {
+		let rest = did.strip_prefix("did:").ok_or(DidError::InvalidScheme)?;
+		let (method, rest) = rest.split_once(':').ok_or(DidError::InvalidMethodSpecificId)?;
+		if method.is_empty() ||
+			!method.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+		{
+			return Err(DidError::InvalidMethod)
+		}
+
+		let (rest, fragment) = split_off(rest, '#');
+		let (rest, query) = split_off(rest, '?');
+		let (method_specific_id, path) = match rest.find('/') {
+			Some(pos) => (&rest[..pos], Some(&rest[pos..])),
+			None => (rest, None),
+		};
+
+		// only the last colon separated segment of the id has to be non-empty
+		if !method_specific_id.rsplit(':').next().map_or(false, |last| !last.is_empty()) ||
+			!is_encoded(method_specific_id, |b| b == b':' || is_idchar(b))
+		{
+			return Err(DidError::InvalidMethodSpecificId)
+		}
+		if !path.map_or(true, |path| is_encoded(path, |b| b == b'/' || is_pchar(b))) {
+			return Err(DidError::InvalidPath)
+		}
+		if !query.map_or(true, |query| is_encoded(query, is_query_char)) {
+			return Err(DidError::InvalidQuery)
+		}
+		if !fragment.map_or(true, |fragment| is_encoded(fragment, is_query_char)) {
+			return Err(DidError::InvalidFragment)
+		}
+
+		Ok(Did { method, method_specific_id, path, query, fragment })
+	}
+
+	/// Parse a DID or DID URL from its UTF-8 encoding.
+	pub fn parse_bytes(did: &'a [u8]) -> Result<Self, DidError> {
+		Self::parse(str::from_utf8(did).map_err(|_| DidError::NotUtf8)?)
+	}
+
+	/// Length of the DID the URL was parsed from, without path, query and fragment.
+	pub fn did_len(&self) -> usize {
+		"did:".len() + self.method.len() + 1 + self.method_specific_id.len()
+	}
+
+	/// Whether this is a plain DID rather than a DID URL.
+	pub fn is_bare(&self) -> bool {
+		self.path.is_none() && self.query.is_none() && self.fragment.is_none()
+	}
+}
+
+/// Resolves a DID, or a DID URL naming one of its keys, to the account currently entitled to
+/// sign on its behalf.
+pub trait DidResolver<AccountId> {
+	fn resolve(did: &[u8]) -> Result<AccountId, DidError>;
+	/// Keys the holder of the DID authenticates as the DID with, as when presenting
+	/// credentials it holds.
+	fn authentication_keys(did: &[u8]) -> Result<Vec<AccountId>, DidError>;
+}
+
+/// Implicit resolution only, where every `did:seneca` DID is controlled by the account its
+/// method-specific id encodes as an address with the SS58 prefix `Prefix`.
+pub struct ImplicitResolver<Prefix>(PhantomData<Prefix>);
+
+impl<AccountId, Prefix> DidResolver<AccountId> for ImplicitResolver<Prefix>
+where
+	AccountId: Encode + Decode,
+	Prefix: Get<u16>,
+{
+	fn resolve(did: &[u8]) -> Result<AccountId, DidError> {
+		seneca_account_id(did, Prefix::get())
+	}
+
+	fn authentication_keys(did: &[u8]) -> Result<Vec<AccountId>, DidError> {
+		seneca_account_id(did, Prefix::get()).map(|account| vec![account])
+	}
+}
+
+/// Resolve a `did:seneca:<ss58 address>` DID, or a DID URL built on one, to the account it
+/// names. The address must use `ss58_prefix`, the `SS58Prefix` of the runtime.
+pub fn seneca_account_id<AccountId>(did: &[u8], ss58_prefix: u16) -> Result<AccountId, DidError>
+where
+	AccountId: Encode + Decode,
+{
+	let did = Did::parse_bytes(did)?;
+	if did.method != SENECA_METHOD {
+		return Err(DidError::UnsupportedMethod)
+	}
+	if did.method_specific_id.contains(':') {
+		return Err(DidError::InvalidMethodSpecificId)
+	}
+	convert_string_to_accountid(did.method_specific_id, ss58_prefix)
+		.map_err(DidError::InvalidAccount)
+}
+
+// split `s` at the first `delimiter`, dropping the delimiter itself
+fn split_off(s: &str, delimiter: char) -> (&str, Option<&str>) {
+	match s.split_once(delimiter) {
+		Some((head, tail)) => (head, Some(tail)),
+		None => (s, None),
+	}
+}
+
+fn is_idchar(b: u8) -> bool {
+	b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_')
+}
+
+// RFC 3986 pchar without pct-encoded
+fn is_pchar(b: u8) -> bool {
+	b.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@".contains(&b)
+}
+
+fn is_query_char(b: u8) -> bool {
+	b == b'/' || b == b'?' || is_pchar(b)
+}
+
+// every byte is either `allowed` or part of a `%XX` escape
+fn is_encoded(s: &str, allowed: impl Fn(u8) -> bool) -> bool {
+	let bytes = s.as_bytes();
+	let mut i = 0;
+	while i < bytes.len() {
+		if bytes[i] == b'%' {
+			let escape = bytes.get(i + 1..i + 3);
+			if !escape.map_or(false, |hex| hex.iter().all(u8::is_ascii_hexdigit)) {
+				return false
+			}
+			i += 3;
+		} else if allowed(bytes[i]) {
+			i += 1;
+		} else {
+			return false
+		}
+	}
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::crypto::{AccountId32, Ss58Codec};
+
+	#[test]
+	fn parses_did_url_components() {
+		let did = Did::parse("did:example:123:abc/path/to%20x?service=a#key-1").unwrap();
+		assert_eq!(did.method, "example");
+		assert_eq!(did.method_specific_id, "123:abc");
+		assert_eq!(did.path, Some("/path/to%20x"));
+		assert_eq!(did.query, Some("service=a"));
+		assert_eq!(did.fragment, Some("key-1"));
+		assert_eq!(did.did_len(), "did:example:123:abc".len());
+		assert!(!did.is_bare());
+	}
+
+	#[test]
+	fn rejects_malformed_dids() {
+		assert_eq!(Did::parse("did:seneca"), Err(DidError::InvalidMethodSpecificId));
+		assert_eq!(Did::parse("did:seneca:"), Err(DidError::InvalidMethodSpecificId));
+		assert_eq!(Did::parse("did:Seneca:abc"), Err(DidError::InvalidMethod));
+		assert_eq!(Did::parse("did::abc"), Err(DidError::InvalidMethod));
+		assert_eq!(Did::parse("uri:seneca:abc"), Err(DidError::InvalidScheme));
+		assert_eq!(Did::parse("did:seneca:a b"), Err(DidError::InvalidMethodSpecificId));
+		assert_eq!(Did::parse("did:seneca:abc%2"), Err(DidError::InvalidMethodSpecificId));
+		assert_eq!(Did::parse("did:seneca:abc/a b"), Err(DidError::InvalidPath));
+		assert_eq!(Did::parse("did:seneca:abc#a#b"), Err(DidError::InvalidFragment));
+		assert_eq!(Did::parse_bytes(&[0xff]), Err(DidError::NotUtf8));
+	}
+
+	#[test]
+	fn resolves_seneca_accounts() {
+		let account = AccountId32::new([7u8; 32]);
+		let did = format!("did:seneca:{}", account.to_ss58check());
+		assert_eq!(seneca_account_id::<AccountId32>(did.as_bytes(), 42), Ok(account.clone()));
+		let key = format!("{}#key-1", did);
+		assert_eq!(seneca_account_id::<AccountId32>(key.as_bytes(), 42), Ok(account.clone()));
+		assert_eq!(
+			seneca_account_id::<AccountId32>(did.as_bytes(), 0),
+			Err(DidError::InvalidAccount(Ss58Error::UnexpectedPrefix(42)))
+		);
+
+		let other = format!("did:example:{}", account.to_ss58check());
+		assert_eq!(
+			seneca_account_id::<AccountId32>(other.as_bytes(), 42),
+			Err(DidError::UnsupportedMethod)
+		);
+		assert_eq!(
+			seneca_account_id::<AccountId32>(b"did:seneca:a:b", 42),
+			Err(DidError::InvalidMethodSpecificId)
+		);
+		assert_eq!(
+			seneca_account_id::<AccountId32>(b"did:seneca:abc", 42),
+			Err(DidError::InvalidAccount(Ss58Error::BadLength))
+		);
+	}
+
+	#[test]
+	fn implicit_dids_authenticate_with_their_account() {
+		type Resolver = ImplicitResolver<sp_core::ConstU16<42>>;
+		let account = AccountId32::new([7u8; 32]);
+		let did = format!("did:seneca:{}", account.to_ss58check());
+		assert_eq!(
+			<Resolver as DidResolver<AccountId32>>::authentication_keys(did.as_bytes()),
+			Ok(vec![account])
+		);
+		assert_eq!(
+			<Resolver as DidResolver<AccountId32>>::authentication_keys(b"did:example:abc"),
+			Err(DidError::UnsupportedMethod)
+		);
+	}
+}
@@ -7,6 +7,7 @@ use sp_runtime::{
 	MultiSignature,
 };
 pub mod convert2accountid;
+pub mod did;
 
 /// Type used for expressing timestamp.
 pub type Moment = u64;
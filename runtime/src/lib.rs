@@ -31,6 +31,14 @@ use sp_std::prelude::*;
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 use frame_system::{EnsureRoot, EnsureWithSuccess};
+use pallet_credentials::{
+	credential::Credential,
+	types::{
+		BatchProof, CredentialStatusRecord, DisclosureError, Presentation,
+		PresentationVerification, UpdateRecord,
+	},
+	ClaimOpeningOf, CredentialOf, DidOf,
+};
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
 pub mod weights;
 // A few exports that help ease life for downstream crates.
@@ -117,10 +125,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 120,
+	spec_version: 121,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -542,12 +550,35 @@ impl pallet_preimage::Config for Runtime {
 	type ByteDeposit = PreimageByteDeposit;
 }
 
+impl pallet_did::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Moment = Moment;
+	type Timestamp = pallet_timestamp::Pallet<Runtime>;
+	type Currency = Balances;
+	type DidDepositBase = DidDepositBase;
+	type DidDepositPerByte = DidDepositPerByte;
+	type MaxDidLength = ConstU32<128>;
+	type MaxEntries = ConstU32<16>;
+	type MaxServiceLength = ConstU32<512>;
+	type WeightInfo = pallet_did::weights::DidRegistryWeightInfo<Runtime>;
+}
+
 impl pallet_schemas::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Public = <Signature as Verify>::Signer;
 	type Moment = Moment;
 	type Signature = Signature;
 	type Timestamp = pallet_timestamp::Pallet<Runtime>;
+	type DidResolver = Did;
+	type Currency = Balances;
+	type SchemaDepositBase = SchemaDepositBase;
+	type SchemaDepositPerByte = SchemaDepositPerByte;
+	type MaxNameLength = ConstU32<64>;
+	type MaxDidLength = ConstU32<128>;
+	type MaxClaims = ConstU32<32>;
+	type MaxValueLength = ConstU32<512>;
+	type MaxMetadataLength = ConstU32<2048>;
+	type MaxAuthorizedIssuers = ConstU32<64>;
 	type WeightInfo = pallet_schemas::weights::SchemaRegistryWeightInfo<Runtime>;
 	type SchemaId = u32;
 }
@@ -558,13 +589,36 @@ impl pallet_credentials::Config for Runtime {
 	type CredentialsWeightInfo = pallet_credentials::weights::CredentialRegistryWeightInfo<Runtime>;
 	type CredentialId = u32;
 	type SchemaCheck = Schemas;
+	type CredentialDepositBase = CredentialDepositBase;
+	type CredentialDepositPerByte = CredentialDepositPerByte;
+	type MaxContextLength = ConstU32<256>;
+	type OfferLifetime = CredentialOfferLifetime;
+	type MaxBatchSize = ConstU32<100>;
+	type MaxDelegatedSchemas = ConstU32<16>;
+	type MaxExpiriesPerBlock = ConstU32<64>;
 }
 
+/// A credential as stored by the credentials pallet.
+pub type StoredCredential = CredentialOf<Runtime>;
+/// A committed claim revealed by the holder of a credential.
+pub type StoredClaimOpening = ClaimOpeningOf<Runtime>;
+
 parameter_types! {
 	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
 	pub const DepositBase: Balance = deposit(1, 88);
 	// Additional storage item size of 32 bytes.
 	pub const DepositFactor: Balance = deposit(0, 32);
+	// The DID document plus its deposit entry, the document itself is charged per byte.
+	pub const DidDepositBase: Balance = deposit(2, 0);
+	pub const DidDepositPerByte: Balance = deposit(0, 1);
+	// The schema plus its deposit entry, the payload itself is charged per byte.
+	pub const SchemaDepositBase: Balance = deposit(2, 0);
+	pub const SchemaDepositPerByte: Balance = deposit(0, 1);
+	// The credential plus its status, deposit and four index entries.
+	pub const CredentialDepositBase: Balance = deposit(7, 0);
+	pub const CredentialDepositPerByte: Balance = deposit(0, 1);
+	// Holders have a week to accept a credential offered to them.
+	pub const CredentialOfferLifetime: BlockNumber = 7 * DAYS;
 }
 
 impl pallet_multisig::Config for Runtime {
@@ -606,6 +660,7 @@ construct_runtime!(
 		Multisig: pallet_multisig,
 		Schemas: pallet_schemas,
 		Credentials: pallet_credentials,
+		Did: pallet_did,
 	}
 );
 
@@ -637,6 +692,17 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
 /// Migrate from `Instance1Council` to the new pallet prefix `Council`
 
 
+/// Storage migrations applied on the next runtime upgrade.
+type Migrations = (
+	pallet_schemas::migrations::v1::MigrateToV1<Runtime>,
+	pallet_credentials::migrations::v1::MigrateToV1<Runtime>,
+	pallet_credentials::migrations::v2::MigrateToV2<Runtime>,
+	pallet_credentials::migrations::v3::MigrateToV3<Runtime>,
+	pallet_credentials::migrations::v4::MigrateToV4<Runtime>,
+	pallet_credentials::migrations::v5::MigrateToV5<Runtime>,
+	pallet_credentials::migrations::v6::MigrateToV6<Runtime>,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -644,6 +710,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -833,6 +900,77 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_credentials_runtime_api::CredentialsApi<
+		Block,
+		u32,
+		BlockNumber,
+		Moment,
+		StoredCredential,
+		StoredClaimOpening,
+		Signature,
+	> for Runtime {
+		fn credentials_by_schema(
+			schema_id: u32,
+			start: Option<u32>,
+			limit: u32,
+		) -> Vec<(u32, StoredCredential)> {
+			Credentials::get_credentials_by_schemaid(&schema_id, start, limit)
+		}
+		fn credentials_by_subject(
+			subject_id: Vec<u8>,
+			start: Option<u32>,
+			limit: u32,
+		) -> Vec<(u32, StoredCredential)> {
+			Credentials::get_credentials_by_subject(&subject_id, start, limit)
+		}
+		fn credentials_by_holder(
+			holder: Vec<u8>,
+			start: Option<u32>,
+			limit: u32,
+		) -> Vec<(u32, StoredCredential)> {
+			Credentials::get_credentials_by_holder(&holder, start, limit)
+		}
+		fn credentials_by_issuer(
+			issuer: Vec<u8>,
+			start: Option<u32>,
+			limit: u32,
+		) -> Vec<(u32, StoredCredential)> {
+			Credentials::get_credentials_by_creator(&issuer, start, limit)
+		}
+		fn credential_status(id: u32) -> Option<CredentialStatusRecord<BlockNumber, Moment>> {
+			Credentials::credential_status(&id)
+		}
+		fn is_credential_valid(id: u32) -> bool {
+			Credentials::is_credential_valid(&id)
+		}
+		fn next_credential_nonce(issuer: Vec<u8>) -> u64 {
+			DidOf::<Runtime>::try_from(issuer).map(Credentials::get_nonce).unwrap_or_default()
+		}
+		fn next_schema_nonce(creator: Vec<u8>) -> u64 {
+			DidOf::<Runtime>::try_from(creator).map(Schemas::get_nonce).unwrap_or_default()
+		}
+		fn credential_history(id: u32) -> Vec<(u32, UpdateRecord<BlockNumber, Moment>)> {
+			Credentials::credential_history(&id)
+		}
+		fn schema_history(schema_id: u32) -> Vec<(u32, UpdateRecord<BlockNumber, Moment>)> {
+			Schemas::schema_history(&schema_id)
+		}
+		fn verify_disclosure(
+			id: u32,
+			openings: Vec<StoredClaimOpening>,
+		) -> Result<(), DisclosureError> {
+			Credentials::verify_disclosure(&id, &openings)
+		}
+		fn verify_presentation(
+			presentation: Presentation<u32, Signature>,
+		) -> PresentationVerification<u32> {
+			Credentials::verify_presentation(&presentation)
+		}
+		fn batch_proof(id: u32) -> Option<BatchProof> {
+			Credentials::batch_proof(&id)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -850,6 +988,7 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, pallet_timestamp, Timestamp);
 			list_benchmark!(list, extra, pallet_schemas, Schemas);
 			list_benchmark!(list, extra, pallet_credentials, Credentials);
+			list_benchmark!(list, extra, pallet_did, Did);
 			list_benchmark!(list, extra, frame_benchmarking, BaselineBench::<Runtime>);
 			list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
 			list_benchmark!(list, extra, pallet_collective, Council);
@@ -881,6 +1020,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_schemas, Schemas);
 			add_benchmark!(params, batches, pallet_credentials, Credentials);
+			add_benchmark!(params, batches, pallet_did, Did);
 			add_benchmark!(params, batches, frame_benchmarking, BaselineBench::<Runtime>);
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_collective, Council);
@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+# Regenerates the weights of the chain's own pallets from their benchmarks. Run it on the
+# reference hardware the chain's weights are meant for, from the repository root.
+set -e
+
+echo "*** Building the node with runtime benchmarks"
+cargo build --profile production --features runtime-benchmarks
+
+# pallet directory and the name of the weight struct the runtime uses for it
+for target in did:DidRegistryWeightInfo schemas:SchemaRegistryWeightInfo \
+	credentials:CredentialRegistryWeightInfo; do
+	pallet=${target%%:*}
+	output=pallets/$pallet/src/weights.rs
+
+	echo "*** Benchmarking pallet_$pallet"
+	./target/production/zeno-node benchmark pallet \
+		--chain dev \
+		--execution=wasm \
+		--wasm-execution=compiled \
+		--pallet "pallet_$pallet" \
+		--extrinsic '*' \
+		--steps 50 \
+		--repeat 20 \
+		--template scripts/frame-weight-template.hbs \
+		--output "$output"
+	sed -i "s/WeightInfoStruct/${target#*:}/g" "$output"
+done